@@ -0,0 +1,146 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use crate::api::bilibili::Reply;
+
+use super::timeline::normalize;
+
+const MAX_TOP_LIKED: usize = 3;
+const MAX_PHRASES: usize = 5;
+const MAX_EMOTES: usize = 5;
+/// 超过这个长度的评论一般不会被原样重复，不参与统计
+const MAX_PHRASE_CHARS: usize = 20;
+
+/// 已加载评论的概览，显示在 AI 分析栏
+#[derive(Debug, Clone, Default)]
+pub struct CommentDigest {
+    /// 评论条数（包括置顶评论和已加载的楼中楼回复）
+    pub total: usize,
+    /// 参与评论的用户数
+    pub users: usize,
+    /// 获赞最多的一级评论
+    pub top_liked: Vec<Reply>,
+    /// 被原样重复最多的短评论及次数
+    pub phrases: Vec<(String, usize)>,
+    /// 使用最多的表情（`[doge]` 这样的文本）及次数
+    pub emotes: Vec<(String, usize)>,
+}
+
+impl CommentDigest {
+    /// 统计一级评论 `replies`，连同它们附带的楼中楼回复
+    pub fn build<'a>(replies: impl IntoIterator<Item = &'a Reply>) -> Self {
+        // 置顶评论也可能出现在普通评论里，按 rpid 去重
+        let mut seen = HashSet::new();
+        let roots: Vec<&Reply> = replies.into_iter().filter(|r| seen.insert(r.rpid)).collect();
+        let all: Vec<&Reply> = roots
+            .iter()
+            .flat_map(|r| std::iter::once(*r).chain(r.replies.iter().flatten()))
+            .collect();
+
+        let users = all.iter().map(|r| r.mid).collect::<HashSet<_>>().len();
+
+        let mut top_liked: Vec<Reply> = roots.iter().map(|r| (*r).clone()).collect();
+        top_liked.sort_by_key(|r| (Reverse(r.like), r.ctime));
+        top_liked.truncate(MAX_TOP_LIKED);
+
+        let phrases = top_counts(
+            all.iter()
+                .map(|r| normalize(&r.content.message))
+                .filter(|p| !p.is_empty() && p.chars().count() <= MAX_PHRASE_CHARS),
+            MAX_PHRASES,
+        );
+        let emotes = top_counts(all.iter().flat_map(|r| emotes(&r.content.message)), MAX_EMOTES);
+
+        Self { total: all.len(), users, top_liked, phrases, emotes }
+    }
+}
+
+/// 评论中的表情文本，例如 `[doge]`、`[笑哭]`
+fn emotes(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(']') else { break };
+        let name = &after[..end];
+        if !name.is_empty() && !name.contains('[') && name.chars().count() <= 10 {
+            found.push(format!("[{}]", name));
+            rest = &after[end + 1..];
+        } else {
+            rest = after;
+        }
+    }
+    found
+}
+
+/// 出现次数最多的若干项，只出现一次的不算；次数相同时按内容排序
+fn top_counts(items: impl Iterator<Item = String>, limit: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for item in items {
+        *counts.entry(item).or_default() += 1;
+    }
+    let mut top: Vec<(String, usize)> = counts.into_iter().filter(|(_, n)| *n > 1).collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top.truncate(limit);
+    top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::bilibili::{ReplyContent, ReplyMember};
+
+    fn reply(rpid: i64, mid: i64, like: i64, message: &str) -> Reply {
+        Reply {
+            rpid,
+            oid: 1,
+            mid,
+            root: 0,
+            parent: 0,
+            rcount: 0,
+            like,
+            ctime: rpid,
+            member: ReplyMember { mid: mid.to_string(), uname: format!("user{}", mid), avatar: String::new() },
+            content: ReplyContent { message: message.to_string() },
+            replies: None,
+        }
+    }
+
+    #[test]
+    fn finds_emotes() {
+        assert_eq!(emotes("好耶[doge][笑哭] [ 不完整"), ["[doge]", "[笑哭]"]);
+        assert_eq!(emotes("[[doge]]"), ["[doge]"]);
+        assert!(emotes("[] 没有表情 [这个括号里的内容太长了不是表情]").is_empty());
+    }
+
+    #[test]
+    fn counts_replies_and_sub_replies() {
+        let mut first = reply(1, 10, 5, "来了来了");
+        first.replies = Some(vec![reply(11, 20, 0, "来了来了"), reply(12, 10, 0, "哈哈哈哈哈[doge]")]);
+        let replies = [first, reply(2, 30, 50, "哈哈哈[doge]"), reply(3, 40, 8, "这期讲得很清楚")];
+
+        let digest = CommentDigest::build(&replies);
+        assert_eq!(digest.total, 5);
+        assert_eq!(digest.users, 4);
+        let liked: Vec<i64> = digest.top_liked.iter().map(|r| r.rpid).collect();
+        assert_eq!(liked, [2, 3, 1]);
+        assert_eq!(digest.phrases, [("哈哈哈[doge]".to_string(), 2), ("来了来了".to_string(), 2)]);
+        assert_eq!(digest.emotes, [("[doge]".to_string(), 2)]);
+    }
+
+    #[test]
+    fn pinned_reply_is_counted_once() {
+        let pinned = reply(1, 10, 100, "置顶");
+        let digest = CommentDigest::build([&pinned, &pinned, &reply(2, 20, 1, "第二")]);
+        assert_eq!(digest.total, 2);
+        let liked: Vec<i64> = digest.top_liked.iter().map(|r| r.rpid).collect();
+        assert_eq!(liked, [1, 2]);
+    }
+
+    #[test]
+    fn empty_digest() {
+        let digest = CommentDigest::build([]);
+        assert_eq!(digest.total, 0);
+        assert!(digest.top_liked.is_empty() && digest.phrases.is_empty() && digest.emotes.is_empty());
+    }
+}
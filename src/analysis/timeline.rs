@@ -0,0 +1,269 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::api::danmaku::{Danmaku, DanmakuMode};
+
+/// 时间轴大约分成多少段，时长很长的直播回放会相应加大每段的长度
+const TARGET_BUCKETS: f64 = 120.0;
+/// 每段最短 10 秒
+const MIN_BUCKET_SECONDS: f64 = 10.0;
+/// 超过平均密度多少个标准差才算高能时刻
+const PEAK_SIGMA: f64 = 1.5;
+/// 一个高能区间至少要有这么多条弹幕
+const MIN_PEAK_COUNT: usize = 5;
+const MAX_HIGHLIGHTS: usize = 8;
+const MAX_PHRASES: usize = 3;
+
+/// 弹幕密度时间轴
+#[derive(Debug, Clone, Default)]
+pub struct DanmakuTimeline {
+    /// 每段的长度（秒）
+    pub bucket_seconds: f64,
+    /// 每段的弹幕数
+    pub buckets: Vec<usize>,
+    /// 按时间排序的高能时刻
+    pub highlights: Vec<Highlight>,
+}
+
+/// 弹幕密度明显高于平均的一段时间
+#[derive(Debug, Clone)]
+pub struct Highlight {
+    pub start: f64,
+    pub end: f64,
+    /// 区间内弹幕最多的那一段的起始时间
+    pub peak: f64,
+    pub count: usize,
+    /// 区间内重复最多的弹幕及次数
+    pub phrases: Vec<(String, usize)>,
+}
+
+impl DanmakuTimeline {
+    /// 按时长分段统计弹幕密度并检测高能时刻，`duration` 为 0 时按最后一条弹幕计算
+    pub fn build(list: &[Danmaku], duration: i64) -> Self {
+        let last = list.iter().map(|d| d.time).fold(0.0, f64::max);
+        let duration = (duration as f64).max(last);
+        if list.is_empty() || duration <= 0.0 {
+            return Self::default();
+        }
+
+        let bucket_seconds = (duration / TARGET_BUCKETS).ceil().max(MIN_BUCKET_SECONDS);
+        let len = (duration / bucket_seconds).floor() as usize + 1;
+        let mut buckets = vec![0; len];
+        for danmaku in list {
+            let index = ((danmaku.time.max(0.0) / bucket_seconds) as usize).min(len - 1);
+            buckets[index] += 1;
+        }
+
+        let highlights = detect_highlights(&buckets, bucket_seconds)
+            .into_iter()
+            .map(|(start, end, peak, count)| Highlight {
+                start,
+                end,
+                peak,
+                count,
+                phrases: top_phrases(list.iter().filter(|d| d.time >= start && d.time < end)),
+            })
+            .collect();
+
+        Self { bucket_seconds, buckets, highlights }
+    }
+
+    /// 该段是否落在某个高能区间内
+    pub fn is_highlighted(&self, index: usize) -> bool {
+        let time = index as f64 * self.bucket_seconds;
+        self.highlights.iter().any(|h| time >= h.start && time < h.end)
+    }
+}
+
+/// 对相邻三段做平滑后，把超过 `均值 + PEAK_SIGMA × 标准差` 的连续段合并为一个区间，
+/// 返回弹幕数最多的若干个区间 `(开始, 结束, 峰值, 弹幕数)`
+fn detect_highlights(buckets: &[usize], bucket_seconds: f64) -> Vec<(f64, f64, f64, usize)> {
+    let smoothed: Vec<f64> = (0..buckets.len())
+        .map(|i| {
+            let window = &buckets[i.saturating_sub(1)..(i + 2).min(buckets.len())];
+            window.iter().sum::<usize>() as f64 / window.len() as f64
+        })
+        .collect();
+    let mean = smoothed.iter().sum::<f64>() / smoothed.len() as f64;
+    let variance = smoothed.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / smoothed.len() as f64;
+    let threshold = mean + PEAK_SIGMA * variance.sqrt();
+
+    let mut regions = Vec::new();
+    let mut i = 0;
+    while i < buckets.len() {
+        if smoothed[i] <= threshold {
+            i += 1;
+            continue;
+        }
+        let begin = i;
+        while i < buckets.len() && smoothed[i] > threshold {
+            i += 1;
+        }
+        let count: usize = buckets[begin..i].iter().sum();
+        if count < MIN_PEAK_COUNT {
+            continue;
+        }
+        let peak = (begin..i).max_by_key(|&j| buckets[j]).unwrap_or(begin);
+        regions.push((
+            begin as f64 * bucket_seconds,
+            i as f64 * bucket_seconds,
+            peak as f64 * bucket_seconds,
+            count,
+        ));
+    }
+
+    regions.sort_by_key(|r| Reverse(r.3));
+    regions.truncate(MAX_HIGHLIGHTS);
+    regions.sort_by(|a, b| a.0.total_cmp(&b.0));
+    regions
+}
+
+/// 统计重复最多的弹幕内容，只出现一次的不算
+fn top_phrases<'a>(list: impl Iterator<Item = &'a Danmaku>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for danmaku in list {
+        // 高级弹幕、代码弹幕的内容不是普通文本
+        if matches!(danmaku.mode, DanmakuMode::Advanced | DanmakuMode::Code | DanmakuMode::Bas) {
+            continue;
+        }
+        let phrase = normalize(&danmaku.text);
+        if !phrase.is_empty() {
+            *counts.entry(phrase).or_default() += 1;
+        }
+    }
+
+    let mut phrases: Vec<(String, usize)> = counts.into_iter().filter(|(_, n)| *n > 1).collect();
+    phrases.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    phrases.truncate(MAX_PHRASES);
+    phrases
+}
+
+/// 去掉首尾空白，统一大小写，并把连续重复的字符压缩到最多 3 个（“哈哈哈哈哈” → “哈哈哈”）
+pub(super) fn normalize(text: &str) -> String {
+    let mut out = String::new();
+    let mut prev = None;
+    let mut run = 0;
+    for c in text.trim().chars().flat_map(char::to_lowercase) {
+        if Some(c) == prev {
+            run += 1;
+        } else {
+            prev = Some(c);
+            run = 1;
+        }
+        if run <= 3 {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn danmaku(time: f64, text: &str, mode: DanmakuMode) -> Danmaku {
+        Danmaku {
+            id: 0,
+            time,
+            mode,
+            font_size: 25,
+            color: 0xffffff,
+            timestamp: 0,
+            pool: 0,
+            mid_hash: String::new(),
+            weight: 0,
+            text: text.to_string(),
+        }
+    }
+
+    /// `len` 段、每段 1 条弹幕，指定的段改成给定数量
+    fn buckets(len: usize, peaks: &[(usize, usize)]) -> Vec<usize> {
+        let mut buckets = vec![1; len];
+        for &(index, count) in peaks {
+            buckets[index] = count;
+        }
+        buckets
+    }
+
+    #[test]
+    fn single_peak() {
+        let regions = detect_highlights(&buckets(20, &[(10, 30)]), 10.0);
+        // 平滑后峰值两侧各有一段超过阈值
+        assert_eq!(regions, [(90.0, 120.0, 100.0, 32)]);
+    }
+
+    #[test]
+    fn flat_or_empty_density_has_no_highlights() {
+        assert!(detect_highlights(&[5; 30], 10.0).is_empty());
+        assert!(detect_highlights(&[0; 30], 10.0).is_empty());
+    }
+
+    #[test]
+    fn adjacent_peaks_are_merged() {
+        let regions = detect_highlights(&buckets(60, &[(8, 40), (10, 45)]), 10.0);
+        assert_eq!(regions, [(70.0, 120.0, 100.0, 88)]);
+    }
+
+    #[test]
+    fn distant_peaks_are_separate_and_sorted_by_time() {
+        let regions = detect_highlights(&buckets(60, &[(40, 50), (10, 30)]), 10.0);
+        let peaks: Vec<f64> = regions.iter().map(|r| r.2).collect();
+        assert_eq!(peaks, [100.0, 400.0]);
+        assert!(regions.iter().all(|r| r.0 < r.2 && r.2 < r.1));
+    }
+
+    #[test]
+    fn small_regions_are_dropped() {
+        // 超过阈值但弹幕太少
+        assert!(detect_highlights(&[0, 0, 0, 0, 3, 0, 0, 0, 0, 0], 10.0).is_empty());
+    }
+
+    #[test]
+    fn normalizes_phrases() {
+        assert_eq!(normalize("  哈哈哈哈哈哈 "), "哈哈哈");
+        assert_eq!(normalize("AWSL"), "awsl");
+        assert_eq!(normalize("AAAAb"), "aaab");
+        assert_eq!(normalize("前方高能"), "前方高能");
+        assert_eq!(normalize("   "), "");
+    }
+
+    #[test]
+    fn counts_repeated_phrases() {
+        let list = [
+            danmaku(1.0, "哈哈哈哈", DanmakuMode::Scroll),
+            danmaku(1.0, "哈哈哈", DanmakuMode::Top),
+            danmaku(1.0, "哈哈哈哈哈哈哈", DanmakuMode::Bottom),
+            danmaku(1.0, "草", DanmakuMode::Scroll),
+            danmaku(1.0, "草", DanmakuMode::Scroll),
+            danmaku(1.0, "awsl", DanmakuMode::Scroll),
+            danmaku(1.0, "AWSL", DanmakuMode::Scroll),
+            danmaku(1.0, "只出现一次", DanmakuMode::Scroll),
+            danmaku(1.0, "[0,0,\"1-1\",4.5]", DanmakuMode::Advanced),
+            danmaku(1.0, "[0,0,\"1-1\",4.5]", DanmakuMode::Advanced),
+            danmaku(1.0, "   ", DanmakuMode::Scroll),
+            danmaku(1.0, "   ", DanmakuMode::Scroll),
+        ];
+        assert_eq!(
+            top_phrases(list.iter()),
+            [("哈哈哈".to_string(), 3), ("awsl".to_string(), 2), ("草".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn builds_timeline_with_phrases() {
+        let mut list: Vec<Danmaku> = (0..60).map(|i| danmaku(i as f64 * 10.0 + 1.0, "普通", DanmakuMode::Scroll)).collect();
+        list.extend((0..30).map(|_| danmaku(301.0, "前方高能！", DanmakuMode::Scroll)));
+        let timeline = DanmakuTimeline::build(&list, 600);
+
+        assert_eq!(timeline.bucket_seconds, 10.0);
+        assert_eq!(timeline.buckets.len(), 61);
+        assert_eq!(timeline.highlights.len(), 1);
+        let highlight = &timeline.highlights[0];
+        assert_eq!(highlight.peak, 300.0);
+        assert_eq!(highlight.phrases[0], ("前方高能！".to_string(), 30));
+        assert!(timeline.is_highlighted(30));
+        assert!(!timeline.is_highlighted(0));
+
+        assert!(DanmakuTimeline::build(&[], 600).buckets.is_empty());
+    }
+}
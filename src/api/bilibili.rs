@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use anyhow::Result;
+
+use super::client::{BiliClient, SEARCH_REFERER, SPACE_REFERER};
+use super::error::Result;
 
 /// 合集元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +10,8 @@ pub struct SeasonMeta {
     pub name: String,
     pub total: i32,
     pub description: Option<String>,
+    #[serde(default)]
+    pub cover: String,
 }
 
 /// 系列元数据
@@ -17,6 +21,8 @@ pub struct SeriesMeta {
     pub name: String,
     pub total: i32,
     pub description: Option<String>,
+    #[serde(default)]
+    pub cover: String,
 }
 
 /// 合集数据
@@ -34,6 +40,9 @@ pub struct Series {
 /// 合集和系列列表
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemsLists {
+    /// 分页信息，`total` 为合集和系列的总数
+    #[serde(default)]
+    pub page: Option<PageInfo>,
     pub seasons_list: Option<Vec<Season>>,
     pub series_list: Option<Vec<Series>>,
 }
@@ -62,10 +71,13 @@ pub struct VideoArchive {
     pub pubdate: i64,
     pub duration: i64,
     pub stat: VideoStat,
+    /// 简介；合集和系列接口不返回，投稿列表才有
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// 视频统计数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VideoStat {
     pub view: i64,
     #[serde(default)]
@@ -101,15 +113,14 @@ pub struct PageInfo {
 
 /// 获取用户空间的合集和视频系列列表
 pub async fn fetch_space_collections(
+    client: &BiliClient,
     mid: &str,
-    cookie: &str,
     page_num: i32,
     page_size: i32,
 ) -> Result<SpaceCollectionsData> {
-    let url = "https://api.bilibili.com/x/polymer/web-space/seasons_series_list";
+    let url = client.api_url("/x/polymer/web-space/seasons_series_list");
     
-    let client = reqwest::Client::new();
-    let response = client
+    client
         .get(url)
         .query(&[
             ("mid", mid),
@@ -117,33 +128,23 @@ pub async fn fetch_space_collections(
             ("page_size", &page_size.to_string()),
             ("web_location", "333.1387"),
         ])
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .header("Cookie", cookie)
-        .header("Referer", "https://space.bilibili.com/")
-        .send()
-        .await?;
-    
-    let api_response: ApiResponse<SpaceCollectionsData> = response.json().await?;
-    
-    if api_response.code != 0 {
-        anyhow::bail!("API 返回错误: code={}, message={:?}", api_response.code, api_response.message);
-    }
-    
-    api_response.data.ok_or_else(|| anyhow::anyhow!("API 返回数据为空"))
+        .referer(SPACE_REFERER)
+        .wbi()
+        .data()
+        .await
 }
 
 /// 获取合集的视频列表（单页）
 pub async fn fetch_season_archives(
+    client: &BiliClient,
     mid: &str,
     season_id: &str,
-    cookie: &str,
     page_num: i32,
     page_size: i32,
 ) -> Result<SeasonArchivesData> {
-    let url = "https://api.bilibili.com/x/polymer/web-space/seasons_archives_list";
+    let url = client.api_url("/x/polymer/web-space/seasons_archives_list");
     
-    let client = reqwest::Client::new();
-    let response = client
+    client
         .get(url)
         .query(&[
             ("mid", mid),
@@ -153,34 +154,95 @@ pub async fn fetch_season_archives(
             ("page_size", &page_size.to_string()),
             ("web_location", "333.1387"),
         ])
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .header("Cookie", cookie)
-        .header("Referer", "https://space.bilibili.com/")
-        .send()
-        .await?;
-    
-    // 解析 JSON 响应
-    let api_response: ApiResponse<SeasonArchivesData> = response.json().await?;
-    
-    if api_response.code != 0 {
-        anyhow::bail!("API 返回错误: code={}, message={:?}", api_response.code, api_response.message);
-    }
-    
-    api_response.data.ok_or_else(|| anyhow::anyhow!("API 返回数据为空"))
+        .referer(SPACE_REFERER)
+        .wbi()
+        .data()
+        .await
 }
 
 /// 获取合集的所有视频列表（自动翻页）
 pub async fn fetch_all_season_archives(
+    client: &BiliClient,
     mid: &str,
     season_id: &str,
-    cookie: &str,
 ) -> Result<Vec<VideoArchive>> {
     let mut all_videos = Vec::new();
     let mut page_num = 1;
     let page_size = 30;
     
     loop {
-        let data = fetch_season_archives(mid, season_id, cookie, page_num, page_size).await?;
+        let data = fetch_season_archives(client, mid, season_id, page_num, page_size).await?;
+        
+        if data.archives.is_empty() {
+            break;
+        }
+        
+        let total = data.page.total;
+        all_videos.extend(data.archives);
+        
+        if all_videos.len() >= total as usize {
+            break;
+        }
+        
+        page_num += 1;
+    }
+    
+    Ok(all_videos)
+}
+
+/// 系列视频列表的分页信息（字段名与合集接口不同）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesPageInfo {
+    pub num: i32,
+    pub size: i32,
+    pub total: i32,
+}
+
+/// 系列视频列表响应数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesArchivesData {
+    #[serde(default)]
+    pub archives: Vec<VideoArchive>,
+    pub page: SeriesPageInfo,
+}
+
+/// 获取系列的视频列表（单页）
+pub async fn fetch_series_archives(
+    client: &BiliClient,
+    mid: &str,
+    series_id: &str,
+    page_num: i32,
+    page_size: i32,
+) -> Result<SeriesArchivesData> {
+    let url = client.api_url("/x/series/archives");
+    
+    client
+        .get(url)
+        .query(&[
+            ("mid", mid),
+            ("series_id", series_id),
+            ("only_normal", "true"),
+            ("sort", "desc"),
+            ("pn", &page_num.to_string()),
+            ("ps", &page_size.to_string()),
+        ])
+        .referer(SPACE_REFERER)
+        .data()
+        .await
+}
+
+/// 获取系列的所有视频列表（自动翻页）
+pub async fn fetch_all_series_archives(
+    client: &BiliClient,
+    mid: &str,
+    series_id: &str,
+) -> Result<Vec<VideoArchive>> {
+    let mut all_videos = Vec::new();
+    let mut page_num = 1;
+    let page_size = 30;
+    
+    loop {
+        let data = fetch_series_archives(client, mid, series_id, page_num, page_size).await?;
         
         if data.archives.is_empty() {
             break;
@@ -198,3 +260,960 @@ pub async fn fetch_all_season_archives(
     
     Ok(all_videos)
 }
+
+/// 用户空间里的一个视频集合：合集、系列或全部投稿
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Collection {
+    Season(i64),
+    Series(i64),
+    /// 全部投稿，`keyword` 为空时不过滤
+    Uploads { order: UploadOrder, keyword: String },
+}
+
+/// 获取合集或系列的所有视频
+pub async fn fetch_collection_archives(
+    client: &BiliClient,
+    mid: &str,
+    collection: Collection,
+) -> Result<Vec<VideoArchive>> {
+    match collection {
+        Collection::Season(id) => fetch_all_season_archives(client, mid, &id.to_string()).await,
+        Collection::Series(id) => fetch_all_series_archives(client, mid, &id.to_string()).await,
+        Collection::Uploads { order, keyword } => fetch_all_upload_videos(client, mid, order, &keyword).await,
+    }
+}
+
+/// 投稿列表排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UploadOrder {
+    /// 最新发布
+    #[default]
+    Pubdate,
+    /// 最多播放
+    Click,
+    /// 最多收藏
+    Stow,
+}
+
+impl UploadOrder {
+    pub const ALL: [UploadOrder; 3] = [UploadOrder::Pubdate, UploadOrder::Click, UploadOrder::Stow];
+
+    /// 投稿接口的 `order` 参数
+    fn param(self) -> &'static str {
+        match self {
+            UploadOrder::Pubdate => "pubdate",
+            UploadOrder::Click => "click",
+            UploadOrder::Stow => "stow",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UploadOrder::Pubdate => "最新发布",
+            UploadOrder::Click => "最多播放",
+            UploadOrder::Stow => "最多收藏",
+        }
+    }
+}
+
+/// 投稿列表中的一个视频（字段与合集接口不同）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadVideo {
+    pub aid: i64,
+    pub bvid: String,
+    pub title: String,
+    pub pic: String,
+    /// 发布时间
+    pub created: i64,
+    /// 时长，`mm:ss` 或 `h:mm:ss`
+    #[serde(default)]
+    pub length: String,
+    /// 播放量，可能是数字、数字字符串，部分视频返回字符串 `--`
+    #[serde(default)]
+    pub play: serde_json::Value,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl From<UploadVideo> for VideoArchive {
+    fn from(v: UploadVideo) -> Self {
+        let duration = v
+            .length
+            .split(':')
+            .try_fold(0i64, |acc, part| part.trim().parse::<i64>().ok().map(|n| acc * 60 + n))
+            .unwrap_or(0);
+        let view = v
+            .play
+            .as_i64()
+            .or_else(|| v.play.as_str().and_then(|s| s.trim().parse().ok()))
+            .unwrap_or(0);
+        let description = v.description.trim();
+        VideoArchive {
+            aid: v.aid,
+            bvid: v.bvid,
+            title: v.title,
+            pic: v.pic,
+            pubdate: v.created,
+            duration,
+            stat: VideoStat { view, ..VideoStat::default() },
+            description: (!description.is_empty()).then(|| description.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadList {
+    #[serde(default)]
+    pub vlist: Vec<UploadVideo>,
+}
+
+/// 投稿列表的分页信息，`count` 为符合条件的视频总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadPageInfo {
+    pub pn: i32,
+    pub ps: i32,
+    pub count: i32,
+}
+
+/// 投稿列表响应数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadVideosData {
+    pub list: UploadList,
+    pub page: UploadPageInfo,
+}
+
+/// 获取用户的投稿视频（单页），`keyword` 为空时不过滤
+pub async fn fetch_upload_videos(
+    client: &BiliClient,
+    mid: &str,
+    order: UploadOrder,
+    keyword: &str,
+    page_num: i32,
+    page_size: i32,
+) -> Result<UploadVideosData> {
+    client
+        .get(client.api_url("/x/space/wbi/arc/search"))
+        .query(&[
+            ("mid", mid),
+            ("order", order.param()),
+            ("keyword", keyword),
+            ("tid", "0"),
+            ("pn", &page_num.to_string()),
+            ("ps", &page_size.to_string()),
+            ("platform", "web"),
+            ("web_location", "1550101"),
+        ])
+        .referer(SPACE_REFERER)
+        .wbi()
+        .data()
+        .await
+}
+
+/// 获取用户的全部投稿视频（自动翻页）
+pub async fn fetch_all_upload_videos(
+    client: &BiliClient,
+    mid: &str,
+    order: UploadOrder,
+    keyword: &str,
+) -> Result<Vec<VideoArchive>> {
+    let mut all_videos = Vec::new();
+    let mut page_num = 1;
+    let page_size = 30;
+    
+    loop {
+        let data = fetch_upload_videos(client, mid, order, keyword, page_num, page_size).await?;
+        
+        if data.list.vlist.is_empty() {
+            break;
+        }
+        
+        let total = data.page.count;
+        all_videos.extend(data.list.vlist.into_iter().map(VideoArchive::from));
+        
+        if all_videos.len() >= total as usize {
+            break;
+        }
+        
+        page_num += 1;
+    }
+    
+    Ok(all_videos)
+}
+
+/// 用户搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSearchItem {
+    pub mid: i64,
+    pub uname: String,
+    #[serde(default)]
+    pub upic: String,
+    #[serde(default)]
+    pub usign: String,
+    #[serde(default)]
+    pub fans: i64,
+    #[serde(default)]
+    pub videos: i64,
+}
+
+/// 用户搜索响应数据，没有结果时不返回 `result`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSearchData {
+    #[serde(default)]
+    pub result: Vec<UserSearchItem>,
+}
+
+/// 按昵称搜索用户（第一页）
+pub async fn search_users(client: &BiliClient, keyword: &str) -> Result<Vec<UserSearchItem>> {
+    let data: UserSearchData = client
+        .get(client.api_url("/x/web-interface/wbi/search/type"))
+        .query(&[
+            ("search_type", "bi_user"),
+            ("keyword", keyword),
+            ("page", "1"),
+        ])
+        .referer(SEARCH_REFERER)
+        .wbi()
+        .data()
+        .await?;
+    Ok(data.result)
+}
+
+/// UP 主信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoOwner {
+    pub mid: i64,
+    pub name: String,
+    #[serde(default)]
+    pub face: String,
+}
+
+/// 视频分P，字幕/弹幕/播放地址接口都需要 cid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoPage {
+    pub cid: i64,
+    pub page: i32,
+    pub part: String,
+    pub duration: i64,
+}
+
+/// 视频标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoTag {
+    pub tag_id: i64,
+    pub tag_name: String,
+}
+
+/// 视频详情
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoDetail {
+    pub aid: i64,
+    pub bvid: String,
+    pub cid: i64,
+    pub title: String,
+    pub pic: String,
+    #[serde(default)]
+    pub desc: String,
+    pub pubdate: i64,
+    pub duration: i64,
+    pub owner: VideoOwner,
+    pub stat: VideoStat,
+    #[serde(default)]
+    pub pages: Vec<VideoPage>,
+    #[serde(default)]
+    pub tags: Vec<VideoTag>,
+}
+
+/// 获取视频详情（简介、分P、UP 主、统计数据和标签）
+pub async fn fetch_video_detail(client: &BiliClient, bvid: &str) -> Result<VideoDetail> {
+    let view = client
+        .get(client.api_url("/x/web-interface/view"))
+        .query(&[("bvid", bvid)])
+        .data::<VideoDetail>();
+    let tags = client
+        .get(client.api_url("/x/tag/archive/tags"))
+        .query(&[("bvid", bvid)])
+        .data::<Vec<VideoTag>>();
+
+    let (view, tags) = futures::join!(view, tags);
+    let mut detail = view?;
+    // 标签只是附加信息，获取失败不影响详情
+    match tags {
+        Ok(tags) => detail.tags = tags,
+        Err(e) => warn!("⚠️ 获取视频标签失败: {} - {}", bvid, e),
+    }
+    Ok(detail)
+}
+
+/// 字幕轨道（UP 主上传的 CC 字幕或 AI 生成字幕）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    pub id: i64,
+    /// 语言代码，AI 字幕以 `ai-` 开头，例如 `ai-zh`
+    pub lan: String,
+    /// 语言名称，例如 “中文（自动生成）”
+    pub lan_doc: String,
+    pub subtitle_url: String,
+    /// 0: CC 字幕，1: AI 字幕
+    #[serde(rename = "type", default)]
+    pub kind: i32,
+}
+
+impl SubtitleTrack {
+    pub fn is_ai(&self) -> bool {
+        self.kind == 1 || self.lan.starts_with("ai-")
+    }
+}
+
+/// 一行字幕，时间单位为秒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleLine {
+    pub from: f64,
+    pub to: f64,
+    pub content: String,
+}
+
+/// 获取视频某个分P可用的字幕轨道
+pub async fn fetch_subtitle_tracks(client: &BiliClient, bvid: &str, cid: i64) -> Result<Vec<SubtitleTrack>> {
+    #[derive(Deserialize)]
+    struct SubtitleInfo {
+        #[serde(default)]
+        subtitles: Vec<SubtitleTrack>,
+    }
+    #[derive(Deserialize)]
+    struct PlayerData {
+        subtitle: Option<SubtitleInfo>,
+    }
+
+    let data: PlayerData = client
+        .get(client.api_url("/x/player/wbi/v2"))
+        .query(&[("bvid", bvid), ("cid", &cid.to_string())])
+        .wbi()
+        .data()
+        .await?;
+
+    // 未登录时 AI 字幕的地址为空，无法下载
+    Ok(data
+        .subtitle
+        .map(|s| s.subtitles)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| !t.subtitle_url.is_empty())
+        .collect())
+}
+
+/// 字幕文件（不是 B 站通用的 code/data 响应）
+#[derive(Deserialize)]
+struct SubtitleBody {
+    #[serde(default)]
+    body: Vec<SubtitleLine>,
+}
+
+/// 字幕地址是省略协议的 //aisubtitle.hdslb.com/... 形式，统一补成 https
+fn subtitle_url(raw: &str) -> String {
+    if raw.starts_with("//") {
+        format!("https:{}", raw)
+    } else {
+        raw.replace("http://", "https://")
+    }
+}
+
+/// 下载字幕轨道的 JSON 内容
+pub async fn fetch_subtitle_lines(client: &BiliClient, track: &SubtitleTrack) -> Result<Vec<SubtitleLine>> {
+    let body: SubtitleBody = client.get(subtitle_url(&track.subtitle_url)).json().await?;
+    Ok(body.body)
+}
+
+/// 评论排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplySort {
+    Hot,
+    Time,
+}
+
+impl ReplySort {
+    /// 评论接口的 `mode` 参数
+    fn mode(&self) -> &'static str {
+        match self {
+            ReplySort::Hot => "3",
+            ReplySort::Time => "2",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReplySort::Hot => "最热",
+            ReplySort::Time => "最新",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyMember {
+    /// 评论接口中的 mid 是字符串
+    pub mid: String,
+    pub uname: String,
+    pub avatar: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyContent {
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reply {
+    pub rpid: i64,
+    pub oid: i64,
+    pub mid: i64,
+    /// 根评论 rpid，一级评论为 0
+    pub root: i64,
+    pub parent: i64,
+    /// 回复数
+    #[serde(default)]
+    pub rcount: i64,
+    #[serde(default)]
+    pub like: i64,
+    pub ctime: i64,
+    pub member: ReplyMember,
+    pub content: ReplyContent,
+    /// 一级评论附带的前几条楼中楼回复，没有时为 null
+    #[serde(default)]
+    pub replies: Option<Vec<Reply>>,
+}
+
+/// 一页一级评论
+#[derive(Debug, Clone)]
+pub struct ReplyPage {
+    pub replies: Vec<Reply>,
+    /// 置顶评论（只在第一页返回）
+    pub top_replies: Vec<Reply>,
+    /// 下一页的游标，传给下一次 `fetch_replies`
+    pub next_offset: Option<String>,
+    pub is_end: bool,
+}
+
+/// 获取视频（`oid` 为 aid）的一级评论，`offset` 为上一页返回的游标，第一页传 `None`
+pub async fn fetch_replies(
+    client: &BiliClient,
+    oid: i64,
+    sort: ReplySort,
+    offset: Option<&str>,
+) -> Result<ReplyPage> {
+    #[derive(Deserialize)]
+    struct PaginationReply {
+        next_offset: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct Cursor {
+        #[serde(default)]
+        is_end: bool,
+        pagination_reply: Option<PaginationReply>,
+    }
+    #[derive(Deserialize)]
+    struct MainData {
+        cursor: Cursor,
+        replies: Option<Vec<Reply>>,
+        top_replies: Option<Vec<Reply>>,
+    }
+
+    let pagination = serde_json::json!({ "offset": offset.unwrap_or("") }).to_string();
+    let data: MainData = client
+        .get(client.api_url("/x/v2/reply/wbi/main"))
+        .query(&[
+            ("oid", &oid.to_string()),
+            ("type", "1"),
+            ("mode", sort.mode()),
+            ("pagination_str", &pagination),
+            ("plat", "1"),
+        ])
+        .wbi()
+        .data()
+        .await?;
+
+    let next_offset = data
+        .cursor
+        .pagination_reply
+        .and_then(|p| p.next_offset)
+        .filter(|o| !o.is_empty());
+    Ok(ReplyPage {
+        replies: data.replies.unwrap_or_default(),
+        top_replies: data.top_replies.unwrap_or_default(),
+        is_end: data.cursor.is_end || next_offset.is_none(),
+        next_offset,
+    })
+}
+
+/// 楼中楼每页条数
+const SUB_REPLY_PAGE_SIZE: i64 = 20;
+
+/// 一页楼中楼回复
+#[derive(Debug, Clone)]
+pub struct SubReplyPage {
+    pub replies: Vec<Reply>,
+    pub page: i32,
+    /// 回复总数
+    pub count: i64,
+    pub is_end: bool,
+}
+
+/// 获取根评论 `root` 下的第 `page` 页回复（从 1 开始）
+pub async fn fetch_sub_replies(client: &BiliClient, oid: i64, root: i64, page: i32) -> Result<SubReplyPage> {
+    #[derive(Deserialize)]
+    struct Page {
+        num: i32,
+        count: i64,
+    }
+    #[derive(Deserialize)]
+    struct ReplyData {
+        page: Page,
+        replies: Option<Vec<Reply>>,
+    }
+
+    let data: ReplyData = client
+        .get(client.api_url("/x/v2/reply/reply"))
+        .query(&[
+            ("oid", &oid.to_string()),
+            ("type", "1"),
+            ("root", &root.to_string()),
+            ("pn", &page.to_string()),
+            ("ps", &SUB_REPLY_PAGE_SIZE.to_string()),
+        ])
+        .data()
+        .await?;
+
+    let replies = data.replies.unwrap_or_default();
+    Ok(SubReplyPage {
+        is_end: replies.is_empty() || data.page.num as i64 * SUB_REPLY_PAGE_SIZE >= data.page.count,
+        replies,
+        page: data.page.num,
+        count: data.page.count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use crate::api::client::BiliClientConfig;
+
+    const NAV_BODY: &str = r#"{"code":0,"data":{"wbi_img":{"img_url":"https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png","sub_url":"https://i0.hdslb.com/bfs/wbi/4932caff0ff746eab6f01bf08b70ac45.png"}}}"#;
+
+    /// 按请求路径返回响应体的 mock 服务器，记录收到的路径（含查询参数）；nav 接口固定返回 WBI 密钥
+    async fn serve(route: impl Fn(&str) -> String + Send + Sync + 'static) -> (BiliClient, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let route = Arc::new(route);
+
+        let log = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { break };
+                let (log, route) = (log.clone(), route.clone());
+                tokio::spawn(async move {
+                    let mut data = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !data.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        data.extend_from_slice(&buf[..n]);
+                    }
+                    let request = String::from_utf8_lossy(&data).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+                    let body = if path.starts_with("/x/web-interface/nav") {
+                        NAV_BODY.to_string()
+                    } else {
+                        log.lock().unwrap().push(path.clone());
+                        route(&path)
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        (BiliClient::new(BiliClientConfig::default().with_base_url(base)), requests)
+    }
+
+    /// 取出记录的请求路径里某个查询参数解码后的值
+    fn query(path: &str, key: &str) -> Option<String> {
+        let url = reqwest::Url::parse(&format!("http://mock{}", path)).ok()?;
+        url.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.into_owned())
+    }
+
+    fn upload(length: &str, play: serde_json::Value, description: &str) -> VideoArchive {
+        let video: UploadVideo = serde_json::from_value(serde_json::json!({
+            "aid": 170001,
+            "bvid": "BV17x411w7KC",
+            "title": "标题",
+            "pic": "https://i0.hdslb.com/bfs/archive/a.jpg",
+            "created": 1700000000,
+            "length": length,
+            "play": play,
+            "description": description,
+        }))
+        .unwrap();
+        video.into()
+    }
+
+    #[test]
+    fn parses_upload_length() {
+        assert_eq!(upload("03:25", 1.into(), "").duration, 205);
+        assert_eq!(upload("1:02:03", 1.into(), "").duration, 3723);
+        assert_eq!(upload("00:07", 1.into(), "").duration, 7);
+        assert_eq!(upload("", 1.into(), "").duration, 0);
+        assert_eq!(upload("--", 1.into(), "").duration, 0);
+    }
+
+    #[test]
+    fn parses_upload_play_count() {
+        assert_eq!(upload("00:01", 12345.into(), "").stat.view, 12345);
+        assert_eq!(upload("00:01", "678".into(), "").stat.view, 678);
+        assert_eq!(upload("00:01", "--".into(), "").stat.view, 0);
+        assert_eq!(upload("00:01", serde_json::Value::Null, "").stat.view, 0);
+    }
+
+    #[test]
+    fn keeps_upload_description() {
+        let video = upload("00:01", 1.into(), " 视频简介 ");
+        assert_eq!(video.description.as_deref(), Some("视频简介"));
+        assert_eq!(video.pubdate, 1700000000);
+        assert!(upload("00:01", 1.into(), "").description.is_none());
+
+        // 合集接口没有简介字段
+        let archive: VideoArchive = serde_json::from_value(serde_json::json!({
+            "aid": 1, "bvid": "BV1xx411c7mQ", "title": "", "pic": "", "pubdate": 0, "duration": 10,
+            "stat": { "view": 5 },
+        }))
+        .unwrap();
+        assert!(archive.description.is_none());
+    }
+
+    /// 录制的 `/x/v2/reply/wbi/main` 第一页响应（删减了无关字段）
+    const REPLY_MAIN_BODY: &str = r#"{"code":0,"message":"0","ttl":1,"data":{
+        "cursor":{"is_begin":true,"prev":0,"next":2,"is_end":false,"mode":3,"mode_text":"热门评论",
+            "all_count":1532,"support_mode":[1,2,3],"name":"热门评论",
+            "pagination_reply":{"next_offset":"{\"type\":1,\"direction\":1,\"session_id\":\"1771234567890\",\"data\":{}}"},
+            "session_id":"1771234567890"},
+        "replies":[{"rpid":251234567890,"oid":1155558888,"type":1,"mid":12345678,"root":0,"parent":0,
+            "dialog":0,"count":2,"rcount":2,"state":0,"fansgrade":0,"attr":0,"ctime":1760000000,"like":318,
+            "action":0,"member":{"mid":"12345678","uname":"路人甲","sex":"保密","sign":"",
+                "avatar":"https://i0.hdslb.com/bfs/face/a.jpg","level_info":{"current_level":5}},
+            "content":{"message":"第一条评论[doge]","members":[],"emote":{}},
+            "replies":[{"rpid":251234567999,"oid":1155558888,"type":1,"mid":87654321,"root":251234567890,
+                "parent":251234567890,"rcount":0,"ctime":1760000100,"like":12,
+                "member":{"mid":"87654321","uname":"路人乙","avatar":"https://i0.hdslb.com/bfs/face/b.jpg"},
+                "content":{"message":"回复 @路人甲 :同意"},"replies":null}]},
+            {"rpid":251234560000,"oid":1155558888,"type":1,"mid":23456789,"root":0,"parent":0,
+            "rcount":0,"ctime":1760000200,"like":5,
+            "member":{"mid":"23456789","uname":"路人丙","avatar":"https://i0.hdslb.com/bfs/face/c.jpg"},
+            "content":{"message":"第二条"},"replies":null}],
+        "top_replies":[{"rpid":251200000000,"oid":1155558888,"type":1,"mid":99999,"root":0,"parent":0,
+            "rcount":0,"ctime":1759990000,"like":1024,
+            "member":{"mid":"99999","uname":"UP主","avatar":"https://i0.hdslb.com/bfs/face/up.jpg"},
+            "content":{"message":"置顶"},"replies":[]}],
+        "upper":{"mid":99999},"config":{"showtopic":1}}}"#;
+
+    /// 评论第一页的响应，替换游标部分和置顶评论
+    fn reply_main(cursor: &str, top_replies: &str) -> String {
+        let mut data: serde_json::Value = serde_json::from_str(REPLY_MAIN_BODY).unwrap();
+        data["data"]["cursor"] = serde_json::from_str(cursor).unwrap();
+        data["data"]["top_replies"] = serde_json::from_str(top_replies).unwrap();
+        data.to_string()
+    }
+
+    #[tokio::test]
+    async fn parses_reply_page() {
+        let (client, requests) = serve(|_| REPLY_MAIN_BODY.to_string()).await;
+
+        let page = fetch_replies(&client, 1155558888, ReplySort::Hot, None).await.unwrap();
+        assert_eq!(page.replies.len(), 2);
+        assert_eq!(page.replies[0].member.uname, "路人甲");
+        assert_eq!(page.replies[0].member.mid, "12345678");
+        assert_eq!(page.replies[0].rcount, 2);
+        let nested = page.replies[0].replies.as_ref().unwrap();
+        assert_eq!(nested[0].root, 251234567890);
+        assert_eq!(nested[0].content.message, "回复 @路人甲 :同意");
+        assert!(page.replies[1].replies.is_none());
+        assert_eq!(page.top_replies.len(), 1);
+        assert_eq!(page.top_replies[0].content.message, "置顶");
+        assert!(!page.is_end);
+        let offset = page.next_offset.unwrap();
+        assert_eq!(offset, r#"{"type":1,"direction":1,"session_id":"1771234567890","data":{}}"#);
+
+        fetch_replies(&client, 1155558888, ReplySort::Time, Some(&offset)).await.unwrap();
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|r| r.starts_with("/x/v2/reply/wbi/main?")));
+        assert_eq!(query(&requests[0], "pagination_str").as_deref(), Some(r#"{"offset":""}"#));
+        assert_eq!(query(&requests[0], "mode").as_deref(), Some("3"));
+        assert_eq!(query(&requests[0], "oid").as_deref(), Some("1155558888"));
+        assert!(query(&requests[0], "w_rid").is_some());
+        // 游标本身是 JSON 字符串，整体再编码进 pagination_str
+        let pagination: serde_json::Value = serde_json::from_str(&query(&requests[1], "pagination_str").unwrap()).unwrap();
+        assert_eq!(pagination["offset"].as_str(), Some(offset.as_str()));
+        assert_eq!(query(&requests[1], "mode").as_deref(), Some("2"));
+        assert!(requests[1].contains("pagination_str=%7B%22offset%22%3A%22%7B%5C%22type%5C%22"));
+    }
+
+    #[tokio::test]
+    async fn reply_cursor_marks_last_page() {
+        // 最后一页：is_end 为 true 且游标为空，没有置顶评论
+        let (client, _) = serve(|_| reply_main(r#"{"is_end":true,"pagination_reply":{"next_offset":""}}"#, "null")).await;
+        let page = fetch_replies(&client, 1, ReplySort::Hot, Some("x")).await.unwrap();
+        assert!(page.is_end);
+        assert!(page.next_offset.is_none());
+        assert!(page.top_replies.is_empty());
+
+        // 服务端没标记结束，但游标为空也无法继续翻页
+        let (client, _) = serve(|_| reply_main(r#"{"is_end":false,"pagination_reply":{"next_offset":""}}"#, "null")).await;
+        let page = fetch_replies(&client, 1, ReplySort::Hot, None).await.unwrap();
+        assert!(page.is_end);
+        assert!(page.next_offset.is_none());
+
+        // 没有 pagination_reply 字段
+        let (client, _) = serve(|_| reply_main(r#"{"is_end":false}"#, "[]")).await;
+        assert!(fetch_replies(&client, 1, ReplySort::Hot, None).await.unwrap().is_end);
+
+        // 游标还在，但服务端已经标记结束
+        let (client, _) = serve(|_| reply_main(r#"{"is_end":true,"pagination_reply":{"next_offset":"abc"}}"#, "null")).await;
+        let page = fetch_replies(&client, 1, ReplySort::Hot, None).await.unwrap();
+        assert!(page.is_end);
+        assert_eq!(page.next_offset.as_deref(), Some("abc"));
+
+        // 评论区为空时 replies 为 null
+        let (client, _) = serve(|_| r#"{"code":0,"data":{"cursor":{"is_end":true},"replies":null,"top_replies":null}}"#.to_string()).await;
+        let page = fetch_replies(&client, 1, ReplySort::Time, None).await.unwrap();
+        assert!(page.replies.is_empty() && page.top_replies.is_empty() && page.is_end);
+    }
+
+    #[tokio::test]
+    async fn sub_reply_pages_end_at_count() {
+        let (client, requests) = serve(|path| {
+            let page = query(path, "pn").unwrap();
+            let reply = r#"{"rpid":2,"oid":1,"mid":3,"root":1,"parent":1,"ctime":1760000000,
+                "member":{"mid":"3","uname":"路人","avatar":""},"content":{"message":"回复"}}"#;
+            let replies = match page.as_str() {
+                "1" => [reply; 20].join(","),
+                "2" => [reply; 5].join(","),
+                _ => String::new(),
+            };
+            let replies = if replies.is_empty() { "null".to_string() } else { format!("[{}]", replies) };
+            format!(r#"{{"code":0,"data":{{"page":{{"num":{},"size":20,"count":25}},"replies":{}}}}}"#, page, replies)
+        })
+        .await;
+
+        let first = fetch_sub_replies(&client, 1, 1, 1).await.unwrap();
+        assert_eq!((first.replies.len(), first.page, first.count, first.is_end), (20, 1, 25, false));
+        let second = fetch_sub_replies(&client, 1, 1, 2).await.unwrap();
+        assert_eq!((second.replies.len(), second.is_end), (5, true));
+        // 回复被删掉后总数偏大，空页也要结束
+        let third = fetch_sub_replies(&client, 1, 1, 3).await.unwrap();
+        assert!(third.replies.is_empty() && third.is_end);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("/x/v2/reply/reply?"));
+        assert_eq!(query(&requests[0], "root").as_deref(), Some("1"));
+        assert_eq!(query(&requests[0], "ps").as_deref(), Some("20"));
+    }
+
+    /// 系列接口的一页响应：第 `page` 页，`count` 个视频，`total` 为服务端声称的总数
+    fn series_page(page: i32, count: i32, total: i32) -> String {
+        let archives: Vec<_> = (0..count)
+            .map(|i| {
+                let aid = (page - 1) * 30 + i + 1;
+                serde_json::json!({
+                    "aid": aid, "bvid": format!("BV{}", aid), "title": format!("视频{}", aid),
+                    "pic": "http://i0.hdslb.com/bfs/archive/a.jpg", "pubdate": 1700000000, "ctime": 1700000000,
+                    "duration": 60, "state": 0, "interactive_video": false,
+                    "stat": { "view": aid * 10, "vt": 0 },
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "code": 0, "message": "0", "ttl": 1,
+            "data": { "aids": [], "page": { "num": page, "size": 30, "total": total }, "archives": archives },
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn fetches_all_series_pages_until_total() {
+        let (client, requests) = serve(|path| {
+            let page: i32 = query(path, "pn").unwrap().parse().unwrap();
+            series_page(page, if page < 3 { 30 } else { 5 }, 65)
+        })
+        .await;
+
+        let videos = fetch_all_series_archives(&client, "12345", "678").await.unwrap();
+        assert_eq!(videos.len(), 65);
+        assert_eq!(videos[0].bvid, "BV1");
+        assert_eq!(videos[64].stat.view, 650);
+        assert!(videos[0].description.is_none());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("/x/series/archives?"));
+        assert_eq!(query(&requests[0], "mid").as_deref(), Some("12345"));
+        assert_eq!(query(&requests[0], "series_id").as_deref(), Some("678"));
+        assert_eq!(query(&requests[0], "ps").as_deref(), Some("30"));
+        let pages: Vec<_> = requests.iter().filter_map(|r| query(r, "pn")).collect();
+        assert_eq!(pages, ["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn stops_series_paging_on_empty_page() {
+        // 总数包含已失效的视频，第二页就没有数据了；空页时接口直接省略 archives
+        let (client, requests) = serve(|path| match query(path, "pn").as_deref() {
+            Some("1") => series_page(1, 30, 100),
+            _ => r#"{"code":0,"data":{"aids":[],"page":{"num":2,"size":30,"total":100}}}"#.to_string(),
+        })
+        .await;
+
+        let videos = fetch_all_series_archives(&client, "1", "2").await.unwrap();
+        assert_eq!(videos.len(), 30);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // 总数正好是一页时不再请求下一页
+        let (client, requests) = serve(|_| series_page(1, 30, 30)).await;
+        assert_eq!(fetch_all_series_archives(&client, "1", "2").await.unwrap().len(), 30);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    /// 录制的 `/x/web-interface/view` 响应（删减了无关字段）
+    const VIEW_BODY: &str = r#"{"code":0,"message":"0","ttl":1,"data":{
+        "bvid":"BV1GJ411x7h7","aid":80433022,"videos":2,"tid":172,"tname":"手机游戏","copyright":1,
+        "pic":"http://i0.hdslb.com/bfs/archive/a.jpg","title":"测试视频","pubdate":1577548800,"ctime":1577548800,
+        "desc":"简介\n第二行","duration":213,
+        "owner":{"mid":99999,"name":"UP主","face":"https://i0.hdslb.com/bfs/face/up.jpg"},
+        "stat":{"aid":80433022,"view":123456,"danmaku":789,"reply":321,"favorite":111,"coin":222,"share":33,"like":4444,"vt":0},
+        "cid":137649199,
+        "pages":[{"cid":137649199,"page":1,"from":"vupload","part":"上","duration":100,"dimension":{"width":1920,"height":1080,"rotate":0}},
+            {"cid":137649200,"page":2,"from":"vupload","part":"下","duration":113}]}}"#;
+
+    #[tokio::test]
+    async fn video_detail_keeps_tags_optional() {
+        let (client, requests) = serve(|path| {
+            if path.starts_with("/x/web-interface/view") {
+                VIEW_BODY.to_string()
+            } else {
+                r#"{"code":0,"message":"0","data":[{"tag_id":2512,"tag_name":"手游","music_id":""},{"tag_id":8552,"tag_name":"测试"}]}"#.to_string()
+            }
+        })
+        .await;
+
+        let detail = fetch_video_detail(&client, "BV1GJ411x7h7").await.unwrap();
+        assert_eq!((detail.aid, detail.cid, detail.duration), (80433022, 137649199, 213));
+        assert_eq!(detail.desc, "简介\n第二行");
+        assert_eq!(detail.owner.name, "UP主");
+        assert_eq!((detail.stat.view, detail.stat.like), (123456, Some(4444)));
+        assert_eq!(detail.pages.iter().map(|p| p.part.as_str()).collect::<Vec<_>>(), ["上", "下"]);
+        assert_eq!(detail.tags.iter().map(|t| t.tag_name.as_str()).collect::<Vec<_>>(), ["手游", "测试"]);
+        let requests = requests.lock().unwrap().clone();
+        assert!(requests.iter().all(|r| query(r, "bvid").as_deref() == Some("BV1GJ411x7h7")));
+        assert!(requests.iter().any(|r| r.starts_with("/x/tag/archive/tags?")));
+
+        // 标签接口报错时只记一条警告，详情照常返回
+        let (client, _) = serve(|path| {
+            if path.starts_with("/x/web-interface/view") {
+                VIEW_BODY.to_string()
+            } else {
+                r#"{"code":16006,"message":"稿件不存在"}"#.to_string()
+            }
+        })
+        .await;
+        crate::utils::logging::capture::start();
+        let detail = fetch_video_detail(&client, "BV1GJ411x7h7").await.unwrap();
+        let output = crate::utils::logging::capture::finish();
+        assert_eq!(detail.title, "测试视频");
+        assert!(detail.tags.is_empty());
+        assert!(output.contains("获取视频标签失败: BV1GJ411x7h7"), "{output}");
+
+        // 详情接口本身失败时照常报错
+        let (client, _) = serve(|_| r#"{"code":62002,"message":"稿件不可见"}"#.to_string()).await;
+        assert!(matches!(
+            fetch_video_detail(&client, "BV1GJ411x7h7").await,
+            Err(crate::api::error::BiliError::NotFound { code: 62002, .. })
+        ));
+    }
+
+    /// 录制的 `/x/player/wbi/v2` 响应（删减了无关字段），未登录时 AI 字幕没有地址
+    const PLAYER_BODY: &str = r#"{"code":0,"message":"0","ttl":1,"data":{"aid":80433022,"bvid":"BV1GJ411x7h7",
+        "cid":137649199,"login_mid":0,"online_count":12,
+        "subtitle":{"allow_submit":false,"lan":"","lan_doc":"","subtitles":[
+            {"id":1145141919810,"lan":"zh-CN","lan_doc":"中文（中国）","is_lock":false,
+                "subtitle_url":"//i0.hdslb.com/bfs/subtitle/abc.json","type":0,"id_str":"1145141919810","ai_type":0,"ai_status":0},
+            {"id":1145141919811,"lan":"ai-zh","lan_doc":"中文（自动生成）","is_lock":false,
+                "subtitle_url":"//aisubtitle.hdslb.com/bfs/ai_subtitle/prod/def.json","type":1,"ai_type":0,"ai_status":2},
+            {"id":1145141919812,"lan":"ai-en","lan_doc":"English（自动生成）","is_lock":false,
+                "subtitle_url":"","type":1,"ai_type":0,"ai_status":2}]}}}"#;
+
+    #[tokio::test]
+    async fn parses_subtitle_tracks() {
+        let (client, requests) = serve(|_| PLAYER_BODY.to_string()).await;
+        let tracks = fetch_subtitle_tracks(&client, "BV1GJ411x7h7", 137649199).await.unwrap();
+        assert_eq!(tracks.iter().map(|t| t.lan.as_str()).collect::<Vec<_>>(), ["zh-CN", "ai-zh"]);
+        assert!(!tracks[0].is_ai());
+        assert!(tracks[1].is_ai());
+        assert_eq!(tracks[1].lan_doc, "中文（自动生成）");
+        let requests = requests.lock().unwrap().clone();
+        assert!(requests[0].starts_with("/x/player/wbi/v2?"));
+        assert_eq!(query(&requests[0], "cid").as_deref(), Some("137649199"));
+        assert!(query(&requests[0], "w_rid").is_some());
+
+        // 没有字幕的视频 subtitle 里只有空列表，老视频可能整个字段都没有
+        let (client, _) = serve(|_| r#"{"code":0,"data":{"subtitle":{"subtitles":[]}}}"#.to_string()).await;
+        assert!(fetch_subtitle_tracks(&client, "BV1", 1).await.unwrap().is_empty());
+        let (client, _) = serve(|_| r#"{"code":0,"data":{"aid":1}}"#.to_string()).await;
+        assert!(fetch_subtitle_tracks(&client, "BV1", 1).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn ai_subtitle_detected_by_type_or_language() {
+        let track = |lan: &str, kind: i32| SubtitleTrack {
+            id: 1,
+            lan: lan.to_string(),
+            lan_doc: String::new(),
+            subtitle_url: String::new(),
+            kind,
+        };
+        assert!(track("ai-zh", 0).is_ai());
+        assert!(track("zh-CN", 1).is_ai());
+        assert!(!track("zh-CN", 0).is_ai());
+    }
+
+    #[test]
+    fn normalizes_subtitle_url() {
+        assert_eq!(subtitle_url("//aisubtitle.hdslb.com/bfs/a.json"), "https://aisubtitle.hdslb.com/bfs/a.json");
+        assert_eq!(subtitle_url("http://i0.hdslb.com/bfs/subtitle/b.json"), "https://i0.hdslb.com/bfs/subtitle/b.json");
+        assert_eq!(subtitle_url("https://i0.hdslb.com/bfs/subtitle/c.json"), "https://i0.hdslb.com/bfs/subtitle/c.json");
+    }
+
+    #[test]
+    fn parses_subtitle_lines() {
+        // 录制的字幕文件（删减了行数）
+        let file: SubtitleBody = serde_json::from_str(
+            r##"{"font_size":0.4,"font_color":"#FFFFFF","background_alpha":0.5,"background_color":"#9C27B0",
+            "Stroke":"none","type":"AIsubtitle","lang":"zh","version":"v1.6.0.4",
+            "body":[{"from":0.52,"to":2.16,"sid":1,"location":2,"content":"大家好","music":0.0},
+                {"from":2.16,"to":5,"sid":2,"location":2,"content":"今天聊聊字幕","music":0.0}]}"##,
+        )
+        .unwrap();
+        assert_eq!(file.body.len(), 2);
+        assert_eq!((file.body[0].from, file.body[0].to), (0.52, 2.16));
+        assert_eq!(file.body[1].to, 5.0);
+        assert_eq!(file.body[1].content, "今天聊聊字幕");
+
+        let empty: SubtitleBody = serde_json::from_str(r#"{"type":"AIsubtitle"}"#).unwrap();
+        assert!(empty.body.is_empty());
+    }
+}
@@ -0,0 +1,802 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use reqwest::cookie::CookieStore;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+
+use super::bilibili::ApiResponse;
+use super::error::{BiliError, Result};
+use super::inspector::{network_log, Exchange, ResponseParts};
+use super::policy::{RateLimiter, RequestPolicy};
+use super::wbi::{WbiKeys, WbiSigner};
+
+/// Bilibili API 的标准 User-Agent
+pub const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// 主站 API 地址
+pub const API_BASE: &str = "https://api.bilibili.com";
+
+/// 登录相关接口地址
+pub const PASSPORT_BASE: &str = "https://passport.bilibili.com";
+
+/// 主站网页地址（刷新 Cookie 时读取 correspond 页面）
+pub const WWW_BASE: &str = "https://www.bilibili.com";
+
+/// 默认的 Referer
+pub const DEFAULT_REFERER: &str = "https://www.bilibili.com/";
+
+/// 空间相关接口使用的 Referer
+pub const SPACE_REFERER: &str = "https://space.bilibili.com/";
+
+/// 搜索接口使用的 Referer
+pub const SEARCH_REFERER: &str = "https://search.bilibili.com/";
+
+/// 客户端配置
+#[derive(Debug, Clone)]
+pub struct BiliClientConfig {
+    pub api_base: String,
+    pub passport_base: String,
+    pub www_base: String,
+    pub user_agent: String,
+    pub referer: String,
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// 限速、重试和并发策略
+    pub policy: RequestPolicy,
+}
+
+impl Default for BiliClientConfig {
+    fn default() -> Self {
+        Self {
+            api_base: API_BASE.to_string(),
+            passport_base: PASSPORT_BASE.to_string(),
+            www_base: WWW_BASE.to_string(),
+            user_agent: USER_AGENT.to_string(),
+            referer: DEFAULT_REFERER.to_string(),
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            policy: RequestPolicy::default(),
+        }
+    }
+}
+
+impl BiliClientConfig {
+    /// 所有接口都指向同一个地址（本地 mock 服务器）
+    #[cfg(test)]
+    pub fn with_base_url(mut self, base: impl Into<String>) -> Self {
+        let base = base.into();
+        self.api_base = base.clone();
+        self.passport_base = base.clone();
+        self.www_base = base;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// 按名字保存的 Cookie，同时作为 reqwest 的 cookie provider
+///
+/// 只会把 Cookie 发给 bilibili.com 及配置里的接口地址，图片 CDN 等其它域名拿不到登录态。
+#[derive(Default)]
+pub struct CookieJar {
+    cookies: RwLock<BTreeMap<String, String>>,
+    extra_hosts: HashSet<String>,
+}
+
+impl CookieJar {
+    fn with_hosts(hosts: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            cookies: RwLock::new(BTreeMap::new()),
+            extra_hosts: hosts.into_iter().collect(),
+        }
+    }
+
+    /// 用 `k1=v1; k2=v2` 形式的请求头替换全部 Cookie
+    pub fn set_header(&self, header: &str) {
+        let mut cookies = self.cookies.write().unwrap();
+        cookies.clear();
+        for pair in header.split(';') {
+            if let Some((k, v)) = pair.split_once('=') {
+                let (k, v) = (k.trim(), v.trim());
+                if !k.is_empty() {
+                    cookies.insert(k.to_string(), v.to_string());
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.cookies.read().unwrap().get(name).cloned()
+    }
+
+    pub fn clear(&self) {
+        self.cookies.write().unwrap().clear();
+    }
+
+    /// 生成 Cookie 请求头
+    pub fn header(&self) -> Option<String> {
+        let cookies = self.cookies.read().unwrap();
+        if cookies.is_empty() {
+            return None;
+        }
+        Some(
+            cookies
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    fn accepts(&self, url: &reqwest::Url) -> bool {
+        let Some(host) = url.host_str() else { return false; };
+        host == "bilibili.com" || host.ends_with(".bilibili.com") || self.extra_hosts.contains(host)
+    }
+}
+
+// 只显示 Cookie 名，不显示值
+impl std::fmt::Debug for CookieJar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieJar")
+            .field("names", &self.cookies.read().unwrap().keys().collect::<Vec<_>>())
+            .field("extra_hosts", &self.extra_hosts)
+            .finish()
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &reqwest::Url) {
+        if !self.accepts(url) {
+            return;
+        }
+        let mut cookies = self.cookies.write().unwrap();
+        for value in cookie_headers {
+            let Ok(line) = value.to_str() else { continue; };
+            // 只取 Set-Cookie 的第一个键值对，属性部分忽略
+            let Some((k, v)) = line.split(';').next().and_then(|p| p.split_once('=')) else { continue; };
+            let (k, v) = (k.trim(), v.trim());
+            if k.is_empty() {
+                continue;
+            }
+            cookies.insert(k.to_string(), v.to_string());
+        }
+    }
+
+    fn cookies(&self, url: &reqwest::Url) -> Option<HeaderValue> {
+        if !self.accepts(url) {
+            return None;
+        }
+        self.header().and_then(|h| HeaderValue::from_str(&h).ok())
+    }
+}
+
+/// 所有 Bilibili 请求共用的客户端：连接池、Cookie、默认请求头和超时都在这里
+#[derive(Clone)]
+pub struct BiliClient {
+    http: reqwest::Client,
+    jar: Arc<CookieJar>,
+    wbi: Arc<WbiSigner>,
+    limiter: Arc<RateLimiter>,
+    permits: Arc<tokio::sync::Semaphore>,
+    config: Arc<BiliClientConfig>,
+}
+
+impl BiliClient {
+    pub fn new(config: BiliClientConfig) -> Self {
+        let hosts = [&config.api_base, &config.passport_base, &config.www_base]
+            .into_iter()
+            .filter_map(|base| reqwest::Url::parse(base).ok())
+            .filter_map(|url| url.host_str().map(str::to_string));
+        let jar = Arc::new(CookieJar::with_hosts(hosts));
+
+        let mut headers = HeaderMap::new();
+        if let Ok(referer) = HeaderValue::from_str(&config.referer) {
+            headers.insert(reqwest::header::REFERER, referer);
+        }
+
+        let http = reqwest::Client::builder()
+            .user_agent(config.user_agent.clone())
+            .default_headers(headers)
+            .cookie_provider(jar.clone())
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http,
+            jar,
+            wbi: Arc::new(WbiSigner::default()),
+            limiter: Arc::new(RateLimiter::new(&config.policy)),
+            permits: Arc::new(tokio::sync::Semaphore::new(config.policy.max_concurrency.max(1))),
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn cookie_jar(&self) -> &Arc<CookieJar> {
+        &self.jar
+    }
+
+    /// 拼接主站 API 地址，例如 `/x/web-interface/nav`
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.config.api_base.trim_end_matches('/'), path)
+    }
+
+    /// 拼接登录接口地址
+    pub fn passport_url(&self, path: &str) -> String {
+        format!("{}{}", self.config.passport_base.trim_end_matches('/'), path)
+    }
+
+    /// 拼接主站网页地址
+    pub fn www_url(&self, path: &str) -> String {
+        format!("{}{}", self.config.www_base.trim_end_matches('/'), path)
+    }
+
+    pub fn get(&self, url: impl Into<String>) -> BiliRequest {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: impl Into<String>) -> BiliRequest {
+        self.request(Method::POST, url)
+    }
+
+    fn request(&self, method: Method, url: impl Into<String>) -> BiliRequest {
+        BiliRequest {
+            client: self.clone(),
+            method,
+            url: url.into(),
+            query: Vec::new(),
+            form: None,
+            headers: HeaderMap::new(),
+            wbi: false,
+        }
+    }
+
+    /// 获取 WBI 密钥，缓存过期时从 nav 接口重新拉取（未登录时 nav 也会返回 wbi_img）
+    async fn wbi_keys(&self) -> Result<WbiKeys> {
+        if let Some(keys) = self.wbi.cached_keys() {
+            return Ok(keys);
+        }
+
+        #[derive(serde::Deserialize)]
+        struct WbiImg { img_url: String, sub_url: String }
+        #[derive(serde::Deserialize)]
+        struct NavData { wbi_img: WbiImg }
+
+        let request = self.http.get(self.api_url("/x/web-interface/nav")).build()?;
+        let nav: ApiResponse<NavData> = self.execute(request).await?.json()?;
+        let img = nav
+            .data
+            .ok_or_else(|| BiliError::Decode("nav 接口未返回 wbi_img".to_string()))?
+            .wbi_img;
+        let keys = WbiKeys::from_urls(&img.img_url, &img.sub_url)
+            .ok_or_else(|| BiliError::Decode(format!("无法解析 WBI 密钥: {} / {}", img.img_url, img.sub_url)))?;
+        self.wbi.store(keys.clone());
+        Ok(keys)
+    }
+
+    /// 发出请求并读完响应体，请求和响应都记录到 [`network_log`]
+    async fn execute(&self, request: reqwest::Request) -> Result<BiliResponse> {
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let request_headers = self.effective_headers(&request);
+        let request_body = request.body().and_then(|b| b.as_bytes()).map(<[u8]>::to_vec);
+        let started_at = chrono::Local::now();
+        let started = Instant::now();
+
+        let result = async {
+            let response = self.http.execute(request).await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let final_url = response.url().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok::<_, reqwest::Error>(BiliResponse { url: final_url, status, headers, body })
+        }
+        .await;
+
+        network_log().record(Exchange {
+            started_at,
+            method: &method,
+            url: &url,
+            request_headers,
+            request_body: request_body.as_deref(),
+            latency: started.elapsed(),
+            response: match &result {
+                Ok(response) => Ok(ResponseParts {
+                    status: response.status.as_u16(),
+                    headers: header_pairs(&response.headers),
+                    body: &response.body,
+                }),
+                Err(e) => Err(e.to_string()),
+            },
+        });
+        Ok(result?)
+    }
+
+    /// 实际发出的请求头：User-Agent、默认 Referer 和 Cookie 由 reqwest 在发送时补上，这里一并列出
+    fn effective_headers(&self, request: &reqwest::Request) -> Vec<(String, String)> {
+        let mut headers = header_pairs(request.headers());
+        let has = |headers: &[(String, String)], name: &str| headers.iter().any(|(k, _)| k == name);
+        if !has(&headers, "user-agent") {
+            headers.push(("user-agent".to_string(), self.config.user_agent.clone()));
+        }
+        if !has(&headers, "referer") {
+            headers.push(("referer".to_string(), self.config.referer.clone()));
+        }
+        if let Some(cookie) = self.jar.cookies(request.url()).and_then(|v| v.to_str().ok().map(str::to_string)) {
+            headers.push(("cookie".to_string(), cookie));
+        }
+        headers
+    }
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect()
+}
+
+/// 已经读完响应体的响应
+pub struct BiliResponse {
+    url: reqwest::Url,
+    status: reqwest::StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl BiliResponse {
+    pub fn status(&self) -> reqwest::StatusCode {
+        self.status
+    }
+
+    /// 跟随跳转之后的最终地址
+    pub fn url(&self) -> &reqwest::Url {
+        &self.url
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// 单个请求的构建器，最终统一经由 [`BiliClient`] 发出
+#[derive(Clone)]
+pub struct BiliRequest {
+    client: BiliClient,
+    method: Method,
+    url: String,
+    query: Vec<(String, String)>,
+    form: Option<Vec<(String, String)>>,
+    headers: HeaderMap,
+    wbi: bool,
+}
+
+impl BiliRequest {
+    pub fn query(mut self, pairs: &[(&str, &str)]) -> Self {
+        self.query
+            .extend(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        self
+    }
+
+    pub fn form(mut self, pairs: &[(&str, &str)]) -> Self {
+        self.form = Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+        self
+    }
+
+    /// 覆盖默认请求头，非法的值会被忽略
+    pub fn header(mut self, name: &'static str, value: impl AsRef<str>) -> Self {
+        if let Ok(value) = HeaderValue::from_str(value.as_ref()) {
+            self.headers.insert(HeaderName::from_static(name), value);
+        }
+        self
+    }
+
+    pub fn referer(self, referer: &str) -> Self {
+        self.header("referer", referer)
+    }
+
+    /// 发送前对查询参数做 WBI 签名（追加 `wts`/`w_rid`）
+    pub fn wbi(mut self) -> Self {
+        self.wbi = true;
+        self
+    }
+
+    /// 发送请求，HTTP 状态码不是 2xx 时按 [`BiliError::from_status`] 返回错误
+    pub async fn send(self) -> Result<BiliResponse> {
+        self.with_retry(|| self.send_once()).await
+    }
+
+    /// 发送请求并把响应体解析为 JSON
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        self.with_retry(|| async { self.send_once().await?.json::<T>() })
+            .await
+    }
+
+    /// 发送请求并取出标准响应里的 `data`，`code` 非 0 时按 [`BiliError::from_code`] 返回错误
+    ///
+    /// 风控、限流错误码和网络错误一样按请求策略重试，WBI 签名的请求遇到 -352 时还会先刷新密钥。
+    pub async fn data<T: DeserializeOwned>(self) -> Result<T> {
+        self.with_retry(|| async {
+            let api_response: ApiResponse<T> = self.send_once().await?.json()?;
+            if api_response.code != 0 {
+                return Err(BiliError::from_code(api_response.code, api_response.message.unwrap_or_default()));
+            }
+            api_response
+                .data
+                .ok_or_else(|| BiliError::Decode("API 返回数据为空".to_string()))
+        })
+        .await
+    }
+
+    /// 按请求策略重试：网络错误、HTTP 412 和风控/限流错误码按指数退避加随机抖动等待后重试
+    async fn with_retry<T, F, Fut>(&self, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let policy = &self.client.config.policy;
+        let mut attempt = 0;
+        loop {
+            match op().await {
+                Err(err) if attempt < policy.max_retries && RequestPolicy::is_retryable(&err) => {
+                    if self.wbi && matches!(err, BiliError::RiskControl { code: -352 }) {
+                        self.client.wbi.invalidate();
+                    }
+                    let delay = policy.backoff_delay(attempt, fastrand::f64());
+                    attempt += 1;
+                    warn!(
+                        "⚠️ 请求失败，{} ms 后第 {}/{} 次重试: {} - {}",
+                        delay.as_millis(), attempt, policy.max_retries, self.url, err
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 发出一次请求：先等 host 的限速令牌，再占用一个并发名额直到读完响应体
+    async fn send_once(&self) -> Result<BiliResponse> {
+        let query = if self.wbi {
+            let keys = self.client.wbi_keys().await?;
+            self.client.wbi.sign(&keys, &self.query)
+        } else {
+            self.query.clone()
+        };
+
+        let host = reqwest::Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        self.client.limiter.acquire(&host).await;
+        let _permit = self
+            .client
+            .permits
+            .acquire()
+            .await
+            .expect("request semaphore closed");
+
+        let mut builder = self
+            .client
+            .http
+            .request(self.method.clone(), &self.url)
+            .headers(self.headers.clone());
+        if !query.is_empty() {
+            builder = builder.query(&query);
+        }
+        if let Some(form) = &self.form {
+            builder = builder.form(form);
+        }
+        let response = self.client.execute(builder.build()?).await?;
+        match BiliError::from_status(response.status()) {
+            Some(err) => Err(err),
+            None => Ok(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const NAV_BODY: &str = r#"{"code":0,"data":{"wbi_img":{"img_url":"https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png","sub_url":"https://i0.hdslb.com/bfs/wbi/4932caff0ff746eab6f01bf08b70ac45.png"}}}"#;
+
+    /// 本地 mock 服务器：按顺序返回预设的响应，最后一个响应重复使用；nav 接口固定返回 WBI 密钥
+    struct MockServer {
+        base: String,
+        hits: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        async fn start(responses: Vec<(u16, &str)>, delay: Duration) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let queue: VecDeque<(u16, String)> = responses
+                .into_iter()
+                .map(|(status, body)| (status, body.to_string()))
+                .collect();
+            let queue = Arc::new(Mutex::new(queue));
+            let hits = Arc::new(AtomicUsize::new(0));
+            let in_flight = Arc::new(AtomicUsize::new(0));
+            let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+            let server = Self {
+                base,
+                hits: hits.clone(),
+                max_in_flight: max_in_flight.clone(),
+            };
+            tokio::spawn(async move {
+                loop {
+                    let Ok((mut socket, _)) = listener.accept().await else { break };
+                    let (queue, hits, in_flight, max_in_flight) =
+                        (queue.clone(), hits.clone(), in_flight.clone(), max_in_flight.clone());
+                    tokio::spawn(async move {
+                        let mut buf = vec![0u8; 8192];
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        let request = String::from_utf8_lossy(&buf[..n]);
+                        let (status, body) = if request.starts_with("GET /x/web-interface/nav") {
+                            (200, NAV_BODY.to_string())
+                        } else {
+                            hits.fetch_add(1, Ordering::SeqCst);
+                            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                            max_in_flight.fetch_max(current, Ordering::SeqCst);
+                            tokio::time::sleep(delay).await;
+                            in_flight.fetch_sub(1, Ordering::SeqCst);
+                            let mut queue = queue.lock().unwrap();
+                            if queue.len() > 1 {
+                                queue.pop_front().unwrap()
+                            } else {
+                                queue.front().cloned().unwrap()
+                            }
+                        };
+                        let response = format!(
+                            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            status,
+                            body.len(),
+                            body
+                        );
+                        let _ = socket.write_all(response.as_bytes()).await;
+                        let _ = socket.shutdown().await;
+                    });
+                }
+            });
+            server
+        }
+
+        fn hits(&self) -> usize {
+            self.hits.load(Ordering::SeqCst)
+        }
+
+        fn client(&self, policy: RequestPolicy) -> BiliClient {
+            BiliClient::new(BiliClientConfig::default().with_base_url(&self.base).with_policy(policy))
+        }
+    }
+
+    fn fast_policy() -> RequestPolicy {
+        RequestPolicy {
+            rate_per_sec: 1000.0,
+            burst: 100,
+            max_retries: 3,
+            base_delay: Duration::from_millis(5),
+            max_delay: Duration::from_millis(20),
+            max_concurrency: 4,
+        }
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Value {
+        value: i32,
+    }
+
+    #[tokio::test]
+    async fn retries_http_412_then_succeeds() {
+        let server = MockServer::start(
+            vec![(412, ""), (412, ""), (200, r#"{"code":0,"data":{"value":7}}"#)],
+            Duration::ZERO,
+        )
+        .await;
+        let client = server.client(fast_policy());
+
+        let value: Value = client.get(client.api_url("/x/test")).data().await.unwrap();
+        assert_eq!(value.value, 7);
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_risk_control_codes() {
+        let server = MockServer::start(
+            vec![
+                (200, r#"{"code":-352,"message":"风控校验失败"}"#),
+                (200, r#"{"code":-412,"message":"请求被拦截"}"#),
+                (200, r#"{"code":0,"data":{"value":1}}"#),
+            ],
+            Duration::ZERO,
+        )
+        .await;
+        let client = server.client(fast_policy());
+
+        let value: Value = client.get(client.api_url("/x/test")).wbi().data().await.unwrap();
+        assert_eq!(value.value, 1);
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_warnings_are_redacted() {
+        let server = MockServer::start(vec![(412, ""), (200, r#"{"code":0,"data":{"value":1}}"#)], Duration::ZERO).await;
+        let client = server.client(fast_policy());
+        let url = client.api_url("/x/test?qrcode_key=8f7e6d5c4b3a2918&csrf=0a1b2c3d4e5f6071");
+
+        crate::utils::logging::capture::start();
+        let value: Value = client.get(url).data().await.unwrap();
+        let output = crate::utils::logging::capture::finish();
+        assert_eq!(value.value, 1);
+        assert!(output.contains("重试"), "{output}");
+        assert!(output.contains("/x/test?qrcode_key=***&csrf=***"), "{output}");
+        assert!(!output.contains("8f7e6d5c") && !output.contains("0a1b2c3d"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = MockServer::start(vec![(412, "")], Duration::ZERO).await;
+        let client = server.client(RequestPolicy {
+            max_retries: 2,
+            ..fast_policy()
+        });
+
+        let err = client.get(client.api_url("/x/test")).data::<Value>().await.unwrap_err();
+        assert!(matches!(err, BiliError::RiskControl { code: -412 }), "{err}");
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_errors() {
+        let server = MockServer::start(
+            vec![
+                (200, r#"{"code":-404,"message":"啥都木有"}"#),
+                (200, r#"{"code":-101,"message":"账号未登录"}"#),
+            ],
+            Duration::ZERO,
+        )
+        .await;
+        let client = server.client(fast_policy());
+
+        let err = client.get(client.api_url("/x/test")).data::<Value>().await.unwrap_err();
+        assert!(matches!(err, BiliError::NotFound { code: -404, .. }), "{err}");
+        assert_eq!(server.hits(), 1);
+
+        let err = client.get(client.api_url("/x/test")).data::<Value>().await.unwrap_err();
+        assert!(err.needs_login(), "{err}");
+        assert_eq!(server.hits(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_transport_errors() {
+        // 先占用一个端口再释放，保证连接会被拒绝
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = BiliClient::new(BiliClientConfig::default().with_base_url(base).with_policy(RequestPolicy {
+            max_retries: 2,
+            ..fast_policy()
+        }));
+        let started = Instant::now();
+        let err = client.get(client.api_url("/x/test")).data::<Value>().await.unwrap_err();
+        assert!(matches!(err, BiliError::Transport(_)), "{err}");
+        // 两次重试至少各等待 base_delay 的一半
+        assert!(started.elapsed() >= Duration::from_millis(5));
+    }
+
+    #[tokio::test]
+    async fn limits_concurrent_requests() {
+        let server = MockServer::start(vec![(200, r#"{"code":0,"data":{"value":0}}"#)], Duration::from_millis(50)).await;
+        let client = server.client(RequestPolicy {
+            max_concurrency: 2,
+            ..fast_policy()
+        });
+
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.get(client.api_url("/x/test")).data::<Value>().await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(server.hits(), 6);
+        assert!(server.max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn rate_limits_per_host() {
+        let server = MockServer::start(vec![(200, r#"{"code":0,"data":{"value":0}}"#)], Duration::ZERO).await;
+        let client = server.client(RequestPolicy {
+            rate_per_sec: 20.0,
+            burst: 1,
+            ..fast_policy()
+        });
+
+        let started = Instant::now();
+        for _ in 0..5 {
+            client.get(client.api_url("/x/test")).data::<Value>().await.unwrap();
+        }
+        // 第一个请求用掉桶里的令牌，之后每 50ms 才补充一个
+        assert!(started.elapsed() >= Duration::from_millis(190), "{:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn records_each_attempt_in_network_log() {
+        let server = MockServer::start(
+            vec![(412, ""), (200, r#"{"code":0,"data":{"value":3}}"#)],
+            Duration::ZERO,
+        )
+        .await;
+        let client = server.client(fast_policy());
+        client.cookie_jar().set_header("SESSDATA=secret-sess; buvid3=abc");
+
+        let url = client.api_url("/x/inspected");
+        let value: Value = client.get(url.clone()).query(&[("a", "1")]).data().await.unwrap();
+        assert_eq!(value.value, 3);
+
+        let entries: Vec<_> = network_log()
+            .entries()
+            .into_iter()
+            .filter(|e| e.url.starts_with(&url))
+            .collect();
+        assert_eq!(entries.iter().map(|e| e.status).collect::<Vec<_>>(), vec![Some(412), Some(200)]);
+        let entry = &entries[1];
+        assert_eq!(entry.url, format!("{}?a=1", url));
+        assert_eq!(entry.bili_code, Some(0));
+        let cookie = entry.request_headers.iter().find(|(k, _)| k == "cookie").map(|(_, v)| v.as_str());
+        assert_eq!(cookie, Some("SESSDATA=***; buvid3=abc"));
+        assert!(entry.request_headers.iter().any(|(k, v)| k == "user-agent" && v == USER_AGENT));
+    }
+
+    #[tokio::test]
+    async fn pagination_retries_failed_pages() {
+        let archive = |aid: i64| {
+            format!(
+                r#"{{"aid":{aid},"bvid":"BV{aid}","title":"P{aid}","pic":"","pubdate":0,"duration":60,"stat":{{"view":1}}}}"#
+            )
+        };
+        let page1 = format!(
+            r#"{{"code":0,"data":{{"archives":[{},{}],"page":{{"num":1,"size":2,"total":3}}}}}}"#,
+            archive(1),
+            archive(2)
+        );
+        let page2 = format!(
+            r#"{{"code":0,"data":{{"archives":[{}],"page":{{"num":2,"size":2,"total":3}}}}}}"#,
+            archive(3)
+        );
+        let server = MockServer::start(vec![(200, &page1), (412, ""), (200, &page2)], Duration::ZERO).await;
+        let client = server.client(fast_policy());
+
+        let videos = super::super::bilibili::fetch_all_series_archives(&client, "1", "2").await.unwrap();
+        assert_eq!(videos.iter().map(|v| v.aid).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(server.hits(), 3);
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::io::Read;
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use prost::Message;
+use quick_xml::escape::unescape;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion};
+
+use super::client::BiliClient;
+use super::error::{BiliError, Result};
+
+/// 分段弹幕接口每段覆盖 6 分钟
+const SEGMENT_SECONDS: i64 = 6 * 60;
+
+/// 弹幕类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DanmakuMode {
+    /// 普通滚动弹幕（1~3）
+    Scroll,
+    Bottom,
+    Top,
+    Reverse,
+    /// 高级弹幕
+    Advanced,
+    /// 代码弹幕
+    Code,
+    /// BAS 弹幕
+    Bas,
+    Other(i32),
+}
+
+impl DanmakuMode {
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1..=3 => DanmakuMode::Scroll,
+            4 => DanmakuMode::Bottom,
+            5 => DanmakuMode::Top,
+            6 => DanmakuMode::Reverse,
+            7 => DanmakuMode::Advanced,
+            8 => DanmakuMode::Code,
+            9 => DanmakuMode::Bas,
+            other => DanmakuMode::Other(other),
+        }
+    }
+}
+
+/// 一条弹幕
+#[derive(Debug, Clone, PartialEq)]
+pub struct Danmaku {
+    pub id: i64,
+    /// 出现时间（秒）
+    pub time: f64,
+    pub mode: DanmakuMode,
+    pub font_size: i32,
+    /// 十进制 RGB 颜色
+    pub color: u32,
+    /// 发送时间（Unix 秒）
+    pub timestamp: i64,
+    /// 弹幕池：0 普通，1 字幕，2 特殊
+    pub pool: i32,
+    /// 发送者 mid 的 crc32 哈希
+    pub mid_hash: String,
+    /// 屏蔽等级，越高越不容易被智能屏蔽
+    pub weight: i32,
+    pub text: String,
+}
+
+/// `seg.so` 返回的 protobuf 消息（bilibili.community.service.dm.v1.DmSegMobileReply）
+#[derive(Clone, PartialEq, Message)]
+struct DmSegMobileReply {
+    #[prost(message, repeated, tag = "1")]
+    elems: Vec<DanmakuElem>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DanmakuElem {
+    #[prost(int64, tag = "1")]
+    id: i64,
+    /// 毫秒
+    #[prost(int32, tag = "2")]
+    progress: i32,
+    #[prost(int32, tag = "3")]
+    mode: i32,
+    #[prost(int32, tag = "4")]
+    fontsize: i32,
+    #[prost(uint32, tag = "5")]
+    color: u32,
+    #[prost(string, tag = "6")]
+    mid_hash: String,
+    #[prost(string, tag = "7")]
+    content: String,
+    #[prost(int64, tag = "8")]
+    ctime: i64,
+    #[prost(int32, tag = "9")]
+    weight: i32,
+    #[prost(string, tag = "10")]
+    action: String,
+    #[prost(int32, tag = "11")]
+    pool: i32,
+    #[prost(string, tag = "12")]
+    id_str: String,
+    #[prost(int32, tag = "13")]
+    attr: i32,
+}
+
+impl From<DanmakuElem> for Danmaku {
+    fn from(elem: DanmakuElem) -> Self {
+        Danmaku {
+            id: elem.id,
+            time: elem.progress as f64 / 1000.0,
+            mode: DanmakuMode::from_code(elem.mode),
+            font_size: elem.fontsize,
+            color: elem.color,
+            timestamp: elem.ctime,
+            pool: elem.pool,
+            mid_hash: elem.mid_hash,
+            weight: elem.weight,
+            text: elem.content,
+        }
+    }
+}
+
+/// 解析 `list.so` 返回的 XML，`<d p="时间,类型,字号,颜色,发送时间,弹幕池,用户哈希,dmid,屏蔽等级">内容</d>`
+pub fn parse_xml(xml: &str) -> Result<Vec<Danmaku>> {
+    let mut reader = Reader::from_str(xml);
+    let mut list = Vec::new();
+    // 当前正在读取的 <d> 的属性和内容
+    let mut current: Option<(String, String)> = None;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) if e.name().as_ref() == b"d" => {
+                let p = e
+                    .try_get_attribute("p")
+                    .map_err(xml_error)?
+                    .map(|a| a.normalized_value(XmlVersion::Implicit1_0).map(|v| v.into_owned()))
+                    .transpose()
+                    .map_err(xml_error)?
+                    .unwrap_or_default();
+                current = Some((p, String::new()));
+            }
+            Event::Text(t) => {
+                if let Some((_, text)) = current.as_mut() {
+                    text.push_str(&t.decode().map_err(xml_error)?);
+                }
+            }
+            Event::CData(t) => {
+                if let Some((_, text)) = current.as_mut() {
+                    text.push_str(&t.decode().map_err(xml_error)?);
+                }
+            }
+            Event::GeneralRef(r) => {
+                if let Some((_, text)) = current.as_mut() {
+                    let raw = format!("&{};", r.decode().map_err(xml_error)?);
+                    text.push_str(&unescape(&raw).map_err(xml_error)?);
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"d" => {
+                if let Some((p, text)) = current.take() {
+                    if let Some(danmaku) = parse_xml_attrs(&p, text) {
+                        list.push(danmaku);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(list)
+}
+
+fn xml_error(e: impl std::fmt::Display) -> BiliError {
+    BiliError::Decode(format!("弹幕 XML 解析失败: {}", e))
+}
+
+/// 解析 `p` 属性，字段不全或格式不对的条目直接跳过
+fn parse_xml_attrs(p: &str, text: String) -> Option<Danmaku> {
+    let fields: Vec<&str> = p.split(',').collect();
+    if fields.len() < 8 {
+        return None;
+    }
+    Some(Danmaku {
+        id: fields[7].parse().ok()?,
+        time: fields[0].parse().ok()?,
+        mode: DanmakuMode::from_code(fields[1].parse().ok()?),
+        font_size: fields[2].parse().ok()?,
+        color: fields[3].parse().ok()?,
+        timestamp: fields[4].parse().ok()?,
+        pool: fields[5].parse().ok()?,
+        mid_hash: fields[6].to_string(),
+        // 较早的弹幕没有屏蔽等级字段
+        weight: fields.get(8).and_then(|w| w.parse().ok()).unwrap_or(0),
+        text,
+    })
+}
+
+/// 解析一段 `seg.so` 返回的 protobuf
+pub fn parse_segment(bytes: &[u8]) -> Result<Vec<Danmaku>> {
+    let reply = DmSegMobileReply::decode(bytes)
+        .map_err(|e| BiliError::Decode(format!("弹幕 protobuf 解析失败: {}", e)))?;
+    Ok(reply.elems.into_iter().map(Danmaku::from).collect())
+}
+
+/// `list.so` 的响应体是不带 zlib 头的 deflate 数据，偶尔也会是明文
+fn decode_xml_body(bytes: &[u8]) -> Result<String> {
+    if bytes.trim_ascii_start().starts_with(b"<") {
+        return Ok(String::from_utf8_lossy(bytes).into_owned());
+    }
+
+    let mut xml = String::new();
+    if DeflateDecoder::new(bytes).read_to_string(&mut xml).is_ok() {
+        return Ok(xml);
+    }
+    xml.clear();
+    ZlibDecoder::new(bytes)
+        .read_to_string(&mut xml)
+        .map_err(|e| BiliError::Decode(format!("弹幕 XML 解压失败: {}", e)))?;
+    Ok(xml)
+}
+
+/// 从 XML 接口获取弹幕（只包含弹幕池中最新的一部分）
+pub async fn fetch_danmaku_xml(client: &BiliClient, cid: i64) -> Result<Vec<Danmaku>> {
+    let response = client
+        .get(client.api_url("/x/v1/dm/list.so"))
+        .query(&[("oid", &cid.to_string())])
+        .send()
+        .await?;
+    parse_xml(&decode_xml_body(response.bytes())?)
+}
+
+/// 获取第 `segment_index` 段（从 1 开始）的 protobuf 弹幕
+pub async fn fetch_danmaku_segment(client: &BiliClient, cid: i64, segment_index: i64) -> Result<Vec<Danmaku>> {
+    let response = client
+        .get(client.api_url("/x/v2/dm/wbi/web/seg.so"))
+        .query(&[
+            ("type", "1"),
+            ("oid", &cid.to_string()),
+            ("segment_index", &segment_index.to_string()),
+        ])
+        .wbi()
+        .send()
+        .await?;
+    parse_segment(response.bytes())
+}
+
+/// 按时长获取全部分段，结果按时间排序并按 id 去重
+pub async fn fetch_all_danmaku(client: &BiliClient, cid: i64, duration: i64) -> Result<Vec<Danmaku>> {
+    let segments = (duration.max(1) + SEGMENT_SECONDS - 1) / SEGMENT_SECONDS;
+    let mut all = Vec::new();
+    for index in 1..=segments {
+        all.extend(fetch_danmaku_segment(client, cid, index).await?);
+    }
+
+    let mut seen = HashSet::new();
+    all.retain(|d| seen.insert(d.id));
+    all.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+
+    const LIST_XML: &str = include_str!("fixtures/danmaku_list.xml");
+    const SEGMENT: &[u8] = include_bytes!("fixtures/danmaku_seg.bin");
+
+    #[test]
+    fn parse_xml_fixture() {
+        let list = parse_xml(LIST_XML).unwrap();
+        assert_eq!(list.len(), 4);
+
+        let first = &list[0];
+        assert_eq!(first.id, 1234567890123456789);
+        assert_eq!(first.time, 12.345);
+        assert_eq!(first.mode, DanmakuMode::Scroll);
+        assert_eq!(first.font_size, 25);
+        assert_eq!(first.color, 16777215);
+        assert_eq!(first.timestamp, 1700000000);
+        assert_eq!(first.pool, 0);
+        assert_eq!(first.mid_hash, "a1b2c3d4");
+        assert_eq!(first.weight, 10);
+        assert_eq!(first.text, "前方高能");
+
+        assert_eq!(list[1].mode, DanmakuMode::Top);
+        assert_eq!(list[1].color, 16711680);
+        assert_eq!(list[1].text, "<字幕君> & \"好耶\"");
+        assert_eq!(list[2].mode, DanmakuMode::Bottom);
+        // 缺少屏蔽等级的旧格式
+        assert_eq!(list[2].weight, 0);
+        assert_eq!(list[3].text, "");
+    }
+
+    #[test]
+    fn parse_xml_skips_malformed_entries() {
+        let xml = r#"<i><d p="1.0,1,25">缺字段</d><d p="x,1,25,0,0,0,h,1">坏时间</d><d p="2.5,5,25,255,1,0,h,9,3">ok</d></i>"#;
+        let list = parse_xml(xml).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].id, 9);
+        assert_eq!(list[0].mode, DanmakuMode::Top);
+    }
+
+    #[test]
+    fn decode_deflated_xml_body() {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(LIST_XML.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decode_xml_body(&compressed).unwrap(), LIST_XML);
+        assert_eq!(decode_xml_body(LIST_XML.as_bytes()).unwrap(), LIST_XML);
+    }
+
+    #[test]
+    fn parse_segment_fixture() {
+        let list = parse_segment(SEGMENT).unwrap();
+        assert_eq!(list.len(), 3);
+
+        let first = &list[0];
+        assert_eq!(first.id, 1234567890123456789);
+        assert_eq!(first.time, 12.345);
+        assert_eq!(first.mode, DanmakuMode::Scroll);
+        assert_eq!(first.font_size, 25);
+        assert_eq!(first.color, 16777215);
+        assert_eq!(first.mid_hash, "a1b2c3d4");
+        assert_eq!(first.timestamp, 1700000000);
+        assert_eq!(first.weight, 10);
+        assert_eq!(first.text, "前方高能");
+
+        assert_eq!(list[1].mode, DanmakuMode::Top);
+        assert_eq!(list[1].pool, 1);
+        assert_eq!(list[1].text, "字幕君");
+        assert_eq!(list[2].mode, DanmakuMode::Advanced);
+        assert_eq!(list[2].time, 0.0);
+    }
+
+    #[test]
+    fn parse_segment_empty_and_invalid() {
+        assert!(parse_segment(&[]).unwrap().is_empty());
+        assert!(parse_segment(&[0x0a, 0xff]).is_err());
+    }
+
+    #[test]
+    fn mode_codes() {
+        assert_eq!(DanmakuMode::from_code(2), DanmakuMode::Scroll);
+        assert_eq!(DanmakuMode::from_code(6), DanmakuMode::Reverse);
+        assert_eq!(DanmakuMode::from_code(8), DanmakuMode::Code);
+        assert_eq!(DanmakuMode::from_code(9), DanmakuMode::Bas);
+        assert_eq!(DanmakuMode::from_code(42), DanmakuMode::Other(42));
+    }
+}
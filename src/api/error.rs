@@ -0,0 +1,201 @@
+use std::fmt;
+
+/// API 层统一的返回类型
+pub type Result<T, E = BiliError> = std::result::Result<T, E>;
+
+/// Bilibili 接口错误，按界面需要区别处理的情况分类
+#[derive(Debug)]
+pub enum BiliError {
+    /// 未登录或登录已失效（-101），需要重新扫码
+    NotLoggedIn,
+    /// 触发风控（-352 / -403 / -412），需要降低请求频率或稍后再试
+    RiskControl { code: i32 },
+    /// 请求过于频繁（-509 / -799 / HTTP 429）
+    RateLimited { code: i32 },
+    /// 视频、用户或资源不存在或不可见
+    NotFound { code: i32, message: String },
+    /// 二维码已失效（86038）
+    QrExpired,
+    /// 二维码已扫描，等待手机端确认（86090）
+    QrScanned,
+    /// 二维码未扫描（86101）
+    QrPending,
+    /// 其他非 0 的业务错误码
+    Api { code: i32, message: String },
+    /// 网络错误、超时或 HTTP 状态码错误
+    Transport(reqwest::Error),
+    /// 响应内容无法解析
+    Decode(String),
+}
+
+impl BiliError {
+    /// 按接口返回的 `code` 分类
+    pub fn from_code(code: i32, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match code {
+            -101 => BiliError::NotLoggedIn,
+            // 这些接口上的 -403 和 -352 一样是风控拦截，不是资源不可见
+            -352 | -403 | -412 => BiliError::RiskControl { code },
+            -509 | -799 => BiliError::RateLimited { code },
+            // -404 啥都木有；62002 稿件不可见；62004 稿件审核中
+            -404 | 62002 | 62004 => BiliError::NotFound { code, message },
+            86038 => BiliError::QrExpired,
+            86090 => BiliError::QrScanned,
+            86101 => BiliError::QrPending,
+            _ => BiliError::Api { code, message },
+        }
+    }
+
+    /// 按 HTTP 状态码分类，成功的状态码返回 `None`
+    pub fn from_status(status: reqwest::StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            200..=299 => None,
+            412 => Some(BiliError::RiskControl { code: -412 }),
+            429 => Some(BiliError::RateLimited { code: 429 }),
+            404 => Some(BiliError::NotFound {
+                code: 404,
+                message: status.to_string(),
+            }),
+            _ => Some(BiliError::Api {
+                code: status.as_u16() as i32,
+                message: format!("HTTP {}", status),
+            }),
+        }
+    }
+
+    /// 业务错误码，网络和解析错误没有错误码
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            BiliError::NotLoggedIn => Some(-101),
+            BiliError::RiskControl { code } | BiliError::RateLimited { code } => Some(*code),
+            BiliError::NotFound { code, .. } | BiliError::Api { code, .. } => Some(*code),
+            BiliError::QrExpired => Some(86038),
+            BiliError::QrScanned => Some(86090),
+            BiliError::QrPending => Some(86101),
+            BiliError::Transport(_) | BiliError::Decode(_) => None,
+        }
+    }
+
+    /// 需要重新登录
+    pub fn needs_login(&self) -> bool {
+        matches!(self, BiliError::NotLoggedIn)
+    }
+
+    /// 被风控或限流，应当放慢请求
+    pub fn should_back_off(&self) -> bool {
+        matches!(self, BiliError::RiskControl { .. } | BiliError::RateLimited { .. })
+    }
+
+    /// 从 anyhow 错误链中取出 `BiliError`
+    pub fn find(err: &anyhow::Error) -> Option<&BiliError> {
+        err.chain().find_map(|e| e.downcast_ref::<BiliError>())
+    }
+}
+
+impl fmt::Display for BiliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BiliError::NotLoggedIn => write!(f, "账号未登录或登录已失效"),
+            BiliError::RiskControl { code } => write!(f, "请求被风控拦截 (code={})", code),
+            BiliError::RateLimited { code } => write!(f, "请求过于频繁 (code={})", code),
+            BiliError::NotFound { code, message } => write!(f, "资源不存在: {} (code={})", message, code),
+            BiliError::QrExpired => write!(f, "二维码已失效"),
+            BiliError::QrScanned => write!(f, "二维码已扫描，等待确认"),
+            BiliError::QrPending => write!(f, "二维码未扫描"),
+            BiliError::Api { code, message } => write!(f, "API 返回错误: code={}, message={:?}", code, message),
+            BiliError::Transport(e) => write!(f, "网络请求失败: {}", e),
+            BiliError::Decode(e) => write!(f, "响应解析失败: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BiliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BiliError::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for BiliError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            BiliError::Decode(e.to_string())
+        } else {
+            BiliError::Transport(e)
+        }
+    }
+}
+
+impl From<serde_json::Error> for BiliError {
+    fn from(e: serde_json::Error) -> Self {
+        BiliError::Decode(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn classifies_codes() {
+        assert!(matches!(BiliError::from_code(-101, "账号未登录"), BiliError::NotLoggedIn));
+        for code in [-352, -403, -412] {
+            assert!(matches!(BiliError::from_code(code, ""), BiliError::RiskControl { code: c } if c == code), "{}", code);
+        }
+        for code in [-509, -799] {
+            assert!(matches!(BiliError::from_code(code, ""), BiliError::RateLimited { code: c } if c == code), "{}", code);
+        }
+        for code in [-404, 62002, 62004] {
+            assert!(matches!(BiliError::from_code(code, "啥都木有"), BiliError::NotFound { code: c, .. } if c == code), "{}", code);
+        }
+        assert!(matches!(BiliError::from_code(86038, ""), BiliError::QrExpired));
+        assert!(matches!(BiliError::from_code(86090, ""), BiliError::QrScanned));
+        assert!(matches!(BiliError::from_code(86101, ""), BiliError::QrPending));
+        match BiliError::from_code(-400, "请求错误") {
+            BiliError::Api { code, message } => assert_eq!((code, message.as_str()), (-400, "请求错误")),
+            other => panic!("{other}"),
+        }
+    }
+
+    #[test]
+    fn classifies_http_status() {
+        assert!(BiliError::from_status(StatusCode::OK).is_none());
+        assert!(BiliError::from_status(StatusCode::NO_CONTENT).is_none());
+        assert!(matches!(BiliError::from_status(StatusCode::PRECONDITION_FAILED), Some(BiliError::RiskControl { code: -412 })));
+        assert!(matches!(BiliError::from_status(StatusCode::TOO_MANY_REQUESTS), Some(BiliError::RateLimited { code: 429 })));
+        assert!(matches!(BiliError::from_status(StatusCode::NOT_FOUND), Some(BiliError::NotFound { code: 404, .. })));
+        assert!(matches!(BiliError::from_status(StatusCode::BAD_GATEWAY), Some(BiliError::Api { code: 502, .. })));
+    }
+
+    #[test]
+    fn login_and_back_off() {
+        assert!(BiliError::from_code(-101, "").needs_login());
+        for code in [-352, -403, -412, -509, -799] {
+            let err = BiliError::from_code(code, "");
+            assert!(err.should_back_off(), "{}", code);
+            assert!(!err.needs_login(), "{}", code);
+        }
+        for code in [-101, -404, 0, 86101] {
+            assert!(!BiliError::from_code(code, "").should_back_off(), "{}", code);
+        }
+        assert!(!BiliError::Decode("bad".into()).should_back_off());
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for code in [-101, -352, -403, -404, -412, -509, -799, 62002, 86038, 86090, 86101, -400] {
+            assert_eq!(BiliError::from_code(code, "").code(), Some(code));
+        }
+        assert_eq!(BiliError::Decode("bad".into()).code(), None);
+    }
+
+    #[test]
+    fn finds_error_in_anyhow_chain() {
+        let err = anyhow::Error::new(BiliError::from_code(-403, "")).context("加载合集失败");
+        assert!(BiliError::find(&err).is_some_and(|e| e.should_back_off()));
+        assert!(BiliError::find(&anyhow::anyhow!("其他错误")).is_none());
+    }
+}
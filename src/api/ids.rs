@@ -0,0 +1,300 @@
+//! Bilibili 链接与编号解析（纯函数，不发请求）
+
+/// BV 号编码表
+const BV_TABLE: &[u8; 58] = b"FcwAPNKTMug3GV5Lj7EJnHpWsx4tb8haYeviqBz6rkCy12mUSDQX9RdoZf";
+const XOR_CODE: u64 = 23442827791579;
+const MASK_CODE: u64 = (1 << 51) - 1;
+/// av 号上限（不含）
+const MAX_AID: u64 = 1 << 51;
+
+/// 粘贴的链接或编号指向的内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BiliTarget {
+    /// 视频，`page` 为链接中的 `?p=` 分P（从 1 开始）
+    Video { aid: i64, bvid: String, page: Option<u32> },
+    /// 用户空间
+    Space { mid: i64 },
+    /// 合集
+    Season { mid: i64, season_id: i64 },
+    /// 系列
+    Series { mid: i64, series_id: i64 },
+    /// 直播间
+    Live { room_id: i64 },
+    /// b23.tv 短链接，需要跟随跳转才知道目标
+    ShortLink { url: String },
+}
+
+/// av 号转 BV 号，超出范围时返回 `None`
+pub fn av_to_bv(aid: i64) -> Option<String> {
+    if aid <= 0 || aid as u64 >= MAX_AID {
+        return None;
+    }
+    let mut bytes = *b"BV1000000000";
+    let mut tmp = (MAX_AID | aid as u64) ^ XOR_CODE;
+    let mut idx = bytes.len() - 1;
+    while tmp > 0 {
+        bytes[idx] = BV_TABLE[(tmp % 58) as usize];
+        tmp /= 58;
+        idx -= 1;
+    }
+    bytes.swap(3, 9);
+    bytes.swap(4, 7);
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// BV 号转 av 号，不是合法 BV 号时返回 `None`（前缀 `BV` 大小写均可）
+pub fn bv_to_av(bvid: &str) -> Option<i64> {
+    let mut bytes: [u8; 12] = bvid.as_bytes().try_into().ok()?;
+    if !bytes[..2].eq_ignore_ascii_case(b"bv") {
+        return None;
+    }
+    bytes.swap(3, 9);
+    bytes.swap(4, 7);
+    let mut tmp: u64 = 0;
+    for c in &bytes[3..] {
+        let digit = BV_TABLE.iter().position(|t| t == c)? as u64;
+        tmp = tmp.checked_mul(58)?.checked_add(digit)?;
+    }
+    // 合法的 BV 号解码后第 51 位一定是 1
+    if tmp >> 51 != 1 {
+        return None;
+    }
+    let aid = ((tmp & MASK_CODE) ^ XOR_CODE) as i64;
+    (aid > 0).then_some(aid)
+}
+
+/// 规范化 BV 号（统一为大写 `BV` 前缀），不合法时返回 `None`
+pub fn normalize_bvid(bvid: &str) -> Option<String> {
+    bv_to_av(bvid).map(|_| format!("BV{}", &bvid[2..]))
+}
+
+/// 解析链接或裸的 BV/av 号；分享文案里夹带的链接也能识别。纯数字有歧义（UID、房间号），返回 `None`
+pub fn parse(input: &str) -> Option<BiliTarget> {
+    let input = input.trim();
+    let input = match input.find("http://").or_else(|| input.find("https://")) {
+        Some(start) => input[start..].split_whitespace().next()?,
+        None => input,
+    };
+    if input.is_empty() {
+        return None;
+    }
+
+    let without_scheme = input
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let (without_fragment, _) = without_scheme.split_once('#').unwrap_or((without_scheme, ""));
+    let (location, query) = without_fragment.split_once('?').unwrap_or((without_fragment, ""));
+    let (host, path) = location.split_once('/').unwrap_or((location, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if !host.contains('.') {
+        // 没有域名，只可能是裸的 BV/av 号（BV 号区分大小写）
+        return if segments.is_empty() { video(host, query) } else { None };
+    }
+    let host = host.to_ascii_lowercase();
+
+    match host.as_str() {
+        "b23.tv" | "www.b23.tv" | "bili2233.cn" => {
+            let code = segments.first()?;
+            Some(BiliTarget::ShortLink { url: format!("https://{}/{}", host, code) })
+        }
+        "space.bilibili.com" => space(&segments, query),
+        "live.bilibili.com" => {
+            // https://live.bilibili.com/{room_id} 或手机版 /h5/{room_id}
+            let room = match segments.as_slice() {
+                ["h5", room, ..] => room,
+                [room, ..] => room,
+                [] => return None,
+            };
+            room.parse().ok().map(|room_id| BiliTarget::Live { room_id })
+        }
+        "bilibili.com" | "www.bilibili.com" | "m.bilibili.com" => match segments.as_slice() {
+            ["video", id, ..] | ["s", "video", id, ..] => video(id, query),
+            // 手机版空间：https://m.bilibili.com/space/{mid}
+            ["space", mid, ..] => mid.parse().ok().map(|mid| BiliTarget::Space { mid }),
+            // 合集播放页：https://www.bilibili.com/list/{mid}?sid={season_id}
+            ["list", mid, ..] => {
+                let mid = mid.parse().ok()?;
+                let season_id = query_param(query, "sid")?.parse().ok()?;
+                Some(BiliTarget::Season { mid, season_id })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `https://space.bilibili.com/{mid}/...`
+fn space(segments: &[&str], query: &str) -> Option<BiliTarget> {
+    let mid: i64 = segments.first()?.parse().ok()?;
+    let sid = || query_param(query, "sid")?.parse::<i64>().ok();
+    let target = match segments[1..] {
+        // 旧版：/{mid}/channel/collectiondetail?sid= 和 /{mid}/channel/seriesdetail?sid=
+        ["channel", "collectiondetail", ..] => match sid() {
+            Some(season_id) => BiliTarget::Season { mid, season_id },
+            None => BiliTarget::Space { mid },
+        },
+        ["channel", "seriesdetail", ..] => match sid() {
+            Some(series_id) => BiliTarget::Series { mid, series_id },
+            None => BiliTarget::Space { mid },
+        },
+        // 新版：/{mid}/lists/{id}?type=season|series，不带 type 时是合集
+        ["lists", id, ..] => match id.parse::<i64>() {
+            Ok(id) if query_param(query, "type") == Some("series") => BiliTarget::Series { mid, series_id: id },
+            Ok(id) => BiliTarget::Season { mid, season_id: id },
+            Err(_) => BiliTarget::Space { mid },
+        },
+        _ => BiliTarget::Space { mid },
+    };
+    Some(target)
+}
+
+/// `BV1xx411c7mD` 或 `av170001`，`query` 里的 `p=` 为分P
+fn video(id: &str, query: &str) -> Option<BiliTarget> {
+    let prefix = id.get(..2)?;
+    let (aid, bvid) = if prefix.eq_ignore_ascii_case("bv") {
+        (bv_to_av(id)?, normalize_bvid(id)?)
+    } else if prefix.eq_ignore_ascii_case("av") {
+        let aid = id[2..].parse().ok()?;
+        (aid, av_to_bv(aid)?)
+    } else {
+        return None;
+    };
+    let page = query_param(query, "p").and_then(|p| p.parse().ok()).filter(|p| *p > 0);
+    Some(BiliTarget::Video { aid, bvid, page })
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 已知的 av/BV 对照
+    const PAIRS: &[(i64, &str)] = &[
+        (1, "BV1xx411c7mQ"),
+        (2, "BV1xx411c7mD"),
+        (10001, "BV1bx411c7us"),
+        (170001, "BV17x411w7KC"),
+        (1054803170, "BV1mH4y1u7UA"),
+        (111298867365120, "BV1L9Uoa9EUx"),
+        ((1 << 51) - 1, "BV1aPPTfmvQq"),
+    ];
+
+    #[test]
+    fn converts_known_pairs() {
+        for &(aid, bvid) in PAIRS {
+            assert_eq!(av_to_bv(aid).as_deref(), Some(bvid), "av{}", aid);
+            assert_eq!(bv_to_av(bvid), Some(aid), "{}", bvid);
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for aid in [3, 99, 12345, 80433022, 999999999, 1 << 40, (1 << 51) - 2] {
+            let bvid = av_to_bv(aid).unwrap();
+            assert_eq!(bv_to_av(&bvid), Some(aid), "{}", bvid);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ids() {
+        assert_eq!(av_to_bv(0), None);
+        assert_eq!(av_to_bv(-5), None);
+        assert_eq!(av_to_bv(1 << 51), None);
+        for bvid in ["", "BV", "BV17x411w7K", "BV17x411w7KCC", "XX17x411w7KC", "BV17x411w7K0", "BV17x411w7Kl", "BV1中x411w7K"] {
+            assert_eq!(bv_to_av(bvid), None, "{}", bvid);
+        }
+        // 小写前缀也能识别，规范化后为大写
+        assert_eq!(bv_to_av("bv17x411w7KC"), Some(170001));
+        assert_eq!(normalize_bvid("bv17x411w7KC").as_deref(), Some("BV17x411w7KC"));
+    }
+
+    fn video(aid: i64, bvid: &str, page: Option<u32>) -> Option<BiliTarget> {
+        Some(BiliTarget::Video { aid, bvid: bvid.to_string(), page })
+    }
+
+    #[test]
+    fn parses_links() {
+        let season = |mid, season_id| Some(BiliTarget::Season { mid, season_id });
+        let series = |mid, series_id| Some(BiliTarget::Series { mid, series_id });
+        let space = |mid| Some(BiliTarget::Space { mid });
+        let live = |room_id| Some(BiliTarget::Live { room_id });
+        let short = |url: &str| Some(BiliTarget::ShortLink { url: url.to_string() });
+
+        let cases: &[(&str, Option<BiliTarget>)] = &[
+            // 裸编号
+            ("BV17x411w7KC", video(170001, "BV17x411w7KC", None)),
+            ("bv17x411w7KC", video(170001, "BV17x411w7KC", None)),
+            ("av170001", video(170001, "BV17x411w7KC", None)),
+            ("AV2", video(2, "BV1xx411c7mD", None)),
+            ("  BV1xx411c7mD \n", video(2, "BV1xx411c7mD", None)),
+            // 视频链接
+            ("https://www.bilibili.com/video/BV17x411w7KC", video(170001, "BV17x411w7KC", None)),
+            ("https://www.bilibili.com/video/BV17x411w7KC/", video(170001, "BV17x411w7KC", None)),
+            ("http://www.bilibili.com/video/av170001/", video(170001, "BV17x411w7KC", None)),
+            ("www.bilibili.com/video/BV1xx411c7mD?p=3", video(2, "BV1xx411c7mD", Some(3))),
+            ("bilibili.com/video/BV1xx411c7mD", video(2, "BV1xx411c7mD", None)),
+            (
+                "https://www.bilibili.com/video/BV1mH4y1u7UA/?spm_id_from=333.1007&p=2&vd_source=abc#reply123",
+                video(1054803170, "BV1mH4y1u7UA", Some(2)),
+            ),
+            ("https://www.bilibili.com/video/BV1xx411c7mD?p=0", video(2, "BV1xx411c7mD", None)),
+            ("https://WWW.BILIBILI.COM/video/BV1xx411c7mD", video(2, "BV1xx411c7mD", None)),
+            ("https://www.bilibili.com/s/video/BV1L9Uoa9EUx", video(111298867365120, "BV1L9Uoa9EUx", None)),
+            // 手机版
+            ("https://m.bilibili.com/video/BV17x411w7KC", video(170001, "BV17x411w7KC", None)),
+            ("https://m.bilibili.com/video/av2?share_medium=android", video(2, "BV1xx411c7mD", None)),
+            ("https://m.bilibili.com/space/3461574394120551", space(3461574394120551)),
+            // 用户空间
+            ("https://space.bilibili.com/3461574394120551", space(3461574394120551)),
+            ("https://space.bilibili.com/3461574394120551/", space(3461574394120551)),
+            ("space.bilibili.com/2/video", space(2)),
+            ("https://space.bilibili.com/2/upload/video?tid=0", space(2)),
+            ("https://space.bilibili.com/2?spm_id_from=333.788", space(2)),
+            // 合集与系列
+            ("https://space.bilibili.com/2/channel/collectiondetail?sid=123", season(2, 123)),
+            ("https://space.bilibili.com/2/channel/collectiondetail?sid=123&ctype=0", season(2, 123)),
+            ("https://space.bilibili.com/2/channel/collectiondetail", space(2)),
+            ("https://space.bilibili.com/2/channel/seriesdetail?sid=456", series(2, 456)),
+            ("https://space.bilibili.com/2/lists/789?type=season", season(2, 789)),
+            ("https://space.bilibili.com/2/lists/789", season(2, 789)),
+            ("https://space.bilibili.com/2/lists/456?type=series", series(2, 456)),
+            ("https://www.bilibili.com/list/2?sid=789&oid=170001", season(2, 789)),
+            // 直播间
+            ("https://live.bilibili.com/21452505", live(21452505)),
+            ("https://live.bilibili.com/21452505?broadcast_type=0", live(21452505)),
+            ("https://live.bilibili.com/h5/21452505", live(21452505)),
+            // 短链接
+            ("https://b23.tv/BV1xx411c7mD", short("https://b23.tv/BV1xx411c7mD")),
+            ("https://b23.tv/AbCd123", short("https://b23.tv/AbCd123")),
+            ("b23.tv/AbCd123", short("https://b23.tv/AbCd123")),
+            ("https://bili2233.cn/AbCd123", short("https://bili2233.cn/AbCd123")),
+            ("【测试视频-哔哩哔哩】 https://b23.tv/AbCd123 复制链接打开", short("https://b23.tv/AbCd123")),
+            // 无法识别
+            ("", None),
+            ("3461574394120551", None),
+            ("某个UP主", None),
+            ("BV17x411w7K", None),
+            ("av", None),
+            ("avocado", None),
+            ("https://space.bilibili.com/", None),
+            ("https://space.bilibili.com/abc", None),
+            ("https://www.bilibili.com/", None),
+            ("https://www.bilibili.com/bangumi/play/ep123", None),
+            ("https://live.bilibili.com/", None),
+            ("https://b23.tv/", None),
+            ("https://example.com/video/BV17x411w7KC", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(&parse(input), expected, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, SecondsFormat};
+use serde_json::{json, Value};
+
+use super::session::mask_refresh_csrf;
+use crate::utils::logging::redact;
+
+/// 网络记录最多保留的请求数
+const CAPACITY: usize = 200;
+/// 单个响应体最多保存的字节数
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// 一次请求和它的响应；保存前已经遮盖了 Cookie 和令牌
+#[derive(Clone, Debug)]
+pub struct NetworkEntry {
+    pub id: u64,
+    pub started_at: DateTime<Local>,
+    pub method: String,
+    /// 包含查询参数的完整地址
+    pub url: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    /// 没有收到响应时为 `None`
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub response_headers: Vec<(String, String)>,
+    pub content_type: Option<String>,
+    /// 文本响应体；二进制内容（protobuf 弹幕、压缩的 XML）不保存
+    pub response_body: Option<String>,
+    pub response_size: usize,
+    /// 标准响应里的 `code` 和 `message`
+    pub bili_code: Option<i64>,
+    pub bili_message: Option<String>,
+    /// 网络错误
+    pub error: Option<String>,
+}
+
+/// 记录请求时需要的原始内容，[`NetworkLog::record`] 负责遮盖和截断
+pub struct Exchange<'a> {
+    pub started_at: DateTime<Local>,
+    pub method: &'a str,
+    pub url: &'a str,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<&'a [u8]>,
+    pub latency: Duration,
+    /// 收到的响应，网络错误时为错误信息
+    pub response: Result<ResponseParts<'a>, String>,
+}
+
+pub struct ResponseParts<'a> {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: &'a [u8],
+}
+
+/// API 层发出的请求记录，超过容量时丢弃最早的记录
+pub struct NetworkLog {
+    next_id: AtomicU64,
+    entries: Mutex<VecDeque<NetworkEntry>>,
+}
+
+/// 全局网络记录
+pub fn network_log() -> &'static NetworkLog {
+    static LOG: OnceLock<NetworkLog> = OnceLock::new();
+    LOG.get_or_init(|| NetworkLog {
+        next_id: AtomicU64::new(1),
+        entries: Mutex::new(VecDeque::with_capacity(CAPACITY)),
+    })
+}
+
+impl NetworkLog {
+    pub fn record(&self, exchange: Exchange<'_>) {
+        let redact_pairs = |pairs: Vec<(String, String)>| {
+            pairs
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = redact(&value).into_owned();
+                    (name, value)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (status, response_headers, body, error) = match exchange.response {
+            Ok(r) => (Some(r.status), redact_pairs(r.headers), Some(r.body), None),
+            Err(e) => (None, Vec::new(), None, Some(redact(&e).into_owned())),
+        };
+        let content_type = response_headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.clone());
+        let json: Option<Value> = body.and_then(|b| serde_json::from_slice(b).ok());
+
+        let entry = NetworkEntry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            started_at: exchange.started_at,
+            method: exchange.method.to_string(),
+            url: redact(exchange.url).into_owned(),
+            request_headers: redact_pairs(exchange.request_headers),
+            request_body: exchange.request_body.and_then(text_body),
+            status,
+            latency: exchange.latency,
+            response_headers,
+            content_type,
+            response_body: body.and_then(text_body),
+            response_size: body.map_or(0, <[u8]>::len),
+            bili_code: json.as_ref().and_then(|v| v.get("code")).and_then(Value::as_i64),
+            bili_message: json
+                .as_ref()
+                .and_then(|v| v.get("message"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            error,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// 全部记录，按时间顺序排列
+    pub fn entries(&self) -> Vec<NetworkEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// 最新一条记录的编号，没有记录时为 0
+    pub fn last_id(&self) -> u64 {
+        self.entries.lock().unwrap().back().map_or(0, |e| e.id)
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// 文本内容遮盖后保存，过长时截断；不是 UTF-8 的内容视为二进制，不保存
+fn text_body(bytes: &[u8]) -> Option<String> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let truncated = &bytes[..bytes.len().min(MAX_BODY_BYTES)];
+    let text = match std::str::from_utf8(truncated) {
+        Ok(text) => text,
+        // 截断位置可能落在多字节字符中间
+        Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => {
+            std::str::from_utf8(&truncated[..e.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+    Some(redact(&mask_refresh_csrf(text)).into_owned())
+}
+
+impl NetworkEntry {
+    /// 格式化后的 JSON 响应体，不是 JSON 时原样返回
+    pub fn pretty_body(&self) -> Option<String> {
+        let body = self.response_body.as_deref()?;
+        match serde_json::from_str::<Value>(body) {
+            Ok(value) => serde_json::to_string_pretty(&value).ok(),
+            Err(_) => Some(body.to_string()),
+        }
+    }
+
+    /// 等价的 curl 命令；被遮盖的值需要自己替换成真实的值
+    pub fn to_curl(&self) -> String {
+        let mut parts = vec!["curl".to_string()];
+        if self.method != "GET" {
+            parts.push(format!("-X {}", self.method));
+        }
+        parts.push(shell_quote(&self.url));
+        for (name, value) in &self.request_headers {
+            parts.push(format!("-H {}", shell_quote(&format!("{}: {}", name, value))));
+        }
+        if let Some(body) = &self.request_body {
+            parts.push(format!("--data-raw {}", shell_quote(body)));
+        }
+        parts.join(" \\\n  ")
+    }
+
+    fn to_har(&self) -> Value {
+        let headers = |pairs: &[(String, String)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect::<Vec<_>>()
+        };
+        let query: Vec<Value> = reqwest::Url::parse(&self.url)
+            .map(|url| {
+                url.query_pairs()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let latency_ms = self.latency.as_secs_f64() * 1000.0;
+
+        let mut request = json!({
+            "method": self.method,
+            "url": self.url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": headers(&self.request_headers),
+            "queryString": query,
+            "headersSize": -1,
+            "bodySize": self.request_body.as_ref().map_or(0, |b| b.len()),
+        });
+        if let Some(body) = &self.request_body {
+            let mime = self
+                .request_headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                .map_or("application/x-www-form-urlencoded", |(_, v)| v.as_str());
+            request["postData"] = json!({ "mimeType": mime, "text": body });
+        }
+
+        let mut content = json!({
+            "size": self.response_size,
+            "mimeType": self.content_type.clone().unwrap_or_default(),
+        });
+        if let Some(body) = &self.response_body {
+            content["text"] = json!(body);
+        }
+        let status = self.status.unwrap_or(0);
+        let mut entry = json!({
+            "startedDateTime": self.started_at.to_rfc3339_opts(SecondsFormat::Millis, false),
+            "time": latency_ms,
+            "request": request,
+            "response": {
+                "status": status,
+                "statusText": reqwest::StatusCode::from_u16(status)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or(""),
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": headers(&self.response_headers),
+                "content": content,
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": self.response_size,
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": latency_ms, "receive": 0 },
+        });
+        if let Some(code) = self.bili_code {
+            entry["_biliCode"] = json!(code);
+        }
+        if let Some(error) = &self.error {
+            entry["_error"] = json!(error);
+        }
+        entry
+    }
+}
+
+/// 导出为 HAR 1.2，可以用浏览器开发者工具打开
+pub fn to_har(entries: &[NetworkEntry]) -> Value {
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+            "entries": entries.iter().map(NetworkEntry::to_har).collect::<Vec<_>>(),
+        }
+    })
+}
+
+/// 用单引号包起来，内部的单引号改写成 `'\''`
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange<'a>(url: &'a str, body: &'a [u8]) -> Exchange<'a> {
+        Exchange {
+            started_at: Local::now(),
+            method: "GET",
+            url,
+            request_headers: vec![
+                ("user-agent".to_string(), "test".to_string()),
+                ("cookie".to_string(), "SESSDATA=secret-sess; bili_jct=secret-jct; buvid3=abc".to_string()),
+            ],
+            request_body: None,
+            latency: Duration::from_millis(42),
+            response: Ok(ResponseParts {
+                status: 200,
+                headers: vec![("content-type".to_string(), "application/json".to_string())],
+                body,
+            }),
+        }
+    }
+
+    fn last(log: &NetworkLog) -> NetworkEntry {
+        log.entries().pop().unwrap()
+    }
+
+    fn new_log() -> NetworkLog {
+        NetworkLog {
+            next_id: AtomicU64::new(1),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    #[test]
+    fn records_redacted_entry_with_bili_code() {
+        let log = new_log();
+        log.record(exchange(
+            "https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key=secret-key",
+            br#"{"code":-101,"message":"not logged in","data":{"refresh_token":"secret-token"}}"#,
+        ));
+        let entry = last(&log);
+        assert_eq!(entry.status, Some(200));
+        assert_eq!(entry.bili_code, Some(-101));
+        assert_eq!(entry.bili_message.as_deref(), Some("not logged in"));
+        assert_eq!(entry.content_type.as_deref(), Some("application/json"));
+
+        let all = format!("{:?}", entry);
+        for secret in ["secret-sess", "secret-jct", "secret-key", "secret-token"] {
+            assert!(!all.contains(secret), "{secret} leaked: {all}");
+        }
+        assert!(all.contains("buvid3=abc"));
+    }
+
+    #[test]
+    fn masks_refresh_csrf_in_correspond_page() {
+        let log = new_log();
+        log.record(exchange(
+            "https://www.bilibili.com/correspond/1/abcdef",
+            br#"<html><div id="1-name">b0cc8411ded2f9db2cff2edb3123acac</div></html>"#,
+        ));
+        let body = last(&log).response_body.unwrap();
+        assert_eq!(body, r#"<html><div id="1-name">***</div></html>"#);
+    }
+
+    #[test]
+    fn drops_oldest_entries_and_binary_bodies() {
+        let log = new_log();
+        for _ in 0..CAPACITY + 5 {
+            log.record(exchange("https://api.bilibili.com/x/v2/dm/wbi/web/seg.so", &[0x0a, 0xff, 0x00]));
+        }
+        let entries = log.entries();
+        assert_eq!(entries.len(), CAPACITY);
+        assert_eq!(entries[0].id, 6);
+        assert_eq!(log.last_id(), (CAPACITY + 5) as u64);
+        assert_eq!(entries[0].response_body, None);
+        assert_eq!(entries[0].response_size, 3);
+
+        log.clear();
+        assert_eq!(log.last_id(), 0);
+    }
+
+    #[test]
+    fn records_transport_errors() {
+        let log = new_log();
+        log.record(Exchange {
+            response: Err("connection refused".to_string()),
+            ..exchange("https://api.bilibili.com/x/web-interface/nav", b"")
+        });
+        let entry = last(&log);
+        assert_eq!(entry.status, None);
+        assert_eq!(entry.error.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn curl_quotes_and_keeps_masking() {
+        let log = new_log();
+        log.record(Exchange {
+            method: "POST",
+            request_body: Some(b"csrf=secret-csrf&msg=it's"),
+            ..exchange("https://api.bilibili.com/x/test?a=1", b"{}")
+        });
+        let curl = last(&log).to_curl();
+        assert!(curl.starts_with("curl \\\n  -X POST \\\n  'https://api.bilibili.com/x/test?a=1'"), "{curl}");
+        assert!(curl.contains("-H 'user-agent: test'"), "{curl}");
+        assert!(curl.contains(r"it'\''s"), "{curl}");
+        assert!(!curl.contains("secret"), "{curl}");
+    }
+
+    #[test]
+    fn exports_har() {
+        let log = new_log();
+        log.record(exchange("https://api.bilibili.com/x/test?bvid=BV1xx&page=2", br#"{"code":0}"#));
+        let har = to_har(&log.entries());
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(entry["request"]["method"], "GET");
+        assert_eq!(entry["request"]["queryString"][1]["name"], "page");
+        assert_eq!(entry["response"]["status"], 200);
+        assert_eq!(entry["response"]["statusText"], "OK");
+        assert_eq!(entry["response"]["content"]["text"], r#"{"code":0}"#);
+        assert_eq!(entry["time"], 42.0);
+        assert_eq!(entry["_biliCode"], 0);
+    }
+}
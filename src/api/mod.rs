@@ -1 +1,10 @@
 pub mod bilibili;
+pub mod client;
+pub mod danmaku;
+pub mod error;
+pub mod ids;
+pub mod inspector;
+pub mod policy;
+pub mod search;
+pub mod session;
+pub mod wbi;
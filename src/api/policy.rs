@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::error::BiliError;
+
+/// 请求策略：按 host 限速、失败重试和最大并发数
+#[derive(Debug, Clone)]
+pub struct RequestPolicy {
+    /// 每个 host 每秒最多发出的请求数
+    pub rate_per_sec: f64,
+    /// 令牌桶容量，允许短时间内突发的请求数
+    pub burst: u32,
+    /// 失败后最多重试的次数（不含第一次请求）
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub base_delay: Duration,
+    /// 单次等待时间的上限
+    pub max_delay: Duration,
+    /// 同时进行中的请求数上限
+    pub max_concurrency: usize,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            rate_per_sec: 4.0,
+            burst: 4,
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            max_concurrency: 4,
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// 第 `attempt` 次重试（从 0 开始）前的等待时间：指数增长，上限为 `max_delay`，
+    /// 再在后一半范围内随机抖动，避免多个请求同时重试
+    pub fn backoff_delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32 << attempt.min(16));
+        let capped = exp.min(self.max_delay);
+        capped.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+    }
+
+    /// 网络错误、HTTP 412 和风控/限流错误码值得重试，其余错误重试也不会成功
+    pub fn is_retryable(err: &BiliError) -> bool {
+        matches!(err, BiliError::Transport(_)) || err.should_back_off()
+    }
+}
+
+/// 令牌桶：以 `rate` 每秒的速度补充令牌，最多存 `capacity` 个
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: f64, capacity: u32, now: Instant) -> Self {
+        let capacity = capacity.max(1) as f64;
+        Self {
+            capacity,
+            rate: rate.max(f64::MIN_POSITIVE),
+            tokens: capacity,
+            updated_at: now,
+        }
+    }
+
+    /// 取一个令牌；令牌不足时返回还需要等待的时间
+    pub fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// 按 host 分别限速
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: u32,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(policy: &RequestPolicy) -> Self {
+        Self {
+            rate: policy.rate_per_sec,
+            burst: policy.burst,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 等到 `host` 有可用的令牌为止
+    pub async fn acquire(&self, host: &str) {
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = buckets
+                    .entry(host.to_string())
+                    .or_insert_with(|| TokenBucket::new(self.rate, self.burst, now));
+                match bucket.try_take(now) {
+                    Ok(()) => return,
+                    Err(wait) => wait,
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RequestPolicy {
+        RequestPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            ..RequestPolicy::default()
+        }
+    }
+
+    /// 四舍五入到毫秒，避免浮点误差
+    fn ms(d: Duration) -> u64 {
+        (d.as_secs_f64() * 1000.0).round() as u64
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_caps() {
+        let policy = policy();
+        assert_eq!(ms(policy.backoff_delay(0, 1.0)), 100);
+        assert_eq!(ms(policy.backoff_delay(1, 1.0)), 200);
+        assert_eq!(ms(policy.backoff_delay(2, 1.0)), 400);
+        assert_eq!(ms(policy.backoff_delay(5, 1.0)), 1000);
+        assert_eq!(ms(policy.backoff_delay(40, 1.0)), 1000);
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_upper_half() {
+        let policy = policy();
+        assert_eq!(ms(policy.backoff_delay(1, 0.0)), 100);
+        assert_eq!(ms(policy.backoff_delay(1, 0.5)), 150);
+        // 超出范围的抖动值会被截断
+        assert_eq!(ms(policy.backoff_delay(1, 7.0)), 200);
+    }
+
+    #[test]
+    fn retryable_errors() {
+        assert!(RequestPolicy::is_retryable(&BiliError::RiskControl { code: -412 }));
+        assert!(RequestPolicy::is_retryable(&BiliError::RateLimited { code: -509 }));
+        assert!(!RequestPolicy::is_retryable(&BiliError::NotLoggedIn));
+        assert!(!RequestPolicy::is_retryable(&BiliError::from_code(-404, "啥都木有")));
+        assert!(!RequestPolicy::is_retryable(&BiliError::Decode("bad json".to_string())));
+    }
+
+    #[test]
+    fn token_bucket_allows_burst_then_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 3, start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert_eq!(bucket.try_take(start).map_err(ms), Err(500));
+
+        // 0.25 秒补充半个令牌，还差半个
+        let later = start + Duration::from_millis(250);
+        assert_eq!(bucket.try_take(later).map_err(ms), Err(250));
+        let later = start + Duration::from_millis(500);
+        assert!(bucket.try_take(later).is_ok());
+
+        // 空闲很久也不会超过容量
+        let idle = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(bucket.try_take(idle).is_ok());
+        }
+        assert!(bucket.try_take(idle).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use super::bilibili::search_users;
+use super::client::BiliClient;
+use super::error::{BiliError, Result};
+use super::ids::{self, BiliTarget};
+
+/// 搜索框最终要打开的内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTarget {
+    /// UP 主的合集和系列
+    Creator { mid: i64 },
+    /// 单个合集
+    Season { mid: i64, season_id: i64 },
+    /// 单个系列
+    Series { mid: i64, series_id: i64 },
+    /// 单个视频
+    Video { bvid: String },
+    /// 直播间（暂不支持打开）
+    Live { room_id: i64 },
+}
+
+/// 搜索框输入的解析结果，短链接和昵称还需要请求接口才能确定目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchQuery {
+    Target(SearchTarget),
+    /// b23.tv 短链接
+    ShortLink(String),
+    /// UP 主昵称
+    Nickname(String),
+    /// 无法打开的链接（番剧、专栏等），不拿去搜索昵称
+    UnsupportedLink(String),
+}
+
+/// 解析搜索框输入：UID、[`ids::parse`] 能识别的链接和编号，其余不像链接的按昵称处理
+pub fn parse_query(input: &str) -> Option<SearchQuery> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    // 纯数字按 UID 处理（请求里约定的输入方式）；av 号需要带上 `av` 前缀
+    if input.bytes().all(|b| b.is_ascii_digit()) {
+        if let Some(mid) = input.parse::<i64>().ok().filter(|&mid| mid > 0) {
+            return Some(SearchQuery::Target(SearchTarget::Creator { mid }));
+        }
+    }
+
+    match ids::parse(input) {
+        Some(BiliTarget::ShortLink { url }) => Some(SearchQuery::ShortLink(url)),
+        Some(target) => search_target(target).map(SearchQuery::Target),
+        None => match find_link(input) {
+            Some(link) => Some(SearchQuery::UnsupportedLink(link.to_string())),
+            None => Some(SearchQuery::Nickname(input.to_string())),
+        },
+    }
+}
+
+/// 输入中看起来像链接的部分：带协议头，或者是 bilibili.com、b23.tv 域名下的地址
+fn find_link(input: &str) -> Option<&str> {
+    input.split_whitespace().find(|token| {
+        let token = token.to_ascii_lowercase();
+        token.contains("://") || token.contains("bilibili.com") || token.contains("b23.tv")
+    })
+}
+
+/// 短链接没有直接对应的目标，返回 `None`
+fn search_target(target: BiliTarget) -> Option<SearchTarget> {
+    Some(match target {
+        BiliTarget::Video { bvid, .. } => SearchTarget::Video { bvid },
+        BiliTarget::Space { mid } => SearchTarget::Creator { mid },
+        BiliTarget::Season { mid, season_id } => SearchTarget::Season { mid, season_id },
+        BiliTarget::Series { mid, series_id } => SearchTarget::Series { mid, series_id },
+        BiliTarget::Live { room_id } => SearchTarget::Live { room_id },
+        BiliTarget::ShortLink { .. } => return None,
+    })
+}
+
+/// 确定搜索目标：短链接跟随跳转后再解析，昵称通过用户搜索取最匹配的 UP 主
+pub async fn resolve(client: &BiliClient, query: SearchQuery) -> Result<SearchTarget> {
+    match query {
+        SearchQuery::Target(target) => Ok(target),
+        SearchQuery::ShortLink(url) => {
+            let response = client.get(url.clone()).send().await?;
+            let location = response.url().to_string();
+            info!("🔗 短链接 {} 跳转到 {}", url, location);
+            ids::parse(&location).and_then(search_target).ok_or_else(|| BiliError::NotFound {
+                code: -404,
+                message: format!("无法识别短链接指向的页面: {}", location),
+            })
+        }
+        SearchQuery::Nickname(name) => {
+            let users = search_users(client, &name).await?;
+            let user = users
+                .iter()
+                .find(|u| u.uname.eq_ignore_ascii_case(&name))
+                .or_else(|| users.first())
+                .ok_or_else(|| BiliError::NotFound {
+                    code: -404,
+                    message: format!("没有找到用户: {}", name),
+                })?;
+            info!("👤 昵称 {} 匹配到 {} (UID: {})", name, user.uname, user.mid);
+            Ok(SearchTarget::Creator { mid: user.mid })
+        }
+        SearchQuery::UnsupportedLink(url) => Err(BiliError::NotFound {
+            code: -404,
+            message: format!("暂不支持打开这个链接: {}", url),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_input() {
+        let target = |t| Some(SearchQuery::Target(t));
+        let creator = |mid| target(SearchTarget::Creator { mid });
+        let video = |bvid: &str| target(SearchTarget::Video { bvid: bvid.to_string() });
+        let nickname = |name: &str| Some(SearchQuery::Nickname(name.to_string()));
+        let unsupported = |url: &str| Some(SearchQuery::UnsupportedLink(url.to_string()));
+
+        let cases: &[(&str, Option<SearchQuery>)] = &[
+            // 纯数字是 UID，不是 av 号
+            ("3461574394120551", creator(3461574394120551)),
+            (" 2 ", creator(2)),
+            ("170001", creator(170001)),
+            // 编号和视频链接
+            ("BV17x411w7KC", video("BV17x411w7KC")),
+            ("av170001", video("BV17x411w7KC")),
+            ("https://www.bilibili.com/video/BV1xx411c7mD?p=3", video("BV1xx411c7mD")),
+            // 用户空间、合集、系列
+            ("https://space.bilibili.com/2", creator(2)),
+            ("space.bilibili.com/2/upload/video", creator(2)),
+            (
+                "https://space.bilibili.com/2/channel/collectiondetail?sid=123",
+                target(SearchTarget::Season { mid: 2, season_id: 123 }),
+            ),
+            (
+                "https://space.bilibili.com/2/lists/456?type=series",
+                target(SearchTarget::Series { mid: 2, series_id: 456 }),
+            ),
+            ("https://live.bilibili.com/21452505", target(SearchTarget::Live { room_id: 21452505 })),
+            // 短链接需要请求后才能确定
+            ("https://b23.tv/AbCd123", Some(SearchQuery::ShortLink("https://b23.tv/AbCd123".to_string()))),
+            ("【分享】 https://b23.tv/AbCd123 复制链接打开", Some(SearchQuery::ShortLink("https://b23.tv/AbCd123".to_string()))),
+            // 其余按昵称搜索
+            ("某个UP主", nickname("某个UP主")),
+            ("  老番茄 ", nickname("老番茄")),
+            ("0", nickname("0")),
+            ("-5", nickname("-5")),
+            ("+5", nickname("+5")),
+            ("99999999999999999999", nickname("99999999999999999999")),
+            // 识别不了的链接不按昵称搜索
+            ("https://www.bilibili.com/bangumi/play/ep123", unsupported("https://www.bilibili.com/bangumi/play/ep123")),
+            ("www.bilibili.com/read/cv123456", unsupported("www.bilibili.com/read/cv123456")),
+            ("看这个 https://example.com/page", unsupported("https://example.com/page")),
+            // 空输入不搜索
+            ("", None),
+            ("   ", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(&parse_query(input), expected, "{:?}", input);
+        }
+    }
+
+    #[tokio::test]
+    async fn resolved_targets_need_no_request() {
+        let client = BiliClient::new(Default::default());
+        let target = SearchTarget::Season { mid: 2, season_id: 123 };
+        assert_eq!(resolve(&client, SearchQuery::Target(target.clone())).await.unwrap(), target);
+        let link = SearchQuery::UnsupportedLink("https://www.bilibili.com/read/cv1".to_string());
+        assert!(matches!(resolve(&client, link).await, Err(BiliError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,453 @@
+use std::borrow::Cow;
+
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Oaep, RsaPublicKey};
+use serde::Deserialize;
+
+use super::bilibili::ApiResponse;
+use super::client::BiliClient;
+use super::error::{BiliError, Result};
+use crate::utils::logging::MASK;
+
+/// 生成 correspond 路径用的公钥
+const CORRESPOND_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDLgd2OAkcGVtoE3ThUREbio0Eg
+Uc/prcajMKXvkCKFCWhJYJcLkcM2DKKcSeFpD/j6Boy538YXnR6VhcuUJOhH2x71
+nzPjfdTcqMz7djHum0qSZA0AyCBDABUqCrfNgCiJ00Ra7GmRj+YCK1NJEuewlb40
+JNrRuoEUXpabUzGB8QIDAQAB
+-----END PUBLIC KEY-----";
+
+/// correspond 页面里存放 `refresh_csrf` 的元素
+const REFRESH_CSRF_MARKER: &str = r#"<div id="1-name">"#;
+
+/// 启动时检查登录态的结果
+#[derive(Debug)]
+pub enum SessionStatus {
+    /// 登录有效，`refresh_token` 不为空说明刚刷新过 Cookie，需要保存新的令牌和 Cookie；
+    /// 刷新后没能获取用户信息时 `nav` 为空
+    Valid { nav: Option<NavInfo>, refresh_token: Option<String> },
+    /// 登录已失效且无法刷新，需要重新扫码
+    Expired,
+}
+
+/// nav 接口里的当前用户信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct NavInfo {
+    #[serde(rename = "isLogin", default)]
+    pub is_login: bool,
+    pub uname: Option<String>,
+    pub face: Option<String>,
+    pub pendant: Option<NavPendant>,
+}
+
+/// 头像挂件
+#[derive(Debug, Clone, Deserialize)]
+pub struct NavPendant {
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CookieInfo {
+    refresh: bool,
+    timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RefreshData {
+    refresh_token: String,
+}
+
+/// 通过 nav 接口检查登录态；服务端要求刷新或登录已失效时，有 `refresh_token` 就先刷新 Cookie 再检查一次
+///
+/// 只有确认未登录时才返回 [`SessionStatus::Expired`]，网络错误原样返回，不会把用户登出。
+/// 换到新 Cookie 之后旧的 `refresh_token` 已经失效，之后的请求出错也返回新的令牌。
+pub async fn check(client: &BiliClient, refresh_token: Option<&str>) -> Result<SessionStatus> {
+    let nav = match fetch_nav(client).await {
+        Ok(nav) => Some(nav),
+        Err(BiliError::NotLoggedIn) => None,
+        Err(e) => return Err(e),
+    };
+
+    // 刷新需要旧的 bili_jct 作为 csrf
+    let refresh_token = refresh_token
+        .filter(|t| !t.is_empty())
+        .filter(|_| client.cookie_jar().get("bili_jct").is_some());
+    let Some(refresh_token) = refresh_token else {
+        return Ok(match nav {
+            Some(nav) => SessionStatus::Valid { nav: Some(nav), refresh_token: None },
+            None => SessionStatus::Expired,
+        });
+    };
+
+    if let Some(nav) = nav {
+        // 登录有效时查询失败不影响使用，下次启动再检查
+        let info = match cookie_info(client).await {
+            Ok(info) => info,
+            Err(e) => {
+                warn!("⚠️ 查询是否需要刷新 Cookie 失败: {}", e);
+                return Ok(SessionStatus::Valid { nav: Some(nav), refresh_token: None });
+            }
+        };
+        if !info.refresh {
+            return Ok(SessionStatus::Valid { nav: Some(nav), refresh_token: None });
+        }
+        info!("🔄 服务端要求刷新 Cookie");
+        let new_token = refresh(client, refresh_token, info.timestamp).await?;
+        let nav = match fetch_nav(client).await {
+            Ok(nav) => nav,
+            Err(e) => {
+                warn!("⚠️ 刷新后获取用户信息失败，使用刷新前的信息: {}", e);
+                nav
+            }
+        };
+        return Ok(SessionStatus::Valid { nav: Some(nav), refresh_token: Some(new_token) });
+    }
+
+    // SESSDATA 已经失效，用 refresh_token 试着换一套新的 Cookie
+    info!("🔄 登录已失效，尝试刷新 Cookie");
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    match refresh(client, refresh_token, timestamp).await {
+        Ok(new_token) => {
+            let nav = fetch_nav(client)
+                .await
+                .inspect_err(|e| warn!("⚠️ 刷新后获取用户信息失败: {}", e))
+                .ok();
+            Ok(SessionStatus::Valid { nav, refresh_token: Some(new_token) })
+        }
+        Err(e) if is_rejected(&e) => {
+            warn!("⚠️ 刷新 Cookie 失败: {}", e);
+            Ok(SessionStatus::Expired)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 当前用户信息，未登录时返回 [`BiliError::NotLoggedIn`]
+pub async fn fetch_nav(client: &BiliClient) -> Result<NavInfo> {
+    let nav: NavInfo = client.get(client.api_url("/x/web-interface/nav")).data().await?;
+    if nav.is_login {
+        Ok(nav)
+    } else {
+        Err(BiliError::NotLoggedIn)
+    }
+}
+
+/// 退出登录，让服务端作废当前的 SESSDATA；本地的 Cookie 由调用方清除
+pub async fn logout(client: &BiliClient) -> Result<()> {
+    let csrf = csrf(client)?;
+    let response: ApiResponse<serde_json::Value> = client
+        .post(client.passport_url("/login/exit/v2"))
+        .form(&[("biliCSRF", csrf.as_str())])
+        .json()
+        .await?;
+    match response.code {
+        0 => Ok(()),
+        code => Err(BiliError::from_code(code, response.message.unwrap_or_default())),
+    }
+}
+
+/// 服务端是否要求刷新 Cookie
+async fn cookie_info(client: &BiliClient) -> Result<CookieInfo> {
+    let csrf = csrf(client)?;
+    client
+        .get(client.passport_url("/x/passport-login/web/cookie/info"))
+        .query(&[("csrf", &csrf)])
+        .data()
+        .await
+}
+
+/// 刷新 Cookie：用 correspond 页面拿到 `refresh_csrf`，换取新的 Cookie（由共享客户端的 Cookie 保存）
+/// 和新的 `refresh_token`，最后用新的 `bili_jct` 确认刷新，让旧的 `refresh_token` 失效；
+/// 确认失败只记录日志，新的令牌照样返回
+async fn refresh(client: &BiliClient, refresh_token: &str, timestamp: i64) -> Result<String> {
+    let path = correspond_path(timestamp)?;
+    let html = client
+        .get(client.www_url(&format!("/correspond/1/{}", path)))
+        .send()
+        .await?
+        .text();
+    let refresh_csrf = parse_refresh_csrf(&html)
+        .ok_or_else(|| BiliError::Decode("correspond 页面中没有 refresh_csrf".to_string()))?;
+
+    let old_csrf = csrf(client)?;
+    let data: RefreshData = client
+        .post(client.passport_url("/x/passport-login/web/cookie/refresh"))
+        .form(&[
+            ("csrf", old_csrf.as_str()),
+            ("refresh_csrf", refresh_csrf.as_str()),
+            ("source", "main_web"),
+            ("refresh_token", refresh_token),
+        ])
+        .data()
+        .await?;
+
+    // 新 Cookie 已经生效，确认失败只影响旧令牌的作废
+    if let Err(e) = confirm_refresh(client, refresh_token).await {
+        warn!("⚠️ 确认刷新失败: {}", e);
+    }
+    info!("✅ Cookie 已刷新");
+    Ok(data.refresh_token)
+}
+
+/// 用新的 `bili_jct` 和旧的 `refresh_token` 确认刷新
+async fn confirm_refresh(client: &BiliClient, old_refresh_token: &str) -> Result<()> {
+    let new_csrf = csrf(client)?;
+    let confirm: ApiResponse<serde_json::Value> = client
+        .post(client.passport_url("/x/passport-login/web/confirm/refresh"))
+        .form(&[("csrf", new_csrf.as_str()), ("refresh_token", old_refresh_token)])
+        .json()
+        .await?;
+    match confirm.code {
+        0 => Ok(()),
+        code => Err(BiliError::from_code(code, confirm.message.unwrap_or_default())),
+    }
+}
+
+/// 用时间戳生成 correspond 页面的路径：RSA-OAEP(SHA-256) 加密 `refresh_{timestamp}` 后转成十六进制
+fn correspond_path(timestamp: i64) -> Result<String> {
+    let key = RsaPublicKey::from_public_key_pem(CORRESPOND_PUBLIC_KEY)
+        .map_err(|e| BiliError::Decode(format!("公钥解析失败: {}", e)))?;
+    let message = format!("refresh_{}", timestamp);
+    let encrypted = key
+        .encrypt(&mut rsa::rand_core::OsRng, Oaep::new::<sha2::Sha256>(), message.as_bytes())
+        .map_err(|e| BiliError::Decode(format!("生成 correspond 路径失败: {}", e)))?;
+    Ok(encrypted.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn parse_refresh_csrf(html: &str) -> Option<String> {
+    let (start, end) = refresh_csrf_span(html)?;
+    let value = html[start..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn refresh_csrf_span(html: &str) -> Option<(usize, usize)> {
+    let start = html.find(REFRESH_CSRF_MARKER)? + REFRESH_CSRF_MARKER.len();
+    let end = start + html[start..].find('<')?;
+    Some((start, end))
+}
+
+/// 遮盖 correspond 页面里的 `refresh_csrf`，网络记录保存响应体时使用；不是 correspond 页面时原样返回
+pub(crate) fn mask_refresh_csrf(html: &str) -> Cow<'_, str> {
+    match refresh_csrf_span(html) {
+        Some((start, end)) if end > start => Cow::Owned(format!("{}{}{}", &html[..start], MASK, &html[end..])),
+        _ => Cow::Borrowed(html),
+    }
+}
+
+fn csrf(client: &BiliClient) -> Result<String> {
+    client.cookie_jar().get("bili_jct").ok_or(BiliError::NotLoggedIn)
+}
+
+/// 刷新被服务端拒绝：未登录、csrf 校验失败（-111）或 `refresh_token` 与 Cookie 不匹配（86095）
+fn is_rejected(err: &BiliError) -> bool {
+    err.needs_login() || matches!(err.code(), Some(-111 | 86095)) || matches!(err, BiliError::Decode(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use crate::api::client::BiliClientConfig;
+
+    /// mock 服务器的行为
+    #[derive(Clone, Copy, Default)]
+    struct Mock {
+        /// cookie/refresh 返回的错误码
+        refresh_code: i32,
+        /// 为 false 时刷新成功后 nav 才返回已登录
+        logged_in: bool,
+        /// cookie/info 要求刷新；为 false 时不返回数据（查询失败）
+        needs_refresh: bool,
+        /// 刷新成功后 confirm/refresh 和 nav 都返回无法解析的响应
+        broken_after_refresh: bool,
+    }
+
+    /// 按路径返回响应的 mock 服务器，记录收到的请求行和请求体
+    async fn start_server(mock: Mock) -> (BiliClient, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let refreshed = Arc::new(Mutex::new(false));
+
+        let log = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { break };
+                let (log, refreshed) = (log.clone(), refreshed.clone());
+                tokio::spawn(async move {
+                    let request = read_request(&mut socket).await;
+                    let line = request.lines().next().unwrap_or_default().to_string();
+                    let body = request.split("\r\n\r\n").nth(1).unwrap_or_default();
+                    log.lock().unwrap().push(format!("{} {}", line, body));
+
+                    let mut headers = String::new();
+                    let refreshed_now = *refreshed.lock().unwrap();
+                    let broken = mock.broken_after_refresh && refreshed_now;
+                    let body = if broken && (line.contains("/x/web-interface/nav") || line.contains("/confirm/refresh")) {
+                        "<html>502 Bad Gateway</html>".to_string()
+                    } else if line.contains("/x/web-interface/nav") {
+                        if mock.logged_in || refreshed_now {
+                            r#"{"code":0,"data":{"isLogin":true,"uname":"tester"}}"#.to_string()
+                        } else {
+                            r#"{"code":-101,"message":"账号未登录","data":{"isLogin":false}}"#.to_string()
+                        }
+                    } else if line.contains("/cookie/info") && mock.needs_refresh {
+                        r#"{"code":0,"data":{"refresh":true,"timestamp":1684466082006}}"#.to_string()
+                    } else if line.contains("/correspond/1/") {
+                        r#"<html><div id="1-name">fresh-refresh-csrf</div></html>"#.to_string()
+                    } else if line.contains("/cookie/refresh") {
+                        if mock.refresh_code == 0 {
+                            *refreshed.lock().unwrap() = true;
+                            headers.push_str("Set-Cookie: SESSDATA=new-sess; Path=/\r\nSet-Cookie: bili_jct=new-jct; Path=/\r\n");
+                        }
+                        format!(r#"{{"code":{},"message":"","data":{{"refresh_token":"new-token"}}}}"#, mock.refresh_code)
+                    } else {
+                        r#"{"code":0,"message":"0"}"#.to_string()
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        headers,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        let client = BiliClient::new(BiliClientConfig::default().with_base_url(base));
+        client.cookie_jar().set_header("SESSDATA=old-sess; bili_jct=old-jct");
+        (client, requests)
+    }
+
+    /// 读完请求头和 Content-Length 指定长度的请求体
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap_or(0);
+            data.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&data).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    return text;
+                }
+            }
+            if n == 0 {
+                return text;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_expired_session() {
+        let (client, requests) = start_server(Mock::default()).await;
+
+        let status = check(&client, Some("old-token")).await.unwrap();
+        let SessionStatus::Valid { nav, refresh_token } = status else { panic!("{status:?}") };
+        assert_eq!(nav.and_then(|n| n.uname).as_deref(), Some("tester"));
+        assert_eq!(refresh_token.as_deref(), Some("new-token"));
+        assert_eq!(client.cookie_jar().get("SESSDATA").as_deref(), Some("new-sess"));
+
+        let requests = requests.lock().unwrap();
+        let find = |path: &str| requests.iter().find(|r| r.contains(path)).cloned().unwrap_or_default();
+        assert!(find("/cookie/refresh").ends_with(
+            "csrf=old-jct&refresh_csrf=fresh-refresh-csrf&source=main_web&refresh_token=old-token"
+        ));
+        // 确认刷新用新的 bili_jct 和旧的 refresh_token
+        assert!(find("/confirm/refresh").ends_with("csrf=new-jct&refresh_token=old-token"));
+    }
+
+    #[tokio::test]
+    async fn logout_sends_csrf() {
+        let (client, requests) = start_server(Mock::default()).await;
+        logout(&client).await.unwrap();
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /login/exit/v2 "), "{request}");
+        assert!(request.ends_with("biliCSRF=old-jct"), "{request}");
+
+        client.cookie_jar().clear();
+        assert!(matches!(logout(&client).await, Err(BiliError::NotLoggedIn)));
+    }
+
+    #[tokio::test]
+    async fn expires_when_refresh_is_rejected() {
+        let (client, _) = start_server(Mock { refresh_code: 86095, ..Mock::default() }).await;
+        assert!(matches!(check(&client, Some("old-token")).await.unwrap(), SessionStatus::Expired));
+
+        let (client, requests) = start_server(Mock::default()).await;
+        assert!(matches!(check(&client, None).await.unwrap(), SessionStatus::Expired));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn stays_valid_when_cookie_info_fails() {
+        let (client, requests) = start_server(Mock { logged_in: true, ..Mock::default() }).await;
+        let status = check(&client, Some("old-token")).await.unwrap();
+        assert!(matches!(status, SessionStatus::Valid { refresh_token: None, .. }), "{status:?}");
+        assert_eq!(client.cookie_jar().get("SESSDATA").as_deref(), Some("old-sess"));
+        assert!(!requests.lock().unwrap().iter().any(|r| r.contains("/cookie/refresh")));
+    }
+
+    #[tokio::test]
+    async fn keeps_new_token_when_requests_after_refresh_fail() {
+        // 登录已失效：刷新后 nav 也失败，没有用户信息
+        let (client, requests) = start_server(Mock { broken_after_refresh: true, ..Mock::default() }).await;
+        let status = check(&client, Some("old-token")).await.unwrap();
+        let SessionStatus::Valid { nav, refresh_token } = status else { panic!("{status:?}") };
+        assert!(nav.is_none());
+        assert_eq!(refresh_token.as_deref(), Some("new-token"));
+        assert_eq!(client.cookie_jar().get("SESSDATA").as_deref(), Some("new-sess"));
+        assert!(requests.lock().unwrap().iter().any(|r| r.contains("/confirm/refresh")));
+
+        // 服务端要求刷新：沿用刷新前的用户信息
+        let mock = Mock { logged_in: true, needs_refresh: true, broken_after_refresh: true, ..Mock::default() };
+        let (client, _) = start_server(mock).await;
+        let status = check(&client, Some("old-token")).await.unwrap();
+        let SessionStatus::Valid { nav, refresh_token } = status else { panic!("{status:?}") };
+        assert_eq!(nav.and_then(|n| n.uname).as_deref(), Some("tester"));
+        assert_eq!(refresh_token.as_deref(), Some("new-token"));
+        assert_eq!(client.cookie_jar().get("bili_jct").as_deref(), Some("new-jct"));
+    }
+
+    #[test]
+    fn correspond_path_is_hex_rsa_block() {
+        let path = correspond_path(1684466082006).unwrap();
+        // 1024 位密钥，密文 128 字节
+        assert_eq!(path.len(), 256);
+        assert!(path.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase()));
+        // OAEP 带随机填充，每次结果都不同
+        assert_ne!(path, correspond_path(1684466082006).unwrap());
+    }
+
+    #[test]
+    fn parses_refresh_csrf() {
+        let html = r#"<html><body><div id="1-name">b0cc8411ded2f9db2cff2edb3123acac</div><div id="2-name"></div></body></html>"#;
+        assert_eq!(parse_refresh_csrf(html).as_deref(), Some("b0cc8411ded2f9db2cff2edb3123acac"));
+        assert_eq!(parse_refresh_csrf(r#"<div id="1-name"></div>"#), None);
+        assert_eq!(parse_refresh_csrf("<html>404</html>"), None);
+
+        assert_eq!(
+            mask_refresh_csrf(html),
+            r#"<html><body><div id="1-name">***</div><div id="2-name"></div></body></html>"#
+        );
+        assert!(matches!(mask_refresh_csrf("<html>404</html>"), Cow::Borrowed(_)));
+        assert!(matches!(mask_refresh_csrf(r#"<div id="1-name"></div>"#), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn classifies_rejected_refresh() {
+        assert!(is_rejected(&BiliError::NotLoggedIn));
+        assert!(is_rejected(&BiliError::from_code(86095, "refresh_csrf 错误")));
+        assert!(is_rejected(&BiliError::from_code(-111, "csrf 校验失败")));
+        assert!(!is_rejected(&BiliError::RateLimited { code: -509 }));
+    }
+}
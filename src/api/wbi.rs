@@ -0,0 +1,204 @@
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use md5::{Digest, Md5};
+
+/// WBI 混淆表：按顺序从 img_key + sub_key 中取字符得到 mixin_key
+const MIXIN_KEY_ENC_TAB: [usize; 64] = [
+    46, 47, 18, 2, 53, 8, 23, 32, 15, 50, 10, 31, 58, 3, 45, 35, 27, 43, 5, 49,
+    33, 9, 42, 19, 29, 28, 14, 39, 12, 38, 41, 13, 37, 48, 7, 16, 24, 55, 40,
+    61, 26, 17, 0, 1, 60, 51, 30, 4, 22, 25, 54, 21, 56, 59, 6, 63, 57, 62, 11,
+    36, 20, 34, 44, 52,
+];
+
+/// 签名前需要从参数值中剔除的字符
+const FILTERED_CHARS: &[char] = &['!', '\'', '(', ')', '*'];
+
+/// 密钥每天轮换一次，本地缓存超过这个时间就重新获取
+const KEY_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// 从 nav 接口 `wbi_img` 中得到的两段密钥
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WbiKeys {
+    pub img_key: String,
+    pub sub_key: String,
+}
+
+impl WbiKeys {
+    /// 从 `img_url`/`sub_url` 中取出文件名（不含扩展名）作为密钥
+    pub fn from_urls(img_url: &str, sub_url: &str) -> Option<Self> {
+        fn stem(url: &str) -> Option<String> {
+            let name = url.rsplit('/').next()?;
+            let stem = name.split('.').next()?;
+            (!stem.is_empty()).then(|| stem.to_string())
+        }
+        Some(Self {
+            img_key: stem(img_url)?,
+            sub_key: stem(sub_url)?,
+        })
+    }
+
+    /// 按混淆表重排后取前 32 位
+    pub fn mixin_key(&self) -> String {
+        let raw: Vec<char> = format!("{}{}", self.img_key, self.sub_key).chars().collect();
+        MIXIN_KEY_ENC_TAB
+            .iter()
+            .filter_map(|&i| raw.get(i))
+            .take(32)
+            .collect()
+    }
+}
+
+/// 对查询参数签名，返回带上 `wts` 和 `w_rid` 的完整参数（参数值已过滤特殊字符）
+pub fn sign_params(params: &[(String, String)], mixin_key: &str, wts: i64) -> Vec<(String, String)> {
+    let mut signed: Vec<(String, String)> = params
+        .iter()
+        .filter(|(k, _)| k != "wts" && k != "w_rid")
+        .map(|(k, v)| (k.clone(), v.chars().filter(|c| !FILTERED_CHARS.contains(c)).collect()))
+        .collect();
+    signed.push(("wts".to_string(), wts.to_string()));
+    signed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let query = signed
+        .iter()
+        .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let mut hasher = Md5::new();
+    hasher.update(query.as_bytes());
+    hasher.update(mixin_key.as_bytes());
+    let w_rid = format!("{:x}", hasher.finalize());
+
+    signed.push(("w_rid".to_string(), w_rid));
+    signed
+}
+
+/// 与浏览器 `encodeURIComponent` 一致的编码（特殊字符已提前过滤），空格编码为 `%20`
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+/// 缓存 WBI 密钥，过期后由客户端重新从 nav 接口获取
+#[derive(Debug)]
+pub struct WbiSigner {
+    cached: RwLock<Option<(WbiKeys, Instant)>>,
+    ttl: Duration,
+}
+
+impl Default for WbiSigner {
+    fn default() -> Self {
+        Self {
+            cached: RwLock::new(None),
+            ttl: KEY_TTL,
+        }
+    }
+}
+
+impl WbiSigner {
+    /// 未过期的缓存密钥
+    pub fn cached_keys(&self) -> Option<WbiKeys> {
+        let cached = self.cached.read().unwrap();
+        cached
+            .as_ref()
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(keys, _)| keys.clone())
+    }
+
+    pub fn store(&self, keys: WbiKeys) {
+        *self.cached.write().unwrap() = Some((keys, Instant::now()));
+    }
+
+    /// 服务端提示签名无效（-352）时丢弃缓存，下次请求会重新获取
+    pub fn invalidate(&self) {
+        *self.cached.write().unwrap() = None;
+    }
+
+    /// 用当前时间戳签名
+    pub fn sign(&self, keys: &WbiKeys, params: &[(String, String)]) -> Vec<(String, String)> {
+        let wts = chrono::Utc::now().timestamp();
+        sign_params(params, &keys.mixin_key(), wts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> WbiKeys {
+        WbiKeys {
+            img_key: "7cd084941338484aae1ad9425b84077c".to_string(),
+            sub_key: "4932caff0ff746eab6f01bf08b70ac45".to_string(),
+        }
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn get<'a>(signed: &'a [(String, String)], key: &str) -> Option<&'a str> {
+        signed.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn mixin_key_matches_reference() {
+        assert_eq!(keys().mixin_key(), "ea1db124af3c7062474693fa704f4ff8");
+    }
+
+    #[test]
+    fn keys_from_nav_urls() {
+        let keys = WbiKeys::from_urls(
+            "https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png",
+            "https://i0.hdslb.com/bfs/wbi/4932caff0ff746eab6f01bf08b70ac45.png",
+        );
+        assert_eq!(keys, Some(self::keys()));
+        assert_eq!(WbiKeys::from_urls("https://i0.hdslb.com/bfs/wbi/", "x.png"), None);
+    }
+
+    #[test]
+    fn sign_reference_example() {
+        let signed = sign_params(
+            &params(&[("foo", "114"), ("bar", "514"), ("zab", "1919810")]),
+            &keys().mixin_key(),
+            1702204169,
+        );
+        assert_eq!(get(&signed, "wts"), Some("1702204169"));
+        assert_eq!(get(&signed, "w_rid"), Some("8f6f2b5b3d485fe1886cec6a0be8c5d4"));
+    }
+
+    #[test]
+    fn sign_filters_special_chars_and_encodes_utf8() {
+        let signed = sign_params(
+            &params(&[("mid", "3461574394120551"), ("keyword", "a b!'()*中文")]),
+            &keys().mixin_key(),
+            1700000000,
+        );
+        assert_eq!(get(&signed, "keyword"), Some("a b中文"));
+        assert_eq!(get(&signed, "w_rid"), Some("0aafb2242cb5a90d2cc8a67b9b515325"));
+    }
+
+    #[test]
+    fn sign_replaces_existing_signature() {
+        let mixin = keys().mixin_key();
+        let once = sign_params(&params(&[("foo", "114")]), &mixin, 1702204169);
+        let twice = sign_params(&once, &mixin, 1702204169);
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn signer_cache_and_invalidate() {
+        let signer = WbiSigner::default();
+        assert_eq!(signer.cached_keys(), None);
+        signer.store(keys());
+        assert_eq!(signer.cached_keys(), Some(keys()));
+        signer.invalidate();
+        assert_eq!(signer.cached_keys(), None);
+    }
+}
@@ -1,18 +1,24 @@
 use gpui::*;
 use gpui::prelude::FluentBuilder;
-use gpui_component::IconName;
+use gpui_component::{Icon, IconName};
 
-use crate::views::{home::HomeView, settings::SettingsView, video_list::VideoListView};
-use crate::state::app_state::{AppState, Page, Theme};
+use crate::views::{creator::CreatorView, home::HomeView, logs::LogsView, network::NetworkView, settings::SettingsView, video_list::VideoListView};
+use crate::state::app_state::{AppState, Cookies, Page, Theme};
+use crate::api::error::BiliError;
+use crate::api::session::{self, SessionStatus};
 use crate::components::AnimatedAvatar;
+use crate::utils::get_runtime_handle;
 
 pub struct App {
     state: Entity<AppState>,
     _animation_driver: Option<()>, // 占位符，实际的定时器在启动时创建
     // 缓存视图实例，避免每次渲染都重新创建
     home_view: Option<Entity<HomeView>>,
+    creator_view: Option<Entity<CreatorView>>,
     settings_view: Option<Entity<SettingsView>>,
     video_list_view: Option<Entity<VideoListView>>,
+    logs_view: Option<Entity<LogsView>>,
+    network_view: Option<Entity<NetworkView>>,
 }
 
 impl App {
@@ -35,15 +41,97 @@ impl App {
                 }
             }
         }).detach();
+
+        if state.read(cx).is_logged_in() {
+            Self::check_session(state.clone(), cx);
+        }
         
         Self { 
             state,
             _animation_driver: Some(()),
             home_view: None,
+            creator_view: None,
             settings_view: None,
             video_list_view: None,
+            logs_view: None,
+            network_view: None,
         }
     }
+
+    /// 退出登录：先请求服务端作废会话，无论成功与否都清除本地的登录信息；
+    /// 请求期间切换了账号时只删除退出的那个账号
+    fn logout(state: Entity<AppState>, cx: &mut gpui::App) {
+        let (client, account) = state.update(cx, |s, _| {
+            s.set_user_menu_open(false);
+            (s.client(), s.active_account().map(str::to_string))
+        });
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = get_runtime_handle()
+                .spawn(async move { session::logout(&client).await })
+                .await?;
+            match result {
+                Ok(()) => info!("👋 已退出登录"),
+                Err(e) => warn!("⚠️ 服务端退出登录失败，仍然清除本地登录信息: {}", e),
+            }
+            state.update(cx, |s, _| {
+                if s.active_account() == account.as_deref() {
+                    s.clear_session();
+                } else if let Some(id) = &account {
+                    s.remove_account(id);
+                }
+            })?;
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+
+    /// 切换到已保存的账号，然后检查它的登录态
+    fn switch_account(state: Entity<AppState>, id: &str, cx: &mut gpui::App) {
+        let switched = state.update(cx, |s, _| s.switch_account(id));
+        if switched && state.read(cx).is_logged_in() {
+            Self::check_session(state, cx);
+        }
+    }
+
+    /// 启动时和切换账号后检查保存的登录态：需要时刷新 Cookie，确认失效后退回登录二维码；网络错误时保持原状
+    /// 检查结束前切换了账号时丢弃结果
+    fn check_session(state: Entity<AppState>, cx: &mut gpui::App) {
+        let (client, refresh_token, account) = state.read_with(cx, |s, _| {
+            (s.client(), s.refresh_token().cloned(), s.active_account().map(str::to_string))
+        });
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let checked = get_runtime_handle()
+                .spawn({
+                    let client = client.clone();
+                    async move { session::check(&client, refresh_token.as_deref()).await }
+                })
+                .await?;
+            if state.read_with(cx, |s, _| s.active_account() != account.as_deref())? {
+                return Ok(());
+            }
+            match checked {
+                Ok(SessionStatus::Valid { nav, refresh_token }) => {
+                    info!("✅ 登录有效: {}", nav.and_then(|n| n.uname).unwrap_or_default());
+                    if let Some(token) = refresh_token {
+                        let cookies = Cookies::from_jar(client.cookie_jar());
+                        state.update(cx, |s, _| {
+                            s.set_cookies(cookies);
+                            s.set_refresh_token(Some(token));
+                            s.persist_login();
+                        })?;
+                    }
+                }
+                Ok(SessionStatus::Expired) => {
+                    state.update(cx, |s, _| {
+                        s.set_refresh_token(None);
+                        s.handle_api_error(&BiliError::NotLoggedIn);
+                        s.set_page(Page::Home);
+                    })?;
+                }
+                Err(e) => warn!("⚠️ 检查登录状态失败，暂时保留登录信息: {}", e),
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
 }
 
 impl Render for App {
@@ -51,7 +139,21 @@ impl Render for App {
         let current_page = self.state.read(cx).current_page();
         let state = self.state.clone();
         let is_menu_open = self.state.read(cx).is_user_menu_open();
+        let is_logged_in = self.state.read(cx).is_logged_in();
         let theme = self.state.read(cx).theme();
+        let has_creator = self.state.read(cx).creator().is_some();
+        let active_account = self.state.read(cx).active_account().map(str::to_string);
+        // 菜单里的账号：(DedeUserID, 名字, 头像, 登录是否有效)
+        let accounts: Vec<(String, String, Option<String>, bool)> = self.state.read(cx)
+            .accounts()
+            .iter()
+            .filter_map(|a| {
+                let avatar = a.user.as_ref().and_then(|u| u.face_local.clone().or_else(|| u.face.clone()));
+                let id = a.id()?.to_string();
+                let valid = if active_account.as_deref() == Some(id.as_str()) { is_logged_in } else { a.logged_in };
+                Some((id, a.display_name(), avatar, valid))
+            })
+            .collect();
 
         div()
             .size_full()
@@ -186,6 +288,48 @@ impl Render for App {
                                 }
                             })
                     )
+                    // 创作者页面入口（搜索过 UP 主后显示）
+                    .when(has_creator, |this| {
+                        this.child(
+                            div()
+                                .w_full()
+                                .h(px(48.0))
+                                .flex()
+                                .items_center()
+                                .justify_center()
+                                .text_lg()
+                                .rounded_md()
+                                .map(|div| {
+                                    if matches!(current_page, Page::Creator) {
+                                        div.bg(match theme {
+                                            Theme::Dark => rgb(0x2a2a2a),
+                                            Theme::Light => rgb(0xcccccc),
+                                        })
+                                    } else {
+                                        div.hover(|style| style.bg(match theme {
+                                            Theme::Dark => rgb(0x1a1a1a),
+                                            Theme::Light => rgb(0xdddddd),
+                                        }))
+                                    }
+                                })
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child(IconName::GalleryVerticalEnd)
+                                )
+                                .on_mouse_down(gpui::MouseButton::Left, {
+                                    let state = state.clone();
+                                    move |_, _, cx| {
+                                        state.update(cx, |state, _| {
+                                            state.set_page(Page::Creator);
+                                        });
+                                    }
+                                })
+                        )
+                    })
                     // 填充空白，将用户区域推到底部
                     .child(div().flex_1())
                     // 用户登录状态区域（始终显示）
@@ -205,8 +349,7 @@ impl Render for App {
                             .mb_2()
                             .cursor(CursorStyle::PointingHand)
                             .child({
-                                if is_logged_in && user.is_some() {
-                                    let user = user.unwrap();
+                                if let Some(user) = user.filter(|_| is_logged_in) {
                                     
                                     // 获取头像路径
                                     let avatar_path = if let Some(local_path) = &user.face_local {
@@ -281,8 +424,7 @@ impl Render for App {
                                 let is_logged_in = self.state.read(cx).is_logged_in();
                                 let user = self.state.read(cx).user();
                                 
-                                if is_logged_in && user.is_some() {
-                                    let user = user.unwrap();
+                                if let Some(user) = user.filter(|_| is_logged_in) {
                                     
                                     // 获取头像路径
                                     let avatar_path = if let Some(local_path) = &user.face_local {
@@ -413,17 +555,27 @@ impl Render for App {
                                     Page::Home => {
                                         // 缓存 HomeView 实例，避免每次渲染都重新创建
                                         if self.home_view.is_none() {
-                                            println!("🎯 [App] 首次创建 HomeView");
+                                            debug!("🎯 首次创建 HomeView");
                                             self.home_view = Some(cx.new(|cx| HomeView::new(self.state.clone(), window, cx)));
                                         }
                                         div()
                                             .size_full()
                                             .child(self.home_view.clone().unwrap())
                                     }
+                                    Page::Creator => {
+                                        // 缓存 CreatorView 实例
+                                        if self.creator_view.is_none() {
+                                            debug!("📚 首次创建 CreatorView");
+                                            self.creator_view = Some(cx.new(|cx| CreatorView::new(self.state.clone(), window, cx)));
+                                        }
+                                        div()
+                                            .size_full()
+                                            .child(self.creator_view.clone().unwrap())
+                                    }
                                     Page::VideoList => {
                                         // 缓存 VideoListView 实例
                                         if self.video_list_view.is_none() {
-                                            println!("📹 [App] 首次创建 VideoListView");
+                                            debug!("📹 首次创建 VideoListView");
                                             self.video_list_view = Some(cx.new(|cx| VideoListView::new(self.state.clone(), window, cx)));
                                         }
                                         div()
@@ -433,13 +585,33 @@ impl Render for App {
                                     Page::Settings => {
                                         // 缓存 SettingsView 实例
                                         if self.settings_view.is_none() {
-                                            println!("⚙️  [App] 首次创建 SettingsView");
+                                            debug!("⚙️  首次创建 SettingsView");
                                             self.settings_view = Some(cx.new(|cx| SettingsView::new(self.state.clone(), window, cx)));
                                         }
                                         div()
                                             .size_full()
                                             .child(self.settings_view.clone().unwrap())
                                     }
+                                    Page::Logs => {
+                                        // 缓存 LogsView 实例
+                                        if self.logs_view.is_none() {
+                                            debug!("📜 首次创建 LogsView");
+                                            self.logs_view = Some(cx.new(|cx| LogsView::new(self.state.clone(), window, cx)));
+                                        }
+                                        div()
+                                            .size_full()
+                                            .child(self.logs_view.clone().unwrap())
+                                    }
+                                    Page::Network => {
+                                        // 缓存 NetworkView 实例
+                                        if self.network_view.is_none() {
+                                            debug!("🌐 首次创建 NetworkView");
+                                            self.network_view = Some(cx.new(|cx| NetworkView::new(self.state.clone(), window, cx)));
+                                        }
+                                        div()
+                                            .size_full()
+                                            .child(self.network_view.clone().unwrap())
+                                    }
                                 }
                             )
                     )
@@ -478,6 +650,98 @@ impl Render for App {
                         .rounded_md()
                         .shadow_lg()
                         .py_2()
+                        // 账号列表
+                        .children(accounts.into_iter().enumerate().map(|(i, (id, name, avatar, valid))| {
+                            let is_active = active_account.as_deref() == Some(id.as_str());
+                            div()
+                                .id(("account", i))
+                                .w_full()
+                                .px_4()
+                                .py_2()
+                                .flex()
+                                .items_center()
+                                .gap_3()
+                                .cursor(CursorStyle::PointingHand)
+                                .hover(|style| style.bg(match theme {
+                                    Theme::Dark => rgb(0x2a2a2a),
+                                    Theme::Light => rgb(0xf0f0f0),
+                                }))
+                                .child(match avatar {
+                                    Some(avatar) => cx.new(|_| AnimatedAvatar::new(avatar, px(20.0))).into_any_element(),
+                                    None => div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xaaaaaa),
+                                            Theme::Light => rgb(0x666666),
+                                        })
+                                        .child(IconName::User)
+                                        .into_any_element(),
+                                })
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .min_w_0()
+                                        .truncate()
+                                        .text_sm()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child(if valid { name } else { format!("{}（已失效）", name) })
+                                )
+                                .when(is_active, |this| {
+                                    this.child(
+                                        div()
+                                            .text_color(rgb(0x30a46c))
+                                            .child(IconName::Check)
+                                    )
+                                })
+                                .on_mouse_down(gpui::MouseButton::Left, {
+                                    let state = self.state.clone();
+                                    move |_, _, cx| Self::switch_account(state.clone(), &id, cx)
+                                })
+                        }))
+                        // 添加账号
+                        .child(
+                            div()
+                                .w_full()
+                                .px_4()
+                                .py_3()
+                                .flex()
+                                .items_center()
+                                .gap_3()
+                                .cursor(CursorStyle::PointingHand)
+                                .border_b_1()
+                                .border_color(match theme {
+                                    Theme::Dark => rgb(0x333333),
+                                    Theme::Light => rgb(0xe5e5e5),
+                                })
+                                .hover(|style| style.bg(match theme {
+                                    Theme::Dark => rgb(0x2a2a2a),
+                                    Theme::Light => rgb(0xf0f0f0),
+                                }))
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child(IconName::Plus)
+                                )
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child("添加账号")
+                                )
+                                .on_mouse_down(gpui::MouseButton::Left, {
+                                    let state = self.state.clone();
+                                    move |_, _, cx| {
+                                        state.update(cx, |state, _| state.begin_add_account());
+                                    }
+                                })
+                        )
                         // 设置选项
                         .child(
                             div()
@@ -518,6 +782,123 @@ impl Render for App {
                                     }
                                 })
                         )
+                        // 日志选项
+                        .child(
+                            div()
+                                .w_full()
+                                .px_4()
+                                .py_3()
+                                .flex()
+                                .items_center()
+                                .gap_3()
+                                .cursor(CursorStyle::PointingHand)
+                                .hover(|style| style.bg(match theme {
+                                    Theme::Dark => rgb(0x2a2a2a),
+                                    Theme::Light => rgb(0xf0f0f0),
+                                }))
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child(IconName::SquareTerminal)
+                                )
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child("日志")
+                                )
+                                .on_mouse_down(gpui::MouseButton::Left, {
+                                    let state = self.state.clone();
+                                    move |_, _, cx| {
+                                        state.update(cx, |state, _| {
+                                            state.set_page(Page::Logs);
+                                            state.set_user_menu_open(false);
+                                        });
+                                    }
+                                })
+                        )
+                        // 网络选项
+                        .child(
+                            div()
+                                .w_full()
+                                .px_4()
+                                .py_3()
+                                .flex()
+                                .items_center()
+                                .gap_3()
+                                .cursor(CursorStyle::PointingHand)
+                                .hover(|style| style.bg(match theme {
+                                    Theme::Dark => rgb(0x2a2a2a),
+                                    Theme::Light => rgb(0xf0f0f0),
+                                }))
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child(IconName::Inspector)
+                                )
+                                .child(
+                                    div()
+                                        .text_color(match theme {
+                                            Theme::Dark => rgb(0xffffff),
+                                            Theme::Light => rgb(0x333333),
+                                        })
+                                        .child("网络")
+                                )
+                                .on_mouse_down(gpui::MouseButton::Left, {
+                                    let state = self.state.clone();
+                                    move |_, _, cx| {
+                                        state.update(cx, |state, _| {
+                                            state.set_page(Page::Network);
+                                            state.set_user_menu_open(false);
+                                        });
+                                    }
+                                })
+                        )
+                        // 退出登录选项
+                        .when(is_logged_in, |menu| {
+                            menu.child(
+                                div()
+                                    .w_full()
+                                    .px_4()
+                                    .py_3()
+                                    .flex()
+                                    .items_center()
+                                    .gap_3()
+                                    .cursor(CursorStyle::PointingHand)
+                                    .hover(|style| style.bg(match theme {
+                                        Theme::Dark => rgb(0x2a2a2a),
+                                        Theme::Light => rgb(0xf0f0f0),
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_color(match theme {
+                                                Theme::Dark => rgb(0xffffff),
+                                                Theme::Light => rgb(0x333333),
+                                            })
+                                            .child(Icon::default().path("icons/log-out.svg"))
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(match theme {
+                                                Theme::Dark => rgb(0xffffff),
+                                                Theme::Light => rgb(0x333333),
+                                            })
+                                            .child("退出登录")
+                                    )
+                                    .on_mouse_down(gpui::MouseButton::Left, {
+                                        let state = self.state.clone();
+                                        move |_, _, cx| Self::logout(state.clone(), cx)
+                                    })
+                            )
+                        })
                         .into_any_element()
                 } else {
                     div().into_any_element()
@@ -84,7 +84,7 @@ impl AnimatedAvatar {
             
             // 如果需要检测，在后台线程异步处理
             if should_check {
-                println!("[AnimatedAvatar] 后台检测动画: {}", image_path);
+                debug!("后台检测动画: {}", image_path);
                 Self::check_and_decode_async(image_path.clone());
             }
         }
@@ -120,7 +120,7 @@ impl AnimatedAvatar {
                     });
                     drop(states);
                     
-                    println!("[AnimatedAvatar] 后台解码完成: {}, 帧数: {}", path, animation_data.frames.len());
+                    debug!("后台解码完成: {}, 帧数: {}", path, animation_data.frames.len());
                 } else {
                     // 解码失败，标记为静态
                     let mut cache = ANIMATION_STATUS_CACHE.lock().unwrap();
@@ -175,8 +175,8 @@ impl AnimatedAvatar {
         let mut last_timestamp = 0;
         for frame in decoder {
             // webp_animation的Frame不是Result，直接使用
-            let width = frame.dimensions().0 as u32;
-            let height = frame.dimensions().1 as u32;
+            let width = frame.dimensions().0;
+            let height = frame.dimensions().1;
             let rgba_data = frame.data().to_vec();
             let timestamp = frame.timestamp() as u64;
             
@@ -248,7 +248,7 @@ impl Render for AnimatedAvatar {
                         state.warmed_up = true;
                         state.current_frame = 0;
                         state.last_update = std::time::Instant::now();
-                        println!("[AnimatedAvatar] GPU预热完成: {}, 共{}帧", self.image_path, total_frames);
+                        debug!("GPU预热完成: {}, 共{}帧", self.image_path, total_frames);
                     }
                     
                     // 预热帧（用于GPU上传）
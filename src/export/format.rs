@@ -0,0 +1,194 @@
+use super::{ExportDocument, ExportFormat};
+
+/// 按格式渲染字幕文本
+pub fn render(doc: &ExportDocument, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Srt => srt(doc),
+        ExportFormat::WebVtt => web_vtt(doc),
+        ExportFormat::PlainText { timestamps } => plain_text(doc, timestamps),
+        ExportFormat::Markdown => markdown(doc),
+    }
+}
+
+/// 多个分P在 SRT 中按起始时间拼接成一条时间轴
+fn srt(doc: &ExportDocument) -> String {
+    let mut out = String::new();
+    let mut index = 1;
+    for part in &doc.parts {
+        for line in &part.lines {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                index,
+                cue_time(part.offset + line.from, ','),
+                cue_time(part.offset + line.to, ','),
+                line.content
+            ));
+            index += 1;
+        }
+    }
+    out
+}
+
+fn web_vtt(doc: &ExportDocument) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    let multi_part = doc.parts.len() > 1;
+    for part in &doc.parts {
+        if multi_part {
+            out.push_str(&format!("NOTE P{} {}\n\n", part.page, part.title));
+        }
+        for line in &part.lines {
+            out.push_str(&format!(
+                "{} --> {}\n{}\n\n",
+                cue_time(part.offset + line.from, '.'),
+                cue_time(part.offset + line.to, '.'),
+                line.content
+            ));
+        }
+    }
+    out
+}
+
+fn plain_text(doc: &ExportDocument, timestamps: bool) -> String {
+    let mut out = String::new();
+    let multi_part = doc.parts.len() > 1;
+    for part in &doc.parts {
+        if multi_part {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("【P{} {}】\n", part.page, part.title));
+        }
+        for line in &part.lines {
+            if timestamps {
+                out.push_str(&format!("[{}] ", clock(line.from)));
+            }
+            out.push_str(&line.content);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn markdown(doc: &ExportDocument) -> String {
+    let mut out = format!(
+        "# {}\n\n- BV号: {}\n- 链接: https://www.bilibili.com/video/{}\n",
+        doc.title, doc.bvid, doc.bvid
+    );
+    let multi_part = doc.parts.len() > 1;
+    for part in &doc.parts {
+        out.push('\n');
+        if multi_part {
+            out.push_str(&format!("## P{} {}\n\n", part.page, part.title));
+        }
+        if part.lines.is_empty() {
+            out.push_str("*（无字幕）*\n");
+            continue;
+        }
+        for line in &part.lines {
+            out.push_str(&format!("- `{}` {}\n", clock(line.from), line.content));
+        }
+    }
+    out
+}
+
+/// `00:01:02,345`（SRT 用逗号，WebVTT 用点号）
+fn cue_time(seconds: f64, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as i64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// `mm:ss` 或 `h:mm:ss`
+fn clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as i64;
+    let (h, m, s) = (total / 3600, total % 3600 / 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::bilibili::SubtitleLine;
+    use crate::export::ExportPart;
+
+    fn line(from: f64, to: f64, content: &str) -> SubtitleLine {
+        SubtitleLine { from, to, content: content.to_string() }
+    }
+
+    fn doc(parts: Vec<ExportPart>) -> ExportDocument {
+        ExportDocument { bvid: "BV1xx411c7mD".to_string(), title: "标题".to_string(), parts }
+    }
+
+    fn part(page: i32, title: &str, offset: f64, lines: Vec<SubtitleLine>) -> ExportPart {
+        ExportPart { page, title: title.to_string(), offset, lines }
+    }
+
+    #[test]
+    fn cue_time_formats() {
+        assert_eq!(cue_time(0.0, ','), "00:00:00,000");
+        assert_eq!(cue_time(62.345, ','), "00:01:02,345");
+        assert_eq!(cue_time(62.345, '.'), "00:01:02.345");
+        assert_eq!(cue_time(3723.5, ','), "01:02:03,500");
+        // 毫秒四舍五入，进位到秒
+        assert_eq!(cue_time(1.9996, ','), "00:00:02,000");
+        assert_eq!(cue_time(-1.0, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn clock_formats() {
+        assert_eq!(clock(5.9), "00:05");
+        assert_eq!(clock(62.0), "01:02");
+        assert_eq!(clock(3723.0), "1:02:03");
+    }
+
+    #[test]
+    fn srt_and_vtt_use_offsets_and_separators() {
+        let d = doc(vec![
+            part(1, "上", 0.0, vec![line(1.0, 2.5, "一")]),
+            part(2, "下", 60.0, vec![line(0.5, 1.0, "二")]),
+        ]);
+        assert_eq!(
+            render(&d, ExportFormat::Srt),
+            "1\n00:00:01,000 --> 00:00:02,500\n一\n\n2\n00:01:00,500 --> 00:01:01,000\n二\n\n"
+        );
+        assert_eq!(
+            render(&d, ExportFormat::WebVtt),
+            "WEBVTT\n\nNOTE P1 上\n\n00:00:01.000 --> 00:00:02.500\n一\n\nNOTE P2 下\n\n00:01:00.500 --> 00:01:01.000\n二\n\n"
+        );
+    }
+
+    #[test]
+    fn plain_text_with_and_without_timestamps() {
+        let d = doc(vec![part(1, "P1", 0.0, vec![line(61.0, 62.0, "你好"), line(65.0, 66.0, "再见")])]);
+        assert_eq!(render(&d, ExportFormat::PlainText { timestamps: false }), "你好\n再见\n");
+        assert_eq!(render(&d, ExportFormat::PlainText { timestamps: true }), "[01:01] 你好\n[01:05] 再见\n");
+    }
+
+    #[test]
+    fn markdown_has_part_headings() {
+        let d = doc(vec![
+            part(1, "开头", 0.0, vec![line(3.0, 4.0, "第一句")]),
+            part(2, "结尾", 100.0, vec![]),
+        ]);
+        assert_eq!(
+            render(&d, ExportFormat::Markdown),
+            "# 标题\n\n- BV号: BV1xx411c7mD\n- 链接: https://www.bilibili.com/video/BV1xx411c7mD\n\
+             \n## P1 开头\n\n- `00:03` 第一句\n\
+             \n## P2 结尾\n\n*（无字幕）*\n"
+        );
+
+        // 只有一个分P时不加标题
+        let single = doc(vec![part(1, "开头", 0.0, vec![line(3.0, 4.0, "第一句")])]);
+        assert!(!render(&single, ExportFormat::Markdown).contains("## "));
+    }
+}
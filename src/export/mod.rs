@@ -0,0 +1,148 @@
+pub mod format;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+
+use crate::api::bilibili::{fetch_subtitle_lines, fetch_subtitle_tracks, fetch_video_detail, SubtitleLine};
+use crate::api::client::BiliClient;
+use crate::utils::paths;
+
+/// 字幕导出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Srt,
+    WebVtt,
+    PlainText { timestamps: bool },
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 5] = [
+        ExportFormat::Srt,
+        ExportFormat::WebVtt,
+        ExportFormat::PlainText { timestamps: false },
+        ExportFormat::PlainText { timestamps: true },
+        ExportFormat::Markdown,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "srt",
+            ExportFormat::WebVtt => "vtt",
+            ExportFormat::PlainText { .. } => "txt",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// 导出的文件名；两种纯文本格式扩展名相同，带时间戳的加 `.timestamps` 区分，避免互相覆盖
+    pub fn file_name(&self, bvid: &str) -> String {
+        match self {
+            ExportFormat::PlainText { timestamps: true } => format!("{}.timestamps.{}", bvid, self.extension()),
+            _ => format!("{}.{}", bvid, self.extension()),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "SRT",
+            ExportFormat::WebVtt => "WebVTT",
+            ExportFormat::PlainText { timestamps: false } => "纯文本",
+            ExportFormat::PlainText { timestamps: true } => "纯文本(时间戳)",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+}
+
+/// 一个分P的字幕
+#[derive(Clone, Debug)]
+pub struct ExportPart {
+    pub page: i32,
+    pub title: String,
+    /// 该分P在整个视频中的起始时间（秒），SRT/WebVTT 按此拼接成一条时间轴
+    pub offset: f64,
+    pub lines: Vec<SubtitleLine>,
+}
+
+/// 一个视频的全部字幕
+#[derive(Clone, Debug)]
+pub struct ExportDocument {
+    pub bvid: String,
+    pub title: String,
+    pub parts: Vec<ExportPart>,
+}
+
+/// 导出文件默认保存的目录（位于数据目录）
+pub fn export_dir() -> PathBuf {
+    paths::paths().export_dir()
+}
+
+/// 拉取视频所有分P的字幕，优先使用 `lan` 指定的语言；整个视频都没有字幕时返回 `None`
+pub async fn collect_document(client: &BiliClient, bvid: &str, lan: Option<&str>) -> Result<Option<ExportDocument>> {
+    let detail = fetch_video_detail(client, bvid).await?;
+    let pages: Vec<(i32, String, i64, i64)> = if detail.pages.is_empty() {
+        vec![(1, detail.title.clone(), detail.cid, detail.duration)]
+    } else {
+        detail.pages.iter().map(|p| (p.page, p.part.clone(), p.cid, p.duration)).collect()
+    };
+
+    let mut parts = Vec::with_capacity(pages.len());
+    let mut offset = 0.0;
+    for (page, title, cid, duration) in pages {
+        let tracks = fetch_subtitle_tracks(client, bvid, cid).await?;
+        let track = lan
+            .and_then(|lan| tracks.iter().find(|t| t.lan == lan))
+            .or_else(|| tracks.first());
+        let lines = match track {
+            Some(track) => fetch_subtitle_lines(client, track).await?,
+            None => Vec::new(),
+        };
+        parts.push(ExportPart { page, title, offset, lines });
+        offset += duration as f64;
+    }
+
+    if parts.iter().all(|p| p.lines.is_empty()) {
+        return Ok(None);
+    }
+    Ok(Some(ExportDocument {
+        bvid: bvid.to_string(),
+        title: detail.title,
+        parts,
+    }))
+}
+
+/// 写入 `<dir>/<bvid>.<ext>`（见 [`ExportFormat::file_name`]），返回文件路径
+pub fn write_document(doc: &ExportDocument, format: ExportFormat, dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format.file_name(&doc.bvid));
+    fs::write(&path, format::render(doc, format))?;
+    Ok(path)
+}
+
+/// 导出单个视频，没有字幕时返回 `None`
+pub async fn export_video(
+    client: &BiliClient,
+    bvid: &str,
+    lan: Option<&str>,
+    format: ExportFormat,
+    dir: &Path,
+) -> Result<Option<PathBuf>> {
+    match collect_document(client, bvid, lan).await? {
+        Some(doc) => Ok(Some(write_document(&doc, format, dir)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_format_has_its_own_file() {
+        let names: std::collections::HashSet<_> = ExportFormat::ALL.iter().map(|f| f.file_name("BV1xx411c7mD")).collect();
+        assert_eq!(names.len(), ExportFormat::ALL.len());
+        assert_eq!(ExportFormat::PlainText { timestamps: false }.file_name("BV1"), "BV1.txt");
+        assert_eq!(ExportFormat::PlainText { timestamps: true }.file_name("BV1"), "BV1.timestamps.txt");
+        assert_eq!(ExportFormat::Srt.file_name("BV1"), "BV1.srt");
+    }
+}
@@ -1,12 +1,19 @@
+// 所有输出都经过日志层（Cookie 和令牌会被遮盖），不能直接打印
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
 use gpui::*;
 use gpui_component::*;
 
+// 日志宏要在其他模块之前引入
+#[macro_use]
+mod utils;
+mod analysis;
 mod api;
 mod app;
 mod assets;
 mod components;
+mod export;
 mod state;
-mod utils;
 mod views;
 
 use app::App;
@@ -17,17 +24,21 @@ use assets::Assets;
 mod tray;
 
 fn main() {
+    // 确定配置、数据和缓存目录，并迁移旧版本放在工作目录下的文件
+    let paths = utils::paths::init(std::env::args().skip(1));
+    utils::logging::init(&paths.log_dir());
+
     let app = Application::new().with_assets(Assets);
 
     app.run(move |cx| {
         // 注册自定义字体 - MiSans
         if let Some(font_data) = Assets::get("fonts/MiSansVF.ttf") {
             cx.text_system()
-                .add_fonts(vec![font_data.data.into()])
+                .add_fonts(vec![font_data.data])
                 .expect("Failed to load MiSans font");
-            println!("✓ MiSans字体加载成功");
+            info!("✓ MiSans字体加载成功");
         } else {
-            eprintln!("⚠ 警告: 无法加载 MiSans 字体文件");
+            warn!("⚠ 警告: 无法加载 MiSans 字体文件");
         }
         
         // TODO: 设置 HTTP client 以支持远程图片加载
@@ -1,10 +1,28 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use crate::analysis::timeline::DanmakuTimeline;
+use crate::api::bilibili::{
+    Reply, ReplyPage, ReplySort, SeasonMeta, SeriesMeta, SpaceCollectionsData, SubReplyPage, SubtitleLine, SubtitleTrack,
+    UploadOrder, VideoDetail,
+};
+use crate::api::client::{BiliClient, BiliClientConfig, CookieJar};
+use crate::api::danmaku::Danmaku;
+use crate::api::error::BiliError;
+use crate::export::ExportFormat;
+use crate::utils::credentials::{self, CredentialStore};
+use crate::utils::image_cache::image_cache;
+use crate::utils::paths;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Page {
     Home,
+    Creator,
     VideoList,
     Settings,
+    Logs,
+    Network,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -13,12 +31,100 @@ pub enum Theme {
     Light,
 }
 
+/// 视频列表页右侧面板的标签
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SideTab {
+    Ai,
+    Comments,
+}
+
+/// 已加载的一级评论
+#[derive(Clone, Debug, Default)]
+pub struct CommentThread {
+    pub top_replies: Vec<Reply>,
+    pub replies: Vec<Reply>,
+    pub next_offset: Option<String>,
+    pub is_end: bool,
+    pub loading: bool,
+    pub failed: bool,
+}
+
+/// 已加载的楼中楼回复
+#[derive(Clone, Debug, Default)]
+pub struct SubReplyThread {
+    pub replies: Vec<Reply>,
+    pub page: i32,
+    pub count: i64,
+    pub is_end: bool,
+    pub loading: bool,
+}
+
+/// 创作者页面：UP 主的合集和系列，按页加载
+#[derive(Clone, Debug, Default)]
+pub struct CreatorCollections {
+    pub mid: String,
+    pub seasons: Vec<SeasonMeta>,
+    pub series: Vec<SeriesMeta>,
+    /// 已加载的页数
+    pub page: i32,
+    /// 合集和系列的总数
+    pub total: i32,
+    pub is_end: bool,
+    pub loading: bool,
+    pub failed: bool,
+}
+
+/// 一个账号浏览的内容：搜索、创作者页面、视频列表以及按视频缓存的详情、字幕、弹幕和评论；
+/// 切换账号时整体换出，切换回来时恢复
+#[derive(Default)]
+struct Library {
+    page: Option<Page>,
+    search_text: String,
+    /// 搜索框下方的提示（解析中、未找到等）
+    search_status: Option<String>,
+    // 创作者页面
+    creator: Option<CreatorCollections>,
+    // 视频列表数据
+    video_list: Vec<VideoInfo>,
+    selected_video_index: Option<usize>,
+    // 正在后台下载封面的视频（按 bvid）
+    pending_covers: HashSet<String>,
+    // 视频详情（选中视频时按需加载，按 bvid 缓存）
+    video_details: HashMap<String, VideoDetail>,
+    loading_details: HashSet<String>,
+    // 字幕（轨道按 cid 缓存，内容按 cid + 语言缓存）
+    selected_cid: Option<i64>,
+    subtitle_lan: Option<String>,
+    subtitle_tracks: HashMap<i64, Vec<SubtitleTrack>>,
+    subtitle_lines: HashMap<(i64, String), Vec<SubtitleLine>>,
+    loading_subtitles: HashSet<i64>,
+    // 弹幕（按 cid 缓存，打开弹幕栏时才加载）
+    danmaku: HashMap<i64, Vec<Danmaku>>,
+    danmaku_timelines: HashMap<i64, DanmakuTimeline>,
+    loading_danmaku: HashSet<i64>,
+    // 评论（按 aid + 排序方式缓存，楼中楼按根评论 rpid 缓存）
+    comment_threads: HashMap<(i64, ReplySort), CommentThread>,
+    sub_reply_threads: HashMap<i64, SubReplyThread>,
+}
+
 pub struct AppState {
     current_page: Page,
     theme: Theme,
     logged_in: bool,
     cookies: Option<Cookies>,
+    // 刷新 Cookie 用的令牌，扫码登录时获得
+    refresh_token: Option<String>,
     user: Option<UserProfile>,
+    // 共享的 API 客户端，Cookie 与 cookies 字段保持同步
+    client: BiliClient,
+    // 登录信息的加密存储，以及旧版本的明文登录文件
+    credentials: Arc<dyn CredentialStore>,
+    legacy_login: PathBuf,
+    // 已登录过的账号（包括当前账号），按 DedeUserID 区分
+    accounts: Vec<SavedLogin>,
+    active_account: Option<String>,
+    // 添加账号时原来的账号，取消时切换回去
+    previous_account: Option<String>,
     // 登录页所需的临时状态
     qr_started: bool,
     qrcode_key: Option<String>,
@@ -26,34 +132,72 @@ pub struct AppState {
     qr_status: String,
     // UI状态
     user_menu_open: bool,
-    search_text: String,
-    // 视频列表数据
-    video_list: Vec<VideoInfo>,
-    selected_video_index: Option<usize>,
+    upload_order: UploadOrder,
+    show_danmaku: bool,
+    side_tab: SideTab,
+    reply_sort: ReplySort,
+    // 当前账号浏览的内容
+    library: Library,
+    // 其他账号的浏览内容，切换回去时恢复
+    libraries: HashMap<String, Library>,
+    // 字幕导出
+    export_open: bool,
+    export_format: ExportFormat,
+    exporting: bool,
+    export_status: Option<String>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        // 尝试从磁盘加载登录状态
+        Self::with_credentials(credentials::open_default(), paths::paths().data_file(paths::LOGIN_FILE))
+    }
+
+    /// 使用指定的凭据存储创建状态，并从中读取保存的账号
+    fn with_credentials(credentials: Arc<dyn CredentialStore>, legacy_login: PathBuf) -> Self {
         let mut s = Self {
             current_page: Page::Home,
             theme: Theme::Dark,  // 默认使用深色主题
             logged_in: false,
             cookies: None,
+            refresh_token: None,
             user: None,
+            client: BiliClient::new(BiliClientConfig::default()),
+            credentials,
+            legacy_login,
+            accounts: Vec::new(),
+            active_account: None,
+            previous_account: None,
             qr_started: false,
             qrcode_key: None,
             qr_svg: None,
             qr_status: String::new(),
             user_menu_open: false,
-            search_text: String::new(),
-            video_list: Vec::new(),
-            selected_video_index: None,
+            upload_order: UploadOrder::default(),
+            show_danmaku: false,
+            side_tab: SideTab::Ai,
+            reply_sort: ReplySort::Hot,
+            library: Library::default(),
+            libraries: HashMap::new(),
+            export_open: false,
+            export_format: ExportFormat::Srt,
+            exporting: false,
+            export_status: None,
         };
-        if let Ok(Some(saved)) = crate::utils::load_json::<SavedLogin>("bili_cookies.json") {
-            s.cookies = Some(saved.cookies.clone());
-            s.logged_in = saved.logged_in;
-            s.user = saved.user.clone();
+        match credentials::load_or_migrate::<StoredLogins>(s.credentials.as_ref(), &s.legacy_login) {
+            Ok(Some(stored)) => {
+                let saved = stored.into_accounts();
+                s.accounts = saved.accounts;
+                let active = saved
+                    .active
+                    .and_then(|id| s.find_account(&id).cloned())
+                    .or_else(|| s.accounts.first().cloned());
+                if let Some(login) = active {
+                    s.apply_login(login);
+                }
+                info!("👥 已读取 {} 个账号", s.accounts.len());
+            }
+            Ok(None) => {}
+            Err(e) => error!("❌ 读取登录信息失败: {}", e),
         }
         s
     }
@@ -85,8 +229,13 @@ impl AppState {
     pub fn user(&self) -> Option<&UserProfile> { self.user.as_ref() }
 
     pub fn set_logged_in(&mut self, logged: bool) { self.logged_in = logged; }
-    pub fn set_cookies(&mut self, cookies: Cookies) { self.cookies = Some(cookies); }
+    pub fn set_cookies(&mut self, cookies: Cookies) {
+        self.cookies = Some(cookies);
+        self.sync_client_cookies();
+    }
     pub fn set_user(&mut self, user: UserProfile) { self.user = Some(user); }
+    pub fn refresh_token(&self) -> Option<&String> { self.refresh_token.as_ref() }
+    pub fn set_refresh_token(&mut self, token: Option<String>) { self.refresh_token = token; }
 
     pub fn cookie_header(&self) -> Option<String> {
         let c = self.cookies.as_ref()?;
@@ -99,13 +248,216 @@ impl AppState {
         Some(parts.join("; "))
     }
 
-    pub fn persist_login(&self) {
-        let saved = SavedLogin { 
-            logged_in: self.logged_in, 
+    /// 获取共享的 API 客户端（内部是引用计数，克隆开销很小）
+    pub fn client(&self) -> BiliClient { self.client.clone() }
+
+    fn sync_client_cookies(&self) {
+        match self.cookie_header() {
+            Some(header) => self.client.cookie_jar().set_header(&header),
+            None => self.client.cookie_jar().clear(),
+        }
+    }
+
+    /// 把当前账号写回账号列表并保存；新登录的账号会加入列表并成为当前账号
+    pub fn persist_login(&mut self) {
+        let saved = SavedLogin {
+            logged_in: self.logged_in,
             cookies: self.cookies.clone().unwrap_or_default(),
+            refresh_token: self.refresh_token.clone(),
             user: self.user.clone(),
         };
-        let _ = crate::utils::save_json("bili_cookies.json", &saved);
+        if let Some(id) = saved.id().map(str::to_string) {
+            match self.accounts.iter_mut().find(|a| a.id() == Some(id.as_str())) {
+                Some(account) => *account = saved,
+                None => {
+                    info!("👤 添加账号: {}", id);
+                    self.accounts.push(saved);
+                }
+            }
+            self.active_account = Some(id);
+            self.previous_account = None;
+        }
+        self.save_accounts();
+    }
+
+    fn save_accounts(&self) {
+        let saved = SavedAccounts {
+            active: self.active_account.clone(),
+            accounts: self.accounts.clone(),
+        };
+        if let Err(e) = credentials::save_value(self.credentials.as_ref(), &saved) {
+            error!("❌ 保存登录信息失败: {}", e);
+        }
+    }
+
+    fn find_account(&self, id: &str) -> Option<&SavedLogin> {
+        self.accounts.iter().find(|a| a.id() == Some(id))
+    }
+
+    /// 使用保存的账号：登录态写入共享客户端
+    fn apply_login(&mut self, login: SavedLogin) {
+        self.active_account = login.id().map(str::to_string);
+        self.logged_in = login.logged_in;
+        self.cookies = Some(login.cookies);
+        self.refresh_token = login.refresh_token;
+        self.user = login.user;
+        self.sync_client_cookies();
+    }
+
+    /// 换出当前账号浏览的内容，正在加载的标记不保留（切换回来时可以重新加载）
+    fn stash_library(&mut self) {
+        let mut library = std::mem::take(&mut self.library);
+        library.page = Some(self.current_page);
+        library.pending_covers.clear();
+        library.loading_details.clear();
+        library.loading_subtitles.clear();
+        library.loading_danmaku.clear();
+        if let Some(creator) = &mut library.creator {
+            creator.loading = false;
+        }
+        library.comment_threads.values_mut().for_each(|t| t.loading = false);
+        library.sub_reply_threads.values_mut().for_each(|t| t.loading = false);
+        if let Some(id) = &self.active_account {
+            self.libraries.insert(id.clone(), library);
+        }
+    }
+
+    /// 恢复账号之前浏览的内容和页面；登录已失效时回到首页扫码
+    fn restore_library(&mut self, id: &str) {
+        self.library = self.libraries.remove(id).unwrap_or_default();
+        let page = self.library.page.take().unwrap_or(Page::Home);
+        self.current_page = if self.logged_in { page } else { Page::Home };
+    }
+
+    fn reset_qr(&mut self) {
+        self.qr_started = false;
+        self.qrcode_key = None;
+        self.qr_svg = None;
+        self.qr_status.clear();
+    }
+
+    // 账号列表
+    pub fn accounts(&self) -> &[SavedLogin] { &self.accounts }
+    pub fn active_account(&self) -> Option<&str> { self.active_account.as_deref() }
+    /// 正在添加账号时原来的账号
+    pub fn previous_account(&self) -> Option<&SavedLogin> {
+        self.previous_account.as_deref().and_then(|id| self.find_account(id))
+    }
+
+    /// 切换到另一个已保存的账号，当前账号浏览的内容保留到切换回来；没有切换时返回 false
+    pub fn switch_account(&mut self, id: &str) -> bool {
+        if self.active_account.as_deref() == Some(id) {
+            return false;
+        }
+        let Some(login) = self.find_account(id).cloned() else { return false };
+        if self.active_account.is_some() {
+            self.persist_login();
+        }
+        self.stash_library();
+        self.apply_login(login);
+        self.restore_library(id);
+        self.reset_qr();
+        self.previous_account = None;
+        self.user_menu_open = false;
+        self.save_accounts();
+        info!("🔀 已切换到账号 {}", id);
+        true
+    }
+
+    /// 添加账号：保留当前账号，回到首页走扫码登录流程
+    pub fn begin_add_account(&mut self) {
+        if self.active_account.is_some() {
+            self.persist_login();
+        }
+        self.stash_library();
+        self.previous_account = self.active_account.take();
+        self.logged_in = false;
+        self.cookies = None;
+        self.refresh_token = None;
+        self.user = None;
+        self.sync_client_cookies();
+        self.reset_qr();
+        self.user_menu_open = false;
+        self.current_page = Page::Home;
+    }
+
+    /// 放弃添加账号，切换回原来的账号
+    pub fn cancel_add_account(&mut self) -> bool {
+        match self.previous_account.take() {
+            Some(id) => self.switch_account(&id),
+            None => false,
+        }
+    }
+
+    /// 根据接口错误更新界面状态：登录失效时退回未登录状态，首页会重新显示登录二维码
+    pub fn handle_api_error(&mut self, err: &BiliError) {
+        if err.needs_login() && self.logged_in {
+            warn!("⚠️ 登录已失效，需要重新扫码登录");
+            self.logged_in = false;
+            self.qr_started = false;
+            self.qr_status = "登录已失效，请重新扫码登录".to_string();
+            self.persist_login();
+        }
+    }
+
+    /// 退出登录后清除当前账号的本地状态：Cookie、用户信息、保存的凭据、头像缓存和浏览过的内容，
+    /// 回到首页重新显示登录二维码；还有其他账号时可以从登录页切换回去
+    pub fn clear_session(&mut self) {
+        if let Some(user) = self.user.take() {
+            for url in [user.face, user.pendant_image].into_iter().flatten() {
+                image_cache().remove(&url);
+            }
+        }
+        if let Some(id) = self.active_account.take() {
+            self.accounts.retain(|a| a.id() != Some(id.as_str()));
+            self.libraries.remove(&id);
+        }
+        self.logged_in = false;
+        self.cookies = None;
+        self.refresh_token = None;
+        self.sync_client_cookies();
+        self.save_or_clear_accounts();
+        self.previous_account = self.accounts.first().and_then(|a| a.id()).map(str::to_string);
+
+        self.reset_qr();
+        self.user_menu_open = false;
+        self.library = Library::default();
+        self.export_open = false;
+        self.exporting = false;
+        self.export_status = None;
+        self.current_page = Page::Home;
+    }
+
+    /// 删除一个不是当前账号的已保存账号，连同它的头像缓存和浏览过的内容；
+    /// 退出登录的请求期间切换到了别的账号时使用
+    pub fn remove_account(&mut self, id: &str) {
+        if self.active_account.as_deref() == Some(id) {
+            return;
+        }
+        let Some(index) = self.accounts.iter().position(|a| a.id() == Some(id)) else { return };
+        let login = self.accounts.remove(index);
+        if let Some(user) = login.user {
+            for url in [user.face, user.pendant_image].into_iter().flatten() {
+                image_cache().remove(&url);
+            }
+        }
+        self.libraries.remove(id);
+        if self.previous_account.as_deref() == Some(id) {
+            self.previous_account = self.accounts.first().and_then(|a| a.id()).map(str::to_string);
+        }
+        self.save_or_clear_accounts();
+        info!("👋 已删除账号 {}", id);
+    }
+
+    /// 保存账号列表；一个账号都不剩时删除保存的凭据
+    fn save_or_clear_accounts(&self) {
+        if self.accounts.is_empty() {
+            if let Err(e) = credentials::clear_all(self.credentials.as_ref(), &self.legacy_login) {
+                error!("❌ 删除登录信息失败: {}", e);
+            }
+        } else {
+            self.save_accounts();
+        }
     }
 
     // 登录页状态
@@ -125,18 +477,244 @@ impl AppState {
     
     // 搜索状态
     #[allow(dead_code)]
-    pub fn search_text(&self) -> &str { &self.search_text }
-    pub fn set_search_text(&mut self, text: String) { self.search_text = text; }
+    pub fn search_text(&self) -> &str { &self.library.search_text }
+    pub fn set_search_text(&mut self, text: String) { self.library.search_text = text; }
+    /// 搜索框下方的提示（解析中、未找到等），为 `None` 时不显示
+    pub fn search_status(&self) -> Option<&str> { self.library.search_status.as_deref() }
+    pub fn set_search_status(&mut self, status: Option<String>) { self.library.search_status = status; }
+
+    // 创作者页面
+    pub fn creator(&self) -> Option<&CreatorCollections> { self.library.creator.as_ref() }
+    /// 切换到另一个 UP 主，清空已加载的合集
+    pub fn open_creator(&mut self, mid: String) {
+        self.library.creator = Some(CreatorCollections { mid, ..Default::default() });
+    }
+    /// 标记开始加载下一页，返回 UP 主 mid 和页码；正在加载或已经到底时返回 `None`
+    pub fn begin_creator_loading(&mut self) -> Option<(String, i32)> {
+        let creator = self.library.creator.as_mut()?;
+        if creator.loading || creator.is_end {
+            return None;
+        }
+        creator.loading = true;
+        creator.failed = false;
+        Some((creator.mid.clone(), creator.page + 1))
+    }
+    pub fn append_creator_collections(&mut self, mid: &str, page: i32, data: SpaceCollectionsData) {
+        // 加载期间切换了 UP 主时丢弃旧结果
+        let Some(creator) = self.library.creator.as_mut().filter(|c| c.mid == mid) else { return; };
+        let seasons = data.items_lists.seasons_list.unwrap_or_default();
+        let series = data.items_lists.series_list.unwrap_or_default();
+        let received = seasons.len() + series.len();
+        creator.seasons.extend(seasons.into_iter().map(|s| s.meta));
+        creator.series.extend(series.into_iter().map(|s| s.meta));
+        creator.page = page;
+        if let Some(info) = data.items_lists.page {
+            creator.total = info.total;
+        }
+        let loaded = (creator.seasons.len() + creator.series.len()) as i32;
+        creator.is_end = received == 0 || loaded >= creator.total;
+        creator.loading = false;
+    }
+    pub fn upload_order(&self) -> UploadOrder { self.upload_order }
+    pub fn set_upload_order(&mut self, order: UploadOrder) { self.upload_order = order; }
+    pub fn fail_creator_loading(&mut self, mid: &str) {
+        if let Some(creator) = self.library.creator.as_mut().filter(|c| c.mid == mid) {
+            creator.loading = false;
+            creator.failed = true;
+        }
+    }
     
     // 视频列表状态
-    pub fn video_list(&self) -> &[VideoInfo] { &self.video_list }
-    pub fn set_video_list(&mut self, videos: Vec<VideoInfo>) { self.video_list = videos; }
-    pub fn selected_video_index(&self) -> Option<usize> { self.selected_video_index }
-    pub fn set_selected_video_index(&mut self, index: Option<usize>) { self.selected_video_index = index; }
+    pub fn video_list(&self) -> &[VideoInfo] { &self.library.video_list }
+    pub fn set_video_list(&mut self, videos: Vec<VideoInfo>) { self.library.video_list = videos; }
+    pub fn selected_video_index(&self) -> Option<usize> { self.library.selected_video_index }
+    pub fn set_selected_video_index(&mut self, index: Option<usize>) {
+        self.library.selected_video_index = index;
+        self.library.selected_cid = None;
+    }
+    pub fn selected_video(&self) -> Option<&VideoInfo> {
+        self.library.selected_video_index.and_then(|i| self.library.video_list.get(i))
+    }
+    /// 封面还在后台下载中
+    pub fn is_cover_pending(&self, bvid: &str) -> bool { self.library.pending_covers.contains(bvid) }
+    /// 标记这些视频的封面开始下载，替换上一个列表未完成的标记
+    pub fn begin_cover_loading(&mut self, bvids: impl IntoIterator<Item = String>) {
+        self.library.pending_covers = bvids.into_iter().collect();
+    }
+    /// 图片缓存被清除后，封面和头像改用网络地址
+    pub fn forget_cached_images(&mut self) {
+        for video in &mut self.library.video_list {
+            video.pic_local = None;
+        }
+        for library in self.libraries.values_mut() {
+            library.video_list.iter_mut().for_each(|v| v.pic_local = None);
+        }
+        for user in self.accounts.iter_mut().filter_map(|a| a.user.as_mut()) {
+            user.face_local = None;
+        }
+        if let Some(user) = &mut self.user {
+            user.face_local = None;
+        }
+        // 没有账号时不能保存，否则会写出一个空的凭据文件
+        if !self.accounts.is_empty() {
+            self.persist_login();
+        }
+    }
+    /// 封面下载结束，成功时记录本地路径
+    pub fn set_video_cover(&mut self, bvid: &str, path: Option<String>) {
+        self.library.pending_covers.remove(bvid);
+        if let Some(path) = path {
+            for video in self.library.video_list.iter_mut().filter(|v| v.bvid == bvid) {
+                video.pic_local = Some(path.clone());
+            }
+        }
+    }
+
+    // 视频详情
+    pub fn video_detail(&self, bvid: &str) -> Option<&VideoDetail> { self.library.video_details.get(bvid) }
+    pub fn is_detail_loading(&self, bvid: &str) -> bool { self.library.loading_details.contains(bvid) }
+    /// 标记开始加载，已加载或正在加载时返回 false
+    pub fn begin_detail_loading(&mut self, bvid: &str) -> bool {
+        if self.library.video_details.contains_key(bvid) {
+            return false;
+        }
+        self.library.loading_details.insert(bvid.to_string())
+    }
+    pub fn finish_detail_loading(&mut self, bvid: &str) { self.library.loading_details.remove(bvid); }
+    /// 保存详情，并把简介同步到视频列表里对应的条目
+    pub fn set_video_detail(&mut self, detail: VideoDetail) {
+        self.library.loading_details.remove(&detail.bvid);
+        for video in self.library.video_list.iter_mut().filter(|v| v.bvid == detail.bvid) {
+            video.description = Some(detail.desc.clone());
+        }
+        self.library.video_details.insert(detail.bvid.clone(), detail);
+    }
+
+    // 字幕状态
+    /// 当前查看的分P：手动选择的分P，否则为视频的第一个分P
+    pub fn current_cid(&self) -> Option<i64> {
+        let detail = self.video_detail(&self.selected_video()?.bvid)?;
+        match self.library.selected_cid {
+            Some(cid) if detail.pages.iter().any(|p| p.cid == cid) => Some(cid),
+            _ => Some(detail.cid),
+        }
+    }
+    pub fn set_selected_cid(&mut self, cid: Option<i64>) { self.library.selected_cid = cid; }
+    pub fn subtitle_tracks(&self, cid: i64) -> Option<&[SubtitleTrack]> {
+        self.library.subtitle_tracks.get(&cid).map(|t| t.as_slice())
+    }
+    pub fn set_subtitle_tracks(&mut self, cid: i64, tracks: Vec<SubtitleTrack>) {
+        self.library.subtitle_tracks.insert(cid, tracks);
+    }
+    /// 当前语言对应的轨道，没有该语言时取第一个
+    pub fn active_subtitle_track(&self, cid: i64) -> Option<&SubtitleTrack> {
+        let tracks = self.library.subtitle_tracks.get(&cid)?;
+        self.library.subtitle_lan
+            .as_ref()
+            .and_then(|lan| tracks.iter().find(|t| &t.lan == lan))
+            .or_else(|| tracks.first())
+    }
+    pub fn set_subtitle_lan(&mut self, lan: String) { self.library.subtitle_lan = Some(lan); }
+    pub fn subtitle_lines(&self, cid: i64, lan: &str) -> Option<&[SubtitleLine]> {
+        self.library.subtitle_lines.get(&(cid, lan.to_string())).map(|l| l.as_slice())
+    }
+    pub fn set_subtitle_lines(&mut self, cid: i64, lan: String, lines: Vec<SubtitleLine>) {
+        self.library.subtitle_lines.insert((cid, lan), lines);
+    }
+    pub fn is_subtitle_loading(&self, cid: i64) -> bool { self.library.loading_subtitles.contains(&cid) }
+    /// 标记开始加载，正在加载时返回 false
+    pub fn begin_subtitle_loading(&mut self, cid: i64) -> bool { self.library.loading_subtitles.insert(cid) }
+    pub fn finish_subtitle_loading(&mut self, cid: i64) { self.library.loading_subtitles.remove(&cid); }
+    /// 当前选择的字幕语言（导出时优先使用）
+    pub fn subtitle_lan(&self) -> Option<&str> { self.library.subtitle_lan.as_deref() }
+
+    // 弹幕状态
+    pub fn is_danmaku_shown(&self) -> bool { self.show_danmaku }
+    pub fn toggle_danmaku_shown(&mut self) { self.show_danmaku = !self.show_danmaku; }
+    pub fn danmaku(&self, cid: i64) -> Option<&[Danmaku]> { self.library.danmaku.get(&cid).map(|d| d.as_slice()) }
+    pub fn set_danmaku(&mut self, cid: i64, list: Vec<Danmaku>, timeline: DanmakuTimeline) {
+        self.library.loading_danmaku.remove(&cid);
+        self.library.danmaku.insert(cid, list);
+        self.library.danmaku_timelines.insert(cid, timeline);
+    }
+    pub fn danmaku_timeline(&self, cid: i64) -> Option<&DanmakuTimeline> { self.library.danmaku_timelines.get(&cid) }
+    pub fn is_danmaku_loading(&self, cid: i64) -> bool { self.library.loading_danmaku.contains(&cid) }
+    /// 标记开始加载，正在加载时返回 false
+    pub fn begin_danmaku_loading(&mut self, cid: i64) -> bool { self.library.loading_danmaku.insert(cid) }
+    pub fn finish_danmaku_loading(&mut self, cid: i64) { self.library.loading_danmaku.remove(&cid); }
+
+    // 右侧面板与评论
+    pub fn side_tab(&self) -> SideTab { self.side_tab }
+    pub fn set_side_tab(&mut self, tab: SideTab) { self.side_tab = tab; }
+    pub fn reply_sort(&self) -> ReplySort { self.reply_sort }
+    pub fn set_reply_sort(&mut self, sort: ReplySort) { self.reply_sort = sort; }
+    pub fn comment_thread(&self, aid: i64, sort: ReplySort) -> Option<&CommentThread> {
+        self.library.comment_threads.get(&(aid, sort))
+    }
+    /// 标记开始加载下一页，返回该页的游标（第一页为 `None`）；正在加载或已经到底时返回 `None`
+    pub fn begin_comment_loading(&mut self, aid: i64, sort: ReplySort) -> Option<Option<String>> {
+        let thread = self.library.comment_threads.entry((aid, sort)).or_default();
+        if thread.loading || thread.is_end {
+            return None;
+        }
+        thread.loading = true;
+        thread.failed = false;
+        Some(thread.next_offset.clone())
+    }
+    pub fn append_comments(&mut self, aid: i64, sort: ReplySort, page: ReplyPage) {
+        let thread = self.library.comment_threads.entry((aid, sort)).or_default();
+        if !page.top_replies.is_empty() {
+            thread.top_replies = page.top_replies;
+        }
+        thread.replies.extend(page.replies);
+        thread.next_offset = page.next_offset;
+        thread.is_end = page.is_end;
+        thread.loading = false;
+    }
+    pub fn fail_comment_loading(&mut self, aid: i64, sort: ReplySort) {
+        if let Some(thread) = self.library.comment_threads.get_mut(&(aid, sort)) {
+            thread.loading = false;
+            thread.failed = true;
+        }
+    }
+    pub fn sub_reply_thread(&self, root: i64) -> Option<&SubReplyThread> { self.library.sub_reply_threads.get(&root) }
+    /// 标记开始加载楼中楼的下一页，返回页码；正在加载或已经到底时返回 `None`
+    pub fn begin_sub_reply_loading(&mut self, root: i64) -> Option<i32> {
+        let thread = self.library.sub_reply_threads.entry(root).or_default();
+        if thread.loading || thread.is_end {
+            return None;
+        }
+        thread.loading = true;
+        Some(thread.page + 1)
+    }
+    pub fn append_sub_replies(&mut self, root: i64, page: SubReplyPage) {
+        let thread = self.library.sub_reply_threads.entry(root).or_default();
+        thread.replies.extend(page.replies);
+        thread.page = page.page;
+        thread.count = page.count;
+        thread.is_end = page.is_end;
+        thread.loading = false;
+    }
+    pub fn finish_sub_reply_loading(&mut self, root: i64) {
+        if let Some(thread) = self.library.sub_reply_threads.get_mut(&root) {
+            thread.loading = false;
+        }
+    }
+
+    // 字幕导出状态
+    pub fn is_export_open(&self) -> bool { self.export_open }
+    pub fn toggle_export_open(&mut self) { self.export_open = !self.export_open; }
+    pub fn export_format(&self) -> ExportFormat { self.export_format }
+    pub fn set_export_format(&mut self, format: ExportFormat) { self.export_format = format; }
+    pub fn is_exporting(&self) -> bool { self.exporting }
+    pub fn set_exporting(&mut self, v: bool) { self.exporting = v; }
+    pub fn export_status(&self) -> Option<&str> { self.export_status.as_deref() }
+    pub fn set_export_status(&mut self, s: impl Into<String>) { self.export_status = Some(s.into()); }
+    pub fn clear_export_status(&mut self) { self.export_status = None; }
 }
 
 #[allow(non_snake_case)]
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct Cookies {
     pub SESSDATA: String,
     pub DedeUserID: Option<String>,
@@ -145,13 +723,97 @@ pub struct Cookies {
     pub sid: Option<String>,
 }
 
+impl Cookies {
+    /// 从共享客户端的 Cookie 中取出登录态（扫码登录或刷新 Cookie 后由 Set-Cookie 写入）
+    pub fn from_jar(jar: &CookieJar) -> Self {
+        Self {
+            SESSDATA: jar.get("SESSDATA").unwrap_or_default(),
+            DedeUserID: jar.get("DedeUserID"),
+            DedeUserID__ckMd5: jar.get("DedeUserID__ckMd5"),
+            bili_jct: jar.get("bili_jct"),
+            sid: jar.get("sid"),
+        }
+    }
+}
+
+// 登录凭据只显示是否存在
+impl std::fmt::Debug for Cookies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mask = |v: Option<&String>| v.map(|_| crate::utils::logging::MASK);
+        f.debug_struct("Cookies")
+            .field("SESSDATA", &mask(Some(&self.SESSDATA).filter(|s| !s.is_empty())))
+            .field("DedeUserID", &self.DedeUserID)
+            .field("DedeUserID__ckMd5", &mask(self.DedeUserID__ckMd5.as_ref()))
+            .field("bili_jct", &mask(self.bili_jct.as_ref()))
+            .field("sid", &self.sid)
+            .finish()
+    }
+}
+
+/// 保存在凭据存储里的账号列表
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SavedAccounts {
+    /// 当前账号的 DedeUserID
+    pub active: Option<String>,
+    pub accounts: Vec<SavedLogin>,
+}
+
+/// 凭据存储里的内容：旧版本只保存一个账号
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredLogins {
+    Accounts(SavedAccounts),
+    Single(SavedLogin),
+}
+
+impl StoredLogins {
+    fn into_accounts(self) -> SavedAccounts {
+        match self {
+            StoredLogins::Accounts(accounts) => accounts,
+            StoredLogins::Single(login) => match login.id().map(str::to_string) {
+                Some(id) => SavedAccounts { active: Some(id), accounts: vec![login] },
+                None => SavedAccounts::default(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct SavedLogin {
     pub logged_in: bool,
     pub cookies: Cookies,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
     pub user: Option<UserProfile>,
 }
 
+impl SavedLogin {
+    /// 账号标识（DedeUserID），没有登录过时为 `None`
+    pub fn id(&self) -> Option<&str> {
+        self.cookies.DedeUserID.as_deref().filter(|id| !id.is_empty())
+    }
+
+    /// 账号列表里显示的名字
+    pub fn display_name(&self) -> String {
+        match (self.user.as_ref().and_then(|u| u.uname.clone()), self.id()) {
+            (Some(name), _) => name,
+            (None, Some(id)) => format!("UID {}", id),
+            (None, None) => "未知账号".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SavedLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SavedLogin")
+            .field("logged_in", &self.logged_in)
+            .field("cookies", &self.cookies)
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| crate::utils::logging::MASK))
+            .field("user", &self.user)
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct UserProfile {
     pub uname: Option<String>,
@@ -174,3 +836,256 @@ pub struct VideoInfo {
     pub like_count: i64,
     pub is_live_replay: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::credentials::PlaintextStore;
+    use std::path::Path;
+
+    fn login(id: &str, uname: &str) -> SavedLogin {
+        SavedLogin {
+            logged_in: true,
+            cookies: Cookies { SESSDATA: format!("sess-{}", id), DedeUserID: Some(id.to_string()), ..Default::default() },
+            refresh_token: None,
+            user: Some(UserProfile { uname: Some(uname.to_string()), ..Default::default() }),
+        }
+    }
+
+    #[test]
+    fn reads_single_login_from_older_versions() {
+        let json = serde_json::to_string(&login("42", "alice")).unwrap();
+        let saved = serde_json::from_str::<StoredLogins>(&json).unwrap().into_accounts();
+        assert_eq!(saved.active.as_deref(), Some("42"));
+        assert_eq!(saved.accounts.len(), 1);
+        assert_eq!(saved.accounts[0].display_name(), "alice");
+    }
+
+    #[test]
+    fn reads_account_list() {
+        let json = serde_json::to_string(&SavedAccounts {
+            active: Some("7".to_string()),
+            accounts: vec![login("42", "alice"), login("7", "bob")],
+        })
+        .unwrap();
+        let saved = serde_json::from_str::<StoredLogins>(&json).unwrap().into_accounts();
+        assert_eq!(saved.active.as_deref(), Some("7"));
+        let ids: Vec<_> = saved.accounts.iter().filter_map(|a| a.id()).collect();
+        assert_eq!(ids, ["42", "7"]);
+    }
+
+    #[test]
+    fn logged_logins_hide_secrets() {
+        let login = SavedLogin {
+            cookies: Cookies {
+                SESSDATA: "7f1c9a2e%2C1767225600%2C4b3a1%2A71CjD".to_string(),
+                bili_jct: Some("0a1b2c3d4e5f60718293a4b5c6d7e8f9".to_string()),
+                DedeUserID__ckMd5: Some("9f8e7d6c5b4a3210".to_string()),
+                ..login("42", "alice").cookies
+            },
+            refresh_token: Some("c4a3e1f2d0b9a8c7e6f5d4c3b2a1f0e9".to_string()),
+            ..login("42", "alice")
+        };
+
+        crate::utils::logging::capture::start();
+        debug!("账号: {:?}", login);
+        debug!("Cookie: {:?}", login.cookies);
+        let output = crate::utils::logging::capture::finish();
+        for secret in ["7f1c9a2e", "0a1b2c3d", "9f8e7d6c", "c4a3e1f2"] {
+            assert!(!output.contains(secret), "泄露了 {}: {}", secret, output);
+        }
+        assert!(output.contains(r#"DedeUserID: Some("42")"#), "{}", output);
+        assert!(output.contains(r#"refresh_token: Some("***")"#), "{}", output);
+    }
+
+    #[test]
+    fn drops_single_login_without_user_id() {
+        let json = serde_json::to_string(&SavedLogin::default()).unwrap();
+        let saved = serde_json::from_str::<StoredLogins>(&json).unwrap().into_accounts();
+        assert!(saved.active.is_none());
+        assert!(saved.accounts.is_empty());
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bili-state-{:016x}", fastrand::u64(..)));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 凭据保存在临时目录的明文文件里，`accounts` 为空时不写入
+    fn state_in(dir: &Path, active: Option<&str>, accounts: Vec<SavedLogin>) -> AppState {
+        let store = PlaintextStore::new(dir.join("credentials.json"));
+        if !accounts.is_empty() {
+            let saved = SavedAccounts { active: active.map(str::to_string), accounts };
+            credentials::save_value(&store, &saved).unwrap();
+        }
+        AppState::with_credentials(Arc::new(store), dir.join("login.json"))
+    }
+
+    fn stored(dir: &Path) -> Option<SavedAccounts> {
+        credentials::load_value(&PlaintextStore::new(dir.join("credentials.json"))).unwrap()
+    }
+
+    fn video(bvid: &str) -> VideoInfo {
+        VideoInfo {
+            aid: 1,
+            bvid: bvid.to_string(),
+            title: bvid.to_string(),
+            pic: String::new(),
+            pic_local: Some(format!("/cache/{}.jpg", bvid)),
+            description: None,
+            pubdate: 0,
+            duration: 0,
+            view_count: 0,
+            like_count: 0,
+            is_live_replay: false,
+        }
+    }
+
+    fn bvids(state: &AppState) -> Vec<&str> {
+        state.video_list().iter().map(|v| v.bvid.as_str()).collect()
+    }
+
+    #[test]
+    fn switching_keeps_each_account_library() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, Some("42"), vec![login("42", "alice"), login("7", "bob")]);
+        assert_eq!(state.active_account(), Some("42"));
+        assert!(state.is_logged_in());
+        state.set_video_list(vec![video("BV1alice")]);
+        state.set_page(Page::VideoList);
+
+        assert!(state.switch_account("7"));
+        assert_eq!(state.active_account(), Some("7"));
+        assert_eq!(state.user().and_then(|u| u.uname.as_deref()), Some("bob"));
+        assert!(state.cookie_header().unwrap().contains("SESSDATA=sess-7"));
+        assert!(state.video_list().is_empty());
+        assert_eq!(state.current_page(), Page::Home);
+        assert_eq!(stored(&dir).unwrap().active.as_deref(), Some("7"));
+        state.set_video_list(vec![video("BV1bob")]);
+
+        assert!(state.switch_account("42"));
+        assert_eq!(bvids(&state), ["BV1alice"]);
+        assert_eq!(state.current_page(), Page::VideoList);
+        assert!(state.switch_account("7"));
+        assert_eq!(bvids(&state), ["BV1bob"]);
+
+        // 当前账号和不存在的账号都不会切换
+        assert!(!state.switch_account("7"));
+        assert!(!state.switch_account("1"));
+        assert_eq!(state.active_account(), Some("7"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn adding_account_can_be_cancelled() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, Some("42"), vec![login("42", "alice")]);
+        state.set_video_list(vec![video("BV1alice")]);
+
+        state.begin_add_account();
+        assert_eq!(state.active_account(), None);
+        assert!(!state.is_logged_in());
+        assert!(state.cookie_header().is_none());
+        assert!(state.video_list().is_empty());
+        assert_eq!(state.previous_account().and_then(|a| a.id()), Some("42"));
+
+        assert!(state.cancel_add_account());
+        assert_eq!(state.active_account(), Some("42"));
+        assert!(state.is_logged_in());
+        assert_eq!(bvids(&state), ["BV1alice"]);
+        assert!(state.previous_account().is_none());
+        assert!(!state.cancel_add_account());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn added_account_becomes_active() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, Some("42"), vec![login("42", "alice")]);
+        state.begin_add_account();
+
+        let bob = login("7", "bob");
+        state.set_cookies(bob.cookies);
+        state.set_logged_in(true);
+        state.persist_login();
+        assert_eq!(state.active_account(), Some("7"));
+        assert!(state.previous_account().is_none());
+        let ids: Vec<_> = state.accounts().iter().filter_map(|a| a.id()).collect();
+        assert_eq!(ids, ["42", "7"]);
+
+        let saved = stored(&dir).unwrap();
+        assert_eq!(saved.active.as_deref(), Some("7"));
+        assert_eq!(saved.accounts.len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn logout_removes_only_current_account() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, Some("42"), vec![login("42", "alice"), login("7", "bob")]);
+        state.set_video_list(vec![video("BV1alice")]);
+
+        state.clear_session();
+        assert_eq!(state.active_account(), None);
+        assert!(!state.is_logged_in());
+        assert!(state.video_list().is_empty());
+        assert_eq!(state.current_page(), Page::Home);
+        // 登录页可以切换回剩下的账号
+        assert_eq!(state.previous_account().and_then(|a| a.id()), Some("7"));
+        let saved = stored(&dir).unwrap();
+        assert_eq!(saved.accounts.iter().filter_map(|a| a.id()).collect::<Vec<_>>(), ["7"]);
+
+        assert!(state.cancel_add_account());
+        state.clear_session();
+        assert!(state.accounts().is_empty());
+        assert!(state.previous_account().is_none());
+        assert!(!dir.join("credentials.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn removes_account_that_is_no_longer_active() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, Some("7"), vec![login("42", "alice"), login("7", "bob")]);
+        state.set_video_list(vec![video("BV1bob")]);
+
+        // 当前账号不会被删除
+        state.remove_account("7");
+        assert_eq!(state.accounts().len(), 2);
+
+        state.remove_account("42");
+        assert_eq!(state.active_account(), Some("7"));
+        assert!(state.is_logged_in());
+        assert_eq!(bvids(&state), ["BV1bob"]);
+        let saved = stored(&dir).unwrap();
+        assert_eq!(saved.accounts.iter().filter_map(|a| a.id()).collect::<Vec<_>>(), ["7"]);
+        assert!(!state.switch_account("42"));
+
+        // 添加账号时删除原来的账号，取消后没有账号可以切换回去
+        state.begin_add_account();
+        state.remove_account("7");
+        assert!(state.previous_account().is_none());
+        assert!(!state.cancel_add_account());
+        assert!(!dir.join("credentials.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn forgetting_images_without_accounts_saves_nothing() {
+        let dir = temp_dir();
+        let mut state = state_in(&dir, None, Vec::new());
+        state.set_video_list(vec![video("BV1")]);
+        state.forget_cached_images();
+        assert!(state.video_list()[0].pic_local.is_none());
+        assert!(!dir.join("credentials.json").exists());
+
+        let mut state = state_in(&dir, Some("42"), vec![login("42", "alice")]);
+        state.begin_add_account();
+        state.forget_cached_images();
+        let saved = stored(&dir).unwrap();
+        assert_eq!(saved.accounts.len(), 1);
+        assert!(saved.active.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
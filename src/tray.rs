@@ -18,12 +18,12 @@ pub fn create_tray_icon() -> Option<tray_icon::TrayIcon> {
             let quit_item = MenuItem::new("Quit", true, None);
 
             if let Err(e) = menu.append(&show_item) {
-                eprintln!("Failed to add show menu item: {}", e);
+                error!("Failed to add show menu item: {}", e);
                 return None;
             }
 
             if let Err(e) = menu.append(&quit_item) {
-                eprintln!("Failed to add quit menu item: {}", e);
+                error!("Failed to add quit menu item: {}", e);
                 return None;
             }
 
@@ -42,17 +42,17 @@ pub fn create_tray_icon() -> Option<tray_icon::TrayIcon> {
 
             match builder.build() {
                 Ok(tray) => {
-                    println!("System tray icon created successfully");
+                    info!("System tray icon created successfully");
                     Some(tray)
                 }
                 Err(e) => {
-                    eprintln!("Failed to create tray icon: {}", e);
+                    error!("Failed to create tray icon: {}", e);
                     None
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to load tray icon: {}", e);
+            error!("Failed to load tray icon: {}", e);
             None
         }
     }
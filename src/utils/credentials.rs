@@ -0,0 +1,421 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Context};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::paths::{self, paths};
+
+/// 加密后的凭据文件（位于数据目录）
+pub const CREDENTIALS_FILE: &str = "credentials.enc";
+/// 本机密钥文件（位于配置目录），没有设置口令时使用
+pub const KEY_FILE: &str = "credentials.key";
+/// 用口令代替本机密钥文件加密凭据
+pub const PASSPHRASE_ENV: &str = "BILI_CREDENTIAL_PASSPHRASE";
+
+const MAGIC: &[u8; 8] = b"BILICRED";
+const VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// 文件头：魔数、版本、密钥来源和盐，同时作为附加认证数据防止被篡改
+const HEADER_LEN: usize = MAGIC.len() + 2 + SALT_LEN;
+
+/// 凭据存储后端，保存的是已经序列化好的字节。
+/// 以后可以在这里接入 macOS 钥匙串、Windows 凭据管理器或 Secret Service
+pub trait CredentialStore: Send + Sync {
+    /// 后端名称，用于日志
+    fn name(&self) -> &'static str;
+    /// 没有保存过凭据时返回 `None`
+    fn load(&self) -> anyhow::Result<Option<Vec<u8>>>;
+    fn save(&self, secret: &[u8]) -> anyhow::Result<()>;
+    /// 删除保存的凭据，没有保存过时什么也不做
+    fn clear(&self) -> anyhow::Result<()>;
+}
+
+/// 按默认方式打开凭据存储：设置了 [`PASSPHRASE_ENV`] 时用口令加密，否则使用本机密钥文件
+pub fn open_default() -> Arc<dyn CredentialStore> {
+    let paths = paths();
+    let source = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase(passphrase),
+        _ => KeySource::KeyFile(paths.config_dir().join(KEY_FILE)),
+    };
+    Arc::new(EncryptedFileStore::new(paths.data_file(CREDENTIALS_FILE), source))
+}
+
+pub fn load_value<T: DeserializeOwned>(store: &dyn CredentialStore) -> anyhow::Result<Option<T>> {
+    match store.load()? {
+        Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn save_value<T: Serialize>(store: &dyn CredentialStore, value: &T) -> anyhow::Result<()> {
+    store.save(&serde_json::to_vec(value)?)
+}
+
+/// 读取凭据；存储中还没有时读取旧版本的明文 JSON 文件，写入存储后删除明文文件
+pub fn load_or_migrate<T: Serialize + DeserializeOwned>(
+    store: &dyn CredentialStore,
+    legacy: &Path,
+) -> anyhow::Result<Option<T>> {
+    if let Some(value) = load_value(store)? {
+        return Ok(Some(value));
+    }
+    let Some(value) = paths::load_json::<T>(legacy)? else { return Ok(None); };
+    save_value(store, &value)?;
+    fs::remove_file(legacy)?;
+    info!("🔐 已将明文登录信息 {:?} 迁移到 {}", legacy, store.name());
+    Ok(Some(value))
+}
+
+/// 退出登录时删除凭据，连同可能残留的旧版本明文文件
+pub fn clear_all(store: &dyn CredentialStore, legacy: &Path) -> anyhow::Result<()> {
+    store.clear()?;
+    remove_if_exists(legacy)?;
+    info!("🧹 已删除保存的登录信息（{}）", store.name());
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> anyhow::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e).with_context(|| format!("删除 {:?} 失败", path)),
+        _ => Ok(()),
+    }
+}
+
+/// 加密密钥的来源
+#[derive(Clone)]
+pub enum KeySource {
+    /// 用 Argon2id 从口令派生密钥，盐随文件保存
+    Passphrase(String),
+    /// 本机密钥文件中的随机密钥，文件不存在时自动生成
+    KeyFile(PathBuf),
+}
+
+impl KeySource {
+    fn tag(&self) -> u8 {
+        match self {
+            KeySource::KeyFile(_) => 1,
+            KeySource::Passphrase(_) => 2,
+        }
+    }
+
+    fn describe(tag: u8) -> &'static str {
+        match tag {
+            1 => "本机密钥文件",
+            2 => "口令",
+            _ => "未知方式",
+        }
+    }
+}
+
+// 不在日志中打印口令
+impl std::fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeySource::Passphrase(_) => f.write_str("Passphrase(..)"),
+            KeySource::KeyFile(path) => f.debug_tuple("KeyFile").field(path).finish(),
+        }
+    }
+}
+
+/// 用 ChaCha20-Poly1305 加密后保存到文件
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    source: KeySource,
+    /// 上一次派生的盐和密钥，避免每次保存都重新计算 Argon2
+    derived: Mutex<Option<([u8; SALT_LEN], Key)>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, source: KeySource) -> Self {
+        Self { path, source, derived: Mutex::new(None) }
+    }
+
+    fn key_for(&self, salt: &[u8; SALT_LEN]) -> anyhow::Result<Key> {
+        let mut derived = self.derived.lock().unwrap();
+        if let Some((cached_salt, key)) = derived.as_ref() {
+            if cached_salt == salt {
+                return Ok(*key);
+            }
+        }
+        let key = match &self.source {
+            KeySource::Passphrase(passphrase) => {
+                let mut key = Key::default();
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| anyhow!("派生密钥失败: {}", e))?;
+                key
+            }
+            KeySource::KeyFile(path) => load_or_create_key_file(path)?,
+        };
+        *derived = Some((*salt, key));
+        Ok(key)
+    }
+
+    /// 复用已有的盐；口令第一次加密时生成新的盐，密钥文件不需要盐
+    fn salt(&self) -> [u8; SALT_LEN] {
+        if let Some((salt, _)) = self.derived.lock().unwrap().as_ref() {
+            return *salt;
+        }
+        let mut salt = [0u8; SALT_LEN];
+        if let KeySource::Passphrase(_) = self.source {
+            OsRng.fill_bytes(&mut salt);
+        }
+        salt
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "加密文件"
+    }
+
+    fn load(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read(&self.path)?;
+        if data.len() < HEADER_LEN + NONCE_LEN || &data[..MAGIC.len()] != MAGIC {
+            bail!("凭据文件格式不正确: {:?}", self.path);
+        }
+        let (header, rest) = data.split_at(HEADER_LEN);
+        let version = header[MAGIC.len()];
+        let tag = header[MAGIC.len() + 1];
+        if version != VERSION {
+            bail!("不支持的凭据文件版本: {}", version);
+        }
+        if tag != self.source.tag() {
+            bail!(
+                "凭据文件使用{}加密，当前配置的是{}",
+                KeySource::describe(tag),
+                KeySource::describe(self.source.tag())
+            );
+        }
+        let salt: [u8; SALT_LEN] = header[MAGIC.len() + 2..].try_into()?;
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key = self.key_for(&salt)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map_err(|_| anyhow!("解密凭据失败：口令或密钥文件不正确，或者文件已损坏"))?;
+        Ok(Some(plaintext))
+    }
+
+    fn save(&self, secret: &[u8]) -> anyhow::Result<()> {
+        let salt = self.salt();
+        let key = self.key_for(&salt)?;
+
+        let mut data = Vec::with_capacity(HEADER_LEN + NONCE_LEN + secret.len() + 16);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.push(self.source.tag());
+        data.extend_from_slice(&salt);
+
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(&nonce, Payload { msg: secret, aad: &data })
+            .map_err(|_| anyhow!("加密凭据失败"))?;
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        write_private(&self.path, &data)
+    }
+
+    /// 只删除凭据文件，本机密钥文件留给下次登录使用
+    fn clear(&self) -> anyhow::Result<()> {
+        remove_if_exists(&self.path)
+    }
+}
+
+/// 明文文件，只用于测试
+#[cfg(test)]
+pub struct PlaintextStore {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl PlaintextStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[cfg(test)]
+impl CredentialStore for PlaintextStore {
+    fn name(&self) -> &'static str {
+        "明文文件"
+    }
+
+    fn load(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(&self.path)?))
+    }
+
+    fn save(&self, secret: &[u8]) -> anyhow::Result<()> {
+        write_private(&self.path, secret)
+    }
+
+    fn clear(&self) -> anyhow::Result<()> {
+        remove_if_exists(&self.path)
+    }
+}
+
+fn load_or_create_key_file(path: &Path) -> anyhow::Result<Key> {
+    if path.exists() {
+        let bytes = fs::read(path)?;
+        if bytes.len() != KEY_LEN {
+            bail!("密钥文件长度不正确: {:?}", path);
+        }
+        return Ok(*Key::from_slice(&bytes));
+    }
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    write_private(path, &key).with_context(|| format!("无法创建密钥文件 {:?}", path))?;
+    info!("🔑 已生成本机密钥文件: {:?}", path);
+    Ok(key)
+}
+
+/// 写入只有当前用户可读写的文件；先写临时文件再改名
+fn write_private(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Secret {
+        sessdata: String,
+        bili_jct: Option<String>,
+    }
+
+    fn secret() -> Secret {
+        Secret { sessdata: "abc%2C123".to_string(), bili_jct: Some("csrf".to_string()) }
+    }
+
+    /// 每个测试使用单独的临时目录
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bili-credentials-{:016x}", fastrand::u64(..)));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn key_file_round_trip_and_reuse() {
+        let dir = temp_dir();
+        let key_file = dir.join(KEY_FILE);
+        let store = EncryptedFileStore::new(dir.join(CREDENTIALS_FILE), KeySource::KeyFile(key_file.clone()));
+        assert!(load_value::<Secret>(&store).unwrap().is_none());
+
+        save_value(&store, &secret()).unwrap();
+        assert_eq!(fs::read(&key_file).unwrap().len(), KEY_LEN);
+        let raw = fs::read(dir.join(CREDENTIALS_FILE)).unwrap();
+        assert!(!raw.windows(4).any(|w| w == b"csrf"));
+
+        // 新打开的存储读取同一个密钥文件
+        let reopened = EncryptedFileStore::new(dir.join(CREDENTIALS_FILE), KeySource::KeyFile(key_file));
+        assert_eq!(load_value::<Secret>(&reopened).unwrap(), Some(secret()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn passphrase_must_match() {
+        let dir = temp_dir();
+        let path = dir.join(CREDENTIALS_FILE);
+        let store = EncryptedFileStore::new(path.clone(), KeySource::Passphrase("correct horse".to_string()));
+        save_value(&store, &secret()).unwrap();
+
+        let same = EncryptedFileStore::new(path.clone(), KeySource::Passphrase("correct horse".to_string()));
+        assert_eq!(load_value::<Secret>(&same).unwrap(), Some(secret()));
+
+        let wrong = EncryptedFileStore::new(path.clone(), KeySource::Passphrase("battery staple".to_string()));
+        assert!(load_value::<Secret>(&wrong).is_err());
+
+        // 用口令加密的文件不能用密钥文件打开
+        let key_file = EncryptedFileStore::new(path, KeySource::KeyFile(dir.join(KEY_FILE)));
+        assert!(key_file.load().is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn tampered_file_is_rejected() {
+        let dir = temp_dir();
+        let path = dir.join(CREDENTIALS_FILE);
+        let store = EncryptedFileStore::new(path.clone(), KeySource::KeyFile(dir.join(KEY_FILE)));
+        save_value(&store, &secret()).unwrap();
+
+        let mut data = fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        fs::write(&path, &data).unwrap();
+        assert!(store.load().is_err());
+
+        // 文件头也参与认证
+        let mut data = fs::read(&path).unwrap();
+        data[last] ^= 0x01;
+        data[HEADER_LEN - 1] ^= 0x01;
+        fs::write(&path, &data).unwrap();
+        assert!(store.load().is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_plaintext_json_is_migrated() {
+        let dir = temp_dir();
+        let legacy = dir.join(paths::LOGIN_FILE);
+        fs::write(&legacy, serde_json::to_vec_pretty(&secret()).unwrap()).unwrap();
+
+        let store = PlaintextStore::new(dir.join("store.json"));
+        assert_eq!(load_or_migrate::<Secret>(&store, &legacy).unwrap(), Some(secret()));
+        assert!(!legacy.exists());
+        assert_eq!(load_value::<Secret>(&store).unwrap(), Some(secret()));
+
+        // 存储中已有凭据时不再读取旧文件
+        assert_eq!(load_or_migrate::<Secret>(&store, &legacy).unwrap(), Some(secret()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn clear_all_removes_store_and_legacy_file() {
+        let dir = temp_dir();
+        let legacy = dir.join(paths::LOGIN_FILE);
+        let key_file = dir.join(KEY_FILE);
+        let store = EncryptedFileStore::new(dir.join(CREDENTIALS_FILE), KeySource::KeyFile(key_file.clone()));
+        save_value(&store, &secret()).unwrap();
+        fs::write(&legacy, b"{}").unwrap();
+
+        clear_all(&store, &legacy).unwrap();
+        assert!(load_value::<Secret>(&store).unwrap().is_none());
+        assert!(!legacy.exists());
+        assert!(key_file.exists());
+        // 重复清除不报错
+        clear_all(&store, &legacy).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
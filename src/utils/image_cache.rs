@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 索引文件名（位于缓存目录中）
+const INDEX_FILE: &str = "index.json";
+/// 默认的缓存大小上限
+pub const DEFAULT_BUDGET: u64 = 200 * 1024 * 1024;
+/// 只有访问时间变化时，两次保存索引之间的最短间隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 一个 URL 对应的缓存记录；文件按内容的 SHA-256 命名，不同 URL 内容相同时共用一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    /// 内容的 SHA-256
+    pub hash: String,
+    /// 文件名：`{hash}.{ext}`
+    pub file: String,
+    pub size: u64,
+    pub content_type: String,
+    /// 最近一次访问时间（Unix 毫秒）
+    pub last_access: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    /// 按 URL 索引
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl CacheIndex {
+    /// 不重复计算共用的文件
+    fn total_bytes(&self) -> u64 {
+        let mut seen = std::collections::HashSet::new();
+        self.entries
+            .values()
+            .filter(|e| seen.insert(e.hash.as_str()))
+            .map(|e| e.size)
+            .sum()
+    }
+}
+
+/// 缓存统计，设置页面显示
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub files: usize,
+    pub total_bytes: u64,
+    pub budget: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct Inner {
+    index: CacheIndex,
+    hits: u64,
+    misses: u64,
+    /// 索引有还没保存的变化（包括访问时间）
+    dirty: bool,
+    saved_at: Option<Instant>,
+}
+
+/// 头像和封面的本地缓存：按真实格式保存，记录元数据索引，超出大小上限时按最近最少使用淘汰
+pub struct ImageCache {
+    dir: PathBuf,
+    budget: u64,
+    client: reqwest::Client,
+    inner: Mutex<Inner>,
+    /// 保证索引按顺序写入，旧的快照不会覆盖新的
+    save_lock: tokio::sync::Mutex<()>,
+}
+
+/// 全局图片缓存，位于 [`super::paths`] 的缓存目录下
+pub fn image_cache() -> &'static ImageCache {
+    static CACHE: OnceLock<ImageCache> = OnceLock::new();
+    CACHE.get_or_init(|| ImageCache::open(super::paths::paths().image_cache_dir(), DEFAULT_BUDGET))
+}
+
+impl ImageCache {
+    /// 打开缓存目录并读取索引，丢弃文件已经不存在的记录
+    pub fn open(dir: PathBuf, budget: u64) -> Self {
+        let _ = fs::create_dir_all(&dir);
+        let mut index: CacheIndex = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        index.entries.retain(|_, e| dir.join(&e.file).exists());
+        info!("📁 缓存目录: {:?}，{} 条记录", dir, index.entries.len());
+
+        let client = reqwest::Client::builder()
+            .user_agent(crate::api::client::USER_AGENT)
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            dir,
+            budget,
+            client,
+            inner: Mutex::new(Inner { index, hits: 0, misses: 0, dirty: false, saved_at: None }),
+            save_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// 获取图片的本地路径，没有缓存时下载；需要在 Tokio runtime 中执行
+    pub async fn fetch(&self, url: &str) -> anyhow::Result<Arc<Path>> {
+        let url = url.replacen("http://", "https://", 1);
+        if let Some(path) = self.lookup(&url) {
+            // 命中只改变访问时间，不必每次都写索引
+            self.persist(false).await;
+            return Ok(path);
+        }
+
+        let bytes = self
+            .client
+            .get(&url)
+            .header("Referer", crate::api::client::DEFAULT_REFERER)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        self.store(url, &bytes).await
+    }
+
+    /// 保存下载到的图片并记录到索引
+    async fn store(&self, url: String, bytes: &[u8]) -> anyhow::Result<Arc<Path>> {
+        let (ext, content_type) = sniff_format(bytes)
+            .ok_or_else(|| anyhow::anyhow!("不是支持的图片格式: {}", url))?;
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let file = format!("{}.{}", hash, ext);
+        let path = self.dir.join(&file);
+
+        if !path.exists() {
+            // 先写临时文件再改名，避免中途失败留下不完整的图片
+            fs::create_dir_all(&self.dir)?;
+            write_atomic(&path, bytes).await?;
+        }
+
+        let entry = CacheEntry {
+            url: url.clone(),
+            hash,
+            file,
+            size: bytes.len() as u64,
+            content_type: content_type.to_string(),
+            last_access: now_millis(),
+        };
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.misses += 1;
+            inner.index.entries.insert(url, entry);
+            self.evict(&mut inner.index);
+            inner.dirty = true;
+        }
+        self.persist(true).await;
+        Ok(Arc::from(path.canonicalize()?.as_path()))
+    }
+
+    /// 命中时更新访问时间
+    fn lookup(&self, url: &str) -> Option<Arc<Path>> {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.index.entries.get_mut(url)?;
+        let path = self.dir.join(&entry.file).canonicalize().ok()?;
+        entry.last_access = now_millis();
+        inner.hits += 1;
+        inner.dirty = true;
+        Some(Arc::from(path.as_path()))
+    }
+
+    /// 按最近访问时间从旧到新淘汰，直到总大小不超过上限；文件没有其他记录引用时才删除
+    fn evict(&self, index: &mut CacheIndex) {
+        let mut total = index.total_bytes();
+        if total <= self.budget {
+            return;
+        }
+        let mut by_age: Vec<(u64, String)> = index
+            .entries
+            .values()
+            .map(|e| (e.last_access, e.url.clone()))
+            .collect();
+        by_age.sort();
+
+        for (_, url) in by_age {
+            if total <= self.budget {
+                break;
+            }
+            let Some(entry) = index.entries.remove(&url) else { continue };
+            if index.entries.values().any(|e| e.hash == entry.hash) {
+                continue;
+            }
+            let _ = fs::remove_file(self.dir.join(&entry.file));
+            total = total.saturating_sub(entry.size);
+            debug!("🗑️ 淘汰: {} ({} bytes)", entry.url, entry.size);
+        }
+    }
+
+    /// 在锁外把索引写到磁盘；`force` 为 false 时距离上次保存不到 [`SAVE_INTERVAL`] 就先不写
+    async fn persist(&self, force: bool) {
+        let _guard = self.save_lock.lock().await;
+        let json = {
+            let mut inner = self.inner.lock().unwrap();
+            let recent = inner.saved_at.is_some_and(|t| t.elapsed() < SAVE_INTERVAL);
+            if !inner.dirty || (!force && recent) {
+                return;
+            }
+            match serde_json::to_vec(&inner.index) {
+                Ok(json) => {
+                    inner.dirty = false;
+                    inner.saved_at = Some(Instant::now());
+                    json
+                }
+                Err(e) => {
+                    error!("❌ 保存索引失败: {}", e);
+                    return;
+                }
+            }
+        };
+        if let Err(e) = write_atomic(&self.dir.join(INDEX_FILE), &json).await {
+            error!("❌ 保存索引失败: {}", e);
+            self.inner.lock().unwrap().dirty = true;
+        }
+    }
+
+    /// 同步保存索引，只用于不在异步环境中的删除操作
+    fn save_now(&self, inner: &mut Inner) {
+        if let Err(e) = serde_json::to_string(&inner.index)
+            .map_err(anyhow::Error::from)
+            .and_then(|s| fs::write(self.dir.join(INDEX_FILE), s).map_err(anyhow::Error::from))
+        {
+            error!("❌ 保存索引失败: {}", e);
+            return;
+        }
+        inner.dirty = false;
+        inner.saved_at = Some(Instant::now());
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let inner = self.inner.lock().unwrap();
+        let files = inner
+            .index
+            .entries
+            .values()
+            .map(|e| e.hash.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        CacheStats {
+            entries: inner.index.entries.len(),
+            files,
+            total_bytes: inner.index.total_bytes(),
+            budget: self.budget,
+            hits: inner.hits,
+            misses: inner.misses,
+        }
+    }
+
+    /// 删除一个 URL 的缓存；文件还被其他 URL 共用时只删除记录
+    pub fn remove(&self, url: &str) {
+        let url = url.replacen("http://", "https://", 1);
+        let mut inner = self.inner.lock().unwrap();
+        let Some(entry) = inner.index.entries.remove(&url) else { return };
+        if !inner.index.entries.values().any(|e| e.hash == entry.hash) {
+            let _ = fs::remove_file(self.dir.join(&entry.file));
+        }
+        self.save_now(&mut inner);
+        debug!("🗑️ 已删除缓存: {}", entry.url);
+    }
+
+    /// 删除全部缓存文件和索引
+    pub fn clear(&self) -> anyhow::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.index.entries.clear();
+        inner.hits = 0;
+        inner.misses = 0;
+        inner.dirty = false;
+        if self.dir.exists() {
+            for entry in fs::read_dir(&self.dir)? {
+                let path = entry?.path();
+                if path.is_file() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        info!("🧹 已清除图片缓存");
+        Ok(())
+    }
+}
+
+/// 按文件头判断图片格式，返回扩展名和 MIME 类型；URL 的后缀不可靠（`@672w_378h.webp` 之类的缩放后缀）
+fn sniff_format(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some(("jpg", "image/jpeg")),
+        [0x89, b'P', b'N', b'G', ..] => Some(("png", "image/png")),
+        [b'G', b'I', b'F', b'8', ..] => Some(("gif", "image/gif")),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(("webp", "image/webp")),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'a', b'v', b'i', b'f', ..] => Some(("avif", "image/avif")),
+        [b'B', b'M', ..] => Some(("bmp", "image/bmp")),
+        _ => None,
+    }
+}
+
+/// 写到同一目录下名字唯一的临时文件再改名；并发写入相同内容时，目标已经存在也算成功
+async fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let tmp = path.with_file_name(format!("{}.{}-{:08x}.tmp", name, std::process::id(), fastrand::u32(..)));
+    tokio::fs::write(&tmp, bytes).await?;
+    match tokio::fs::rename(&tmp, path).await {
+        Ok(()) => Ok(()),
+        Err(_) if path.exists() => {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            Err(e)
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 以 KB/MB 显示字节数
+pub fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用单独的临时目录
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("bili-image-cache-{:016x}", fastrand::u64(..)))
+    }
+
+    /// 10 字节的 PNG“图片”，`n` 不同时内容不同
+    fn png(n: u8) -> Vec<u8> {
+        vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, n]
+    }
+
+    fn set_access(cache: &ImageCache, url: &str, at: u64) {
+        cache.inner.lock().unwrap().index.entries.get_mut(url).unwrap().last_access = at;
+    }
+
+    fn urls(cache: &ImageCache) -> Vec<String> {
+        let mut urls: Vec<_> = cache.inner.lock().unwrap().index.entries.keys().cloned().collect();
+        urls.sort();
+        urls
+    }
+
+    #[test]
+    fn sniffs_formats_from_header() {
+        assert_eq!(sniff_format(&png(0)), Some(("png", "image/png")));
+        assert_eq!(sniff_format(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(("jpg", "image/jpeg")));
+        assert_eq!(sniff_format(b"GIF89a"), Some(("gif", "image/gif")));
+        assert_eq!(sniff_format(b"RIFF\x10\0\0\0WEBPVP8 "), Some(("webp", "image/webp")));
+        assert_eq!(sniff_format(b"<html>"), None);
+        assert_eq!(sniff_format(b"RIFF\x10\0\0\0WAVE"), None);
+        assert_eq!(sniff_format(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_over_budget() {
+        let dir = temp_dir();
+        let cache = ImageCache::open(dir.clone(), 20);
+        cache.store("https://i0.hdslb.com/a.png".into(), &png(1)).await.unwrap();
+        cache.store("https://i0.hdslb.com/b.png".into(), &png(2)).await.unwrap();
+        set_access(&cache, "https://i0.hdslb.com/a.png", 2);
+        set_access(&cache, "https://i0.hdslb.com/b.png", 1);
+
+        cache.store("https://i0.hdslb.com/c.png".into(), &png(3)).await.unwrap();
+
+        assert_eq!(urls(&cache), ["https://i0.hdslb.com/a.png", "https://i0.hdslb.com/c.png"]);
+        let b_file = format!("{:x}.png", Sha256::digest(png(2)));
+        assert!(!dir.join(b_file).exists());
+        assert_eq!(cache.stats().total_bytes, 20);
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn shared_file_survives_eviction_of_one_url() {
+        let dir = temp_dir();
+        let cache = ImageCache::open(dir.clone(), 100);
+        // 同一张头像的两个地址共用一个文件
+        cache.store("https://i0.hdslb.com/face.png".into(), &png(1)).await.unwrap();
+        cache.store("https://i0.hdslb.com/face.png@40w".into(), &png(1)).await.unwrap();
+        cache.store("https://i0.hdslb.com/cover.png".into(), &png(2)).await.unwrap();
+        set_access(&cache, "https://i0.hdslb.com/face.png", 1);
+        set_access(&cache, "https://i0.hdslb.com/cover.png", 2);
+        set_access(&cache, "https://i0.hdslb.com/face.png@40w", 3);
+        assert_eq!(cache.stats().files, 2);
+
+        let cache = ImageCache { budget: 10, ..cache };
+        {
+            let mut inner = cache.inner.lock().unwrap();
+            cache.evict(&mut inner.index);
+        }
+
+        assert_eq!(urls(&cache), ["https://i0.hdslb.com/face.png@40w"]);
+        assert!(dir.join(format!("{:x}.png", Sha256::digest(png(1)))).exists());
+        assert!(!dir.join(format!("{:x}.png", Sha256::digest(png(2)))).exists());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn reloads_index_with_access_times() {
+        let dir = temp_dir();
+        let cache = ImageCache::open(dir.clone(), DEFAULT_BUDGET);
+        let path = cache.store("https://i0.hdslb.com/a.png".into(), &png(1)).await.unwrap();
+        set_access(&cache, "https://i0.hdslb.com/a.png", 42);
+        cache.inner.lock().unwrap().dirty = true;
+        cache.persist(true).await;
+        // 文件已经不存在的记录在打开时丢弃
+        fs::write(dir.join("gone.png"), b"x").unwrap();
+        {
+            let mut inner = cache.inner.lock().unwrap();
+            let mut gone = inner.index.entries["https://i0.hdslb.com/a.png"].clone();
+            gone.url = "https://i0.hdslb.com/gone.png".into();
+            gone.file = "gone.png".into();
+            inner.index.entries.insert(gone.url.clone(), gone);
+            inner.dirty = true;
+        }
+        cache.persist(true).await;
+        fs::remove_file(dir.join("gone.png")).unwrap();
+
+        let reopened = ImageCache::open(dir.clone(), DEFAULT_BUDGET);
+        assert_eq!(urls(&reopened), ["https://i0.hdslb.com/a.png"]);
+        let entry = reopened.inner.lock().unwrap().index.entries["https://i0.hdslb.com/a.png"].clone();
+        assert_eq!(entry.last_access, 42);
+        assert_eq!(entry.content_type, "image/png");
+        assert_eq!(reopened.lookup("https://i0.hdslb.com/a.png").as_deref(), Some(&*path));
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn concurrent_stores_of_same_bytes_succeed() {
+        let dir = temp_dir();
+        let cache = ImageCache::open(dir.clone(), DEFAULT_BUDGET);
+        let bytes = png(7);
+        let (a, b) = tokio::join!(
+            cache.store("https://i0.hdslb.com/a.png".into(), &bytes),
+            cache.store("https://i0.hdslb.com/a.png@96w".into(), &bytes),
+        );
+        assert_eq!(a.unwrap(), b.unwrap());
+        let leftovers = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|x| x == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        fs::remove_dir_all(dir).ok();
+    }
+}
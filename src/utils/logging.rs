@@ -0,0 +1,555 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Local};
+
+/// 日志中需要遮盖的 Cookie 和令牌名（不区分大小写）
+pub const SECRET_KEYS: [&str; 6] = ["SESSDATA", "bili_jct", "csrf", "refresh_token", "ac_time_value", "qrcode_key"];
+/// 遮盖后显示的值
+pub const MASK: &str = "***";
+
+/// 启动时的日志级别，例如 `BILI_LOG=debug`
+pub const LEVEL_ENV: &str = "BILI_LOG";
+/// 日志文件名（位于 [`super::paths::AppPaths::log_dir`]）
+const LOG_FILE: &str = "app.log";
+/// 日志文件超过这个大小时轮转
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// 保留的旧日志文件数：`app.log.1` 到 `app.log.3`
+const MAX_ROTATED_FILES: usize = 3;
+/// 内存中保留的日志条数，日志页面从这里读取
+const BUFFER_CAPACITY: usize = 5000;
+
+/// 按级别打印日志，格式和 `println!` 相同；目标是调用处的模块路径，已知的 Cookie 和令牌会被遮盖
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        $crate::utils::logging::log($level, module_path!(), format_args!($($arg)*))
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { log_at!($crate::utils::logging::Level::Error, $($arg)*) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { log_at!($crate::utils::logging::Level::Warn, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { log_at!($crate::utils::logging::Level::Info, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { log_at!($crate::utils::logging::Level::Debug, $($arg)*) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { log_at!($crate::utils::logging::Level::Trace, $($arg)*) };
+}
+
+/// 日志级别，越往后越详细
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Level> {
+        Level::ALL.into_iter().find(|l| l.label().eq_ignore_ascii_case(s.trim()))
+    }
+
+    fn from_u8(v: u8) -> Level {
+        Level::ALL.into_iter().find(|l| *l as u8 == v).unwrap_or(Level::Info)
+    }
+}
+
+/// 一条日志
+#[derive(Clone, Debug)]
+pub struct Record {
+    /// 递增的序号，日志页面据此判断有没有新日志
+    pub seq: u64,
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// 产生日志的模块，例如 `views::home`
+    pub target: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<5} {}: {}",
+            self.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.level.label(),
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// 全局日志：按级别过滤后输出到终端、日志文件和内存缓冲区
+pub struct Logger {
+    level: AtomicU8,
+    next_seq: AtomicU64,
+    records: Mutex<VecDeque<Record>>,
+    file: Mutex<Option<LogFile>>,
+}
+
+pub fn logger() -> &'static Logger {
+    static LOGGER: OnceLock<Logger> = OnceLock::new();
+    LOGGER.get_or_init(|| {
+        let level = std::env::var(LEVEL_ENV)
+            .ok()
+            .and_then(|v| Level::parse(&v))
+            .unwrap_or(Level::Info);
+        Logger::new(level)
+    })
+}
+
+/// 打开日志文件，之前已经产生的日志会补写进去；需要在 [`super::paths::init`] 之后调用
+pub fn init(dir: &Path) {
+    let logger = logger();
+    match LogFile::open(dir.join(LOG_FILE)) {
+        Ok(mut file) => {
+            for record in logger.records.lock().unwrap().iter() {
+                file.write_line(&record.to_string());
+            }
+            *logger.file.lock().unwrap() = Some(file);
+        }
+        Err(e) => log(Level::Warn, module_path!(), format_args!("⚠️ 无法打开日志文件: {:?} - {}", dir, e)),
+    }
+}
+
+pub fn log(level: Level, target: &'static str, args: fmt::Arguments) {
+    logger().log(level, target, args);
+}
+
+impl Logger {
+    fn new(level: Level) -> Self {
+        Logger {
+            level: AtomicU8::new(level as u8),
+            next_seq: AtomicU64::new(1),
+            records: Mutex::new(VecDeque::with_capacity(BUFFER_CAPACITY)),
+            file: Mutex::new(None),
+        }
+    }
+
+    /// 记录一条日志；整个程序只有这里直接输出到控制台，其余输出都经过日志宏
+    #[allow(clippy::print_stdout)]
+    fn log(&self, level: Level, target: &'static str, args: fmt::Arguments) {
+        #[cfg(test)]
+        capture::push(&redact(&args.to_string()));
+        if level > self.level() {
+            return;
+        }
+
+        let message = args.to_string();
+        let record = Record {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            time: Local::now(),
+            level,
+            target: target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::")).unwrap_or(target),
+            message: redact(&message).into_owned(),
+        };
+        let line = record.to_string();
+        println!("{}", line);
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            file.write_line(&line);
+        }
+
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= BUFFER_CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn level(&self) -> Level {
+        Level::from_u8(self.level.load(Ordering::Relaxed))
+    }
+
+    pub fn set_level(&self, level: Level) {
+        self.level.store(level as u8, Ordering::Relaxed);
+    }
+
+    /// 最新一条日志的序号，没有日志时为 0
+    pub fn last_seq(&self) -> u64 {
+        self.records.lock().unwrap().back().map(|r| r.seq).unwrap_or(0)
+    }
+
+    /// 满足条件的最近 `limit` 条日志，按时间顺序排列
+    pub fn recent(&self, limit: usize, filter: impl Fn(&Record) -> bool) -> Vec<Record> {
+        let records = self.records.lock().unwrap();
+        let mut matched: Vec<Record> = records.iter().rev().filter(|r| filter(r)).take(limit).cloned().collect();
+        matched.reverse();
+        matched
+    }
+
+    /// 清空内存中的日志，日志文件不受影响
+    pub fn clear(&self) {
+        self.records.lock().unwrap().clear();
+    }
+
+    /// 当前日志文件的路径，没有打开文件时为 `None`
+    pub fn file_path(&self) -> Option<PathBuf> {
+        self.file.lock().unwrap().as_ref().map(|f| f.path.clone())
+    }
+}
+
+/// 追加写入的日志文件，超过 [`MAX_FILE_BYTES`] 时改名为 `app.log.1`，旧文件依次后移
+struct LogFile {
+    path: PathBuf,
+    file: fs::File,
+    size: u64,
+    /// 轮转的大小，正常为 [`MAX_FILE_BYTES`]
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        Self::open_with_limit(path, MAX_FILE_BYTES)
+    }
+
+    fn open_with_limit(path: PathBuf, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size, max_bytes })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = self.rotated(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated(1))?;
+        *self = Self::open_with_limit(self.path.clone(), self.max_bytes)?;
+        Ok(())
+    }
+
+    /// 写入失败时只能放弃，不能再记日志
+    fn write_line(&mut self, line: &str) {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes && self.rotate().is_err() {
+            return;
+        }
+        if writeln!(self.file, "{}", line).is_ok() {
+            self.size += len;
+        }
+    }
+}
+
+/// 遮盖文本中 [`SECRET_KEYS`] 的值，支持 `key=value`（Cookie、Set-Cookie、查询参数）、
+/// `"key":"value"`（JSON，包括转义后的 JSON）和 `key: "value"`（`Debug` 输出）几种写法
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let bytes = text.as_bytes();
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let key = SECRET_KEYS.iter().find(|key| {
+            bytes
+                .get(i..i + key.len())
+                .is_some_and(|s| s.eq_ignore_ascii_case(key.as_bytes()))
+        });
+        let Some(key) = key else {
+            i += 1;
+            continue;
+        };
+        i += key.len();
+        if let Some((start, end)) = value_span(bytes, i) {
+            out.push_str(&text[copied..start]);
+            out.push_str(MASK);
+            copied = end;
+            i = end;
+        }
+    }
+    if copied == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[copied..]);
+    Cow::Owned(out)
+}
+
+/// 找出紧跟在键名后面的值的范围；键名后面不是 `=` 或 `:` 时说明只是普通文字，返回 `None`
+fn value_span(bytes: &[u8], mut i: usize) -> Option<(usize, usize)> {
+    let is_quote = |b: u8| matches!(b, b'"' | b'\'' | b'\\');
+    let skip = |i: &mut usize, pred: &dyn Fn(u8) -> bool| {
+        while bytes.get(*i).is_some_and(|&b| pred(b)) {
+            *i += 1;
+        }
+    };
+
+    // JSON 键名的右引号
+    skip(&mut i, &is_quote);
+    skip(&mut i, &|b| b == b' ');
+    let assign = match bytes.get(i) {
+        Some(b'=') => true,
+        Some(b':') => false,
+        _ => return None,
+    };
+    i += 1;
+    skip(&mut i, &|b| b == b' ');
+    // `Debug` 输出中的 `Option`
+    if bytes[i..].starts_with(b"Some(") {
+        i += "Some(".len();
+    }
+    let value_start = i;
+    skip(&mut i, &is_quote);
+    let quoted = i > value_start;
+    let start = i;
+
+    // Cookie 值里可能有逗号，只有 JSON 的裸值才在逗号处结束
+    let end_of_value = |b: u8| {
+        is_quote(b)
+            || b.is_ascii_whitespace()
+            || (!quoted && matches!(b, b';' | b'&' | b'}' | b']' | b')'))
+            || (!quoted && !assign && b == b',')
+    };
+    while bytes.get(i).is_some_and(|&b| !end_of_value(b)) {
+        i += 1;
+    }
+    (i > start).then_some((start, i))
+}
+
+/// 测试中收集当前线程打印的日志
+#[cfg(test)]
+pub mod capture {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LINES: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    }
+
+    pub(super) fn push(line: &str) {
+        LINES.with(|lines| {
+            if let Some(lines) = lines.borrow_mut().as_mut() {
+                lines.push(line.to_string());
+            }
+        });
+    }
+
+    /// 开始收集，清空之前收集的内容
+    pub fn start() {
+        LINES.with(|lines| *lines.borrow_mut() = Some(Vec::new()));
+    }
+
+    /// 停止收集，返回收集到的全部日志
+    pub fn finish() -> String {
+        LINES.with(|lines| lines.borrow_mut().take().unwrap_or_default().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSDATA: &str = "7f1c9a2e%2C1767225600%2C4b3a1%2A71CjD";
+    const BILI_JCT: &str = "0a1b2c3d4e5f60718293a4b5c6d7e8f9";
+    const REFRESH_TOKEN: &str = "c4a3e1f2d0b9a8c7e6f5d4c3b2a1f0e9";
+    const QRCODE_KEY: &str = "8f7e6d5c4b3a29181716151413121110";
+
+    fn assert_clean(text: &str) {
+        for secret in [SESSDATA, BILI_JCT, REFRESH_TOKEN, QRCODE_KEY] {
+            assert!(!text.contains(secret), "泄露了 {}: {}", secret, text);
+        }
+    }
+
+    #[test]
+    fn redacts_cookie_headers() {
+        let cookie = format!("DedeUserID=12345; SESSDATA={}; bili_jct={}; sid=abc", SESSDATA, BILI_JCT);
+        assert_eq!(redact(&cookie), "DedeUserID=12345; SESSDATA=***; bili_jct=***; sid=abc");
+
+        let set_cookie = format!("set-cookie: SESSDATA={}; Path=/; Domain=bilibili.com; HttpOnly", SESSDATA);
+        assert_eq!(redact(&set_cookie), "set-cookie: SESSDATA=***; Path=/; Domain=bilibili.com; HttpOnly");
+
+        // 未编码的逗号也属于 Cookie 值
+        assert_eq!(redact("SESSDATA=a,b,c*71; Path=/"), "SESSDATA=***; Path=/");
+    }
+
+    #[test]
+    fn redacts_query_strings_and_json() {
+        let url = format!("https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key={}&source=main", QRCODE_KEY);
+        assert_eq!(
+            redact(&url),
+            "https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key=***&source=main"
+        );
+
+        let body = format!(
+            r#"{{"code":0,"data":{{"url":"https://passport.biligame.com/x/passport-mng/crossDomain?DedeUserID=12345&SESSDATA={}&bili_jct={}&gourl=https%3A%2F%2Fwww.bilibili.com","refresh_token":"{}","timestamp":1700000000000,"code":0}}}}"#,
+            SESSDATA, BILI_JCT, REFRESH_TOKEN
+        );
+        let redacted = redact(&body);
+        assert_clean(&redacted);
+        assert!(redacted.contains(r#""refresh_token":"***""#));
+        assert!(redacted.contains("DedeUserID=12345&SESSDATA=***&bili_jct=***&gourl="));
+        assert!(redacted.contains(r#""timestamp":1700000000000"#));
+
+        // 转义后的 JSON 和数字值
+        let escaped = format!(r#"{{\"qrcode_key\":\"{}\"}}"#, QRCODE_KEY);
+        assert_eq!(redact(&escaped), r#"{\"qrcode_key\":\"***\"}"#);
+        assert_eq!(redact(r#"{"csrf": 123456, "aid": 1}"#), r#"{"csrf": ***, "aid": 1}"#);
+
+        // 刷新 Cookie 时的表单
+        assert_eq!(
+            redact("csrf=abc&refresh_csrf=def&source=main_web"),
+            "csrf=***&refresh_csrf=***&source=main_web"
+        );
+    }
+
+    #[test]
+    fn redacts_debug_output() {
+        let debug = format!(r#"Cookies {{ SESSDATA: "{}", bili_jct: Some("{}") }}"#, SESSDATA, BILI_JCT);
+        assert_clean(&redact(&debug));
+        let lowercase = format!("sessdata={}", SESSDATA);
+        assert_eq!(redact(&lowercase), "sessdata=***");
+    }
+
+    #[test]
+    fn leaves_other_text_alone() {
+        for text in [
+            "缺少 qrcode_key，无法轮询",
+            "✅ 登录成功: 用户名 = 测试",
+            "https://api.bilibili.com/x/web-interface/view?bvid=BV1xx411c7mD",
+            "SESSDATA=",
+        ] {
+            assert!(matches!(redact(text), Cow::Borrowed(_)), "{}", text);
+        }
+    }
+
+    #[test]
+    fn emitted_lines_are_redacted() {
+        capture::start();
+        info!("  Cookie: SESSDATA={}; bili_jct={}", SESSDATA, BILI_JCT);
+        trace!("令牌: {:?}", format!("refresh_token={}", REFRESH_TOKEN));
+        let output = capture::finish();
+        assert_clean(&output);
+        assert!(output.contains("SESSDATA=***; bili_jct=***"));
+    }
+
+    /// 每个测试使用单独的临时目录
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bili-logging-{:016x}", fastrand::u64(..)));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_levels() {
+        assert_eq!(Level::parse("debug"), Some(Level::Debug));
+        assert_eq!(Level::parse(" WARN "), Some(Level::Warn));
+        assert_eq!(Level::parse("Trace"), Some(Level::Trace));
+        assert_eq!(Level::parse("verbose"), None);
+        assert_eq!(Level::parse(""), None);
+        for level in Level::ALL {
+            assert_eq!(Level::parse(level.label()), Some(level));
+            assert_eq!(Level::from_u8(level as u8), level);
+        }
+    }
+
+    #[test]
+    fn filters_by_level() {
+        let logger = Logger::new(Level::Warn);
+        logger.log(Level::Info, "my_gpui_app::views::home", format_args!("信息"));
+        logger.log(Level::Debug, "my_gpui_app::views::home", format_args!("调试"));
+        assert_eq!(logger.last_seq(), 0);
+
+        logger.log(Level::Error, "my_gpui_app::views::home", format_args!("错误"));
+        logger.log(Level::Warn, "other", format_args!("警告"));
+        let records = logger.recent(10, |_| true);
+        assert_eq!(records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>(), ["错误", "警告"]);
+        assert_eq!(records[0].target, "views::home");
+        assert_eq!(logger.last_seq(), records[1].seq);
+
+        logger.set_level(Level::Trace);
+        logger.log(Level::Trace, "other", format_args!("跟踪"));
+        assert_eq!(logger.recent(1, |r| r.level == Level::Trace)[0].message, "跟踪");
+        assert_eq!(logger.recent(10, |r| r.level <= Level::Warn).len(), 2);
+    }
+
+    #[test]
+    fn rotates_and_keeps_limited_files() {
+        let dir = temp_dir();
+        let path = dir.join(LOG_FILE);
+        // 每行 10 字节（含换行），每个文件放两行
+        let mut file = LogFile::open_with_limit(path.clone(), 20).unwrap();
+        for i in 0..12 {
+            file.write_line(&format!("line-{:04}", i));
+        }
+        let read = |p: &Path| fs::read_to_string(p).unwrap();
+        assert_eq!(read(&path), "line-0010\nline-0011\n");
+        assert_eq!(read(&file.rotated(1)), "line-0008\nline-0009\n");
+        assert_eq!(read(&file.rotated(2)), "line-0006\nline-0007\n");
+        assert_eq!(read(&file.rotated(MAX_ROTATED_FILES)), "line-0004\nline-0005\n");
+        assert!(!file.rotated(MAX_ROTATED_FILES + 1).exists());
+
+        // 重新打开时接着原来的大小计算
+        let mut reopened = LogFile::open_with_limit(path.clone(), 20).unwrap();
+        assert_eq!(reopened.size, 20);
+        reopened.write_line("line-0012");
+        assert_eq!(read(&path), "line-0012\n");
+        assert_eq!(read(&file.rotated(1)), "line-0010\nline-0011\n");
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn long_line_is_written_to_empty_file() {
+        let dir = temp_dir();
+        let path = dir.join(LOG_FILE);
+        let mut file = LogFile::open_with_limit(path.clone(), 5).unwrap();
+        file.write_line("longer than the limit");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "longer than the limit\n");
+        assert!(!file.rotated(1).exists());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn cookie_debug_output_is_masked() {
+        let cookies = crate::state::app_state::Cookies {
+            SESSDATA: SESSDATA.to_string(),
+            DedeUserID: Some("12345".to_string()),
+            DedeUserID__ckMd5: None,
+            bili_jct: Some(BILI_JCT.to_string()),
+            sid: None,
+        };
+        let debug = format!("{:?}", cookies);
+        assert_clean(&debug);
+        assert!(debug.contains("12345"));
+
+        let jar = crate::api::client::BiliClient::new(Default::default());
+        jar.cookie_jar().set_header(&format!("SESSDATA={}; bili_jct={}", SESSDATA, BILI_JCT));
+        assert_clean(&format!("{:?}", jar.cookie_jar()));
+    }
+}
@@ -0,0 +1,332 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::de::DeserializeOwned;
+
+/// 各平台目录下使用的应用目录名
+const APP_DIR_NAME: &str = env!("CARGO_PKG_NAME");
+/// 便携模式根目录的环境变量
+pub const HOME_ENV: &str = "BILI_APP_HOME";
+/// 便携模式根目录的命令行参数：`--home <dir>` 或 `--home=<dir>`
+const HOME_FLAG: &str = "--home";
+/// 使用可执行文件旁边的 `data` 目录作为便携模式根目录
+const PORTABLE_FLAG: &str = "--portable";
+
+/// 旧版本的明文登录信息文件（位于数据目录），启动时迁移到加密的凭据存储
+pub const LOGIN_FILE: &str = "bili_cookies.json";
+/// 图片缓存目录（位于缓存目录）
+const IMAGE_CACHE_DIR: &str = "images";
+/// 日志目录（位于数据目录）
+const LOG_DIR: &str = "logs";
+/// 导出的 HAR 文件目录（位于数据目录）
+const NETWORK_DIR: &str = "network";
+/// 导出的字幕目录（位于数据目录）
+const EXPORT_DIR: &str = "subtitle_exports";
+
+/// 旧版本放在工作目录下、已经不再使用的缓存目录（只提示，不删除）
+const LEGACY_CACHE_DIRS: [&str; 2] = ["avatar_cache", "cover_cache"];
+/// 旧版本放在工作目录下的图片缓存目录
+const LEGACY_IMAGE_CACHE_DIR: &str = "image_cache";
+
+/// 配置、数据和缓存目录
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    config: PathBuf,
+    data: PathBuf,
+    cache: PathBuf,
+    /// 是否为便携模式（所有目录都在同一个根目录下）
+    portable: bool,
+}
+
+static PATHS: OnceLock<AppPaths> = OnceLock::new();
+
+/// 根据命令行参数和环境变量确定目录，并迁移工作目录下的旧文件；需要在读写任何文件之前调用
+pub fn init(args: impl IntoIterator<Item = String>) -> &'static AppPaths {
+    let root = home_from_args(args).or_else(home_from_env);
+    let paths = PATHS.get_or_init(|| AppPaths::resolve(root));
+    info!(
+        "📁 配置目录: {:?}，数据目录: {:?}，缓存目录: {:?}{}",
+        paths.config,
+        paths.data,
+        paths.cache,
+        if paths.portable { "（便携模式）" } else { "" }
+    );
+    paths.migrate_legacy_files();
+    paths
+}
+
+/// 当前使用的目录；没有调用 [`init`] 时只读取环境变量
+pub fn paths() -> &'static AppPaths {
+    PATHS.get_or_init(|| AppPaths::resolve(home_from_env()))
+}
+
+fn home_from_env() -> Option<PathBuf> {
+    std::env::var_os(HOME_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 从命令行参数中找出便携模式根目录
+fn home_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == HOME_FLAG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(dir) = arg.strip_prefix(HOME_FLAG).and_then(|s| s.strip_prefix('=')) {
+            return Some(PathBuf::from(dir));
+        }
+        if arg == PORTABLE_FLAG {
+            let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+            return Some(exe_dir.join("data"));
+        }
+    }
+    None
+}
+
+impl AppPaths {
+    /// 指定根目录时使用便携模式，否则使用平台目录：
+    /// Linux 为 `$XDG_CONFIG_HOME`、`$XDG_DATA_HOME`、`$XDG_CACHE_HOME`，
+    /// macOS 为 `~/Library/Application Support` 和 `~/Library/Caches`，Windows 为 `%APPDATA%` 和 `%LOCALAPPDATA%`
+    pub fn resolve(root: Option<PathBuf>) -> Self {
+        if let Some(root) = root {
+            return Self {
+                config: root.join("config"),
+                data: root.join("data"),
+                cache: root.join("cache"),
+                portable: true,
+            };
+        }
+
+        // 找不到主目录时退回到工作目录下的 `.{APP_DIR_NAME}`，至少保证能用
+        let fallback = || PathBuf::from(format!(".{}", APP_DIR_NAME));
+        let dir = |base: Option<PathBuf>, sub: &str| {
+            base.map(|b| b.join(APP_DIR_NAME)).unwrap_or_else(|| fallback().join(sub))
+        };
+        Self {
+            config: dir(dirs::config_dir(), "config"),
+            data: dir(dirs::data_dir(), "data"),
+            cache: dir(dirs::cache_dir(), "cache"),
+            portable: false,
+        }
+    }
+
+    pub fn config_dir(&self) -> &Path {
+        &self.config
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache
+    }
+
+    pub fn is_portable(&self) -> bool {
+        self.portable
+    }
+
+    /// 数据目录下的文件
+    pub fn data_file(&self, name: &str) -> PathBuf {
+        self.data.join(name)
+    }
+
+    pub fn image_cache_dir(&self) -> PathBuf {
+        self.cache.join(IMAGE_CACHE_DIR)
+    }
+
+    pub fn log_dir(&self) -> PathBuf {
+        self.data.join(LOG_DIR)
+    }
+
+    pub fn network_dir(&self) -> PathBuf {
+        self.data.join(NETWORK_DIR)
+    }
+
+    pub fn export_dir(&self) -> PathBuf {
+        self.data.join(EXPORT_DIR)
+    }
+
+    /// 把旧版本放在工作目录下的文件移到新目录：登录信息、图片缓存和导出的字幕直接搬过去；
+    /// 已经被图片缓存取代的头像/封面缓存目录不动，只提示可以手动删除；目标已存在时不覆盖
+    fn migrate_legacy_files(&self) {
+        self.migrate_legacy_files_from(Path::new("."));
+    }
+
+    fn migrate_legacy_files_from(&self, cwd: &Path) {
+        let login = cwd.join(LOGIN_FILE);
+        if login.is_file() {
+            migrate(&login, &self.data_file(LOGIN_FILE));
+        }
+
+        let images = cwd.join(LEGACY_IMAGE_CACHE_DIR);
+        if images.is_dir() {
+            migrate(&images, &self.image_cache_dir());
+        }
+
+        let exports = cwd.join(EXPORT_DIR);
+        if exports.is_dir() {
+            migrate(&exports, &self.export_dir());
+        }
+
+        for name in LEGACY_CACHE_DIRS {
+            let dir = cwd.join(name);
+            if dir.is_dir() {
+                info!("💡 旧版本的缓存目录 {:?} 已经不再使用，确认无用后可以手动删除", dir);
+            }
+        }
+    }
+}
+
+/// 把 `from` 移到 `to`；`to` 已存在或两者是同一个位置时跳过
+fn migrate(from: &Path, to: &Path) {
+    if to.exists() || same_location(from, to) {
+        return;
+    }
+    match move_path(from, to) {
+        Ok(()) => info!("📦 已迁移 {:?} → {:?}", from, to),
+        Err(e) => warn!("⚠️ 迁移 {:?} 失败: {}", from, e),
+    }
+}
+
+fn same_location(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 先尝试改名，跨文件系统时退回到复制后删除
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> anyhow::Result<Option<T>> {
+    if !path.exists() { return Ok(None); }
+    let data = fs::read_to_string(path)?;
+    let v = serde_json::from_str::<T>(&data)?;
+    Ok(Some(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用单独的临时目录
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bili-paths-{:016x}", fastrand::u64(..)));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn home_from_args_forms() {
+        assert_eq!(home_from_args(args(&["app", "--home", "/tmp/x"])), Some(PathBuf::from("/tmp/x")));
+        assert_eq!(home_from_args(args(&["app", "--home=/tmp/y"])), Some(PathBuf::from("/tmp/y")));
+        assert_eq!(home_from_args(args(&["app", "--home"])), None);
+        assert_eq!(home_from_args(args(&["app", "--homeless"])), None);
+        assert_eq!(home_from_args(args(&["app"])), None);
+
+        let exe_dir = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+        assert_eq!(home_from_args(args(&["app", "--portable"])), Some(exe_dir.join("data")));
+    }
+
+    #[test]
+    fn resolve_portable_root() {
+        let paths = AppPaths::resolve(Some(PathBuf::from("/portable")));
+        assert!(paths.is_portable());
+        assert_eq!(paths.config_dir(), Path::new("/portable/config"));
+        assert_eq!(paths.data_dir(), Path::new("/portable/data"));
+        assert_eq!(paths.cache_dir(), Path::new("/portable/cache"));
+        assert_eq!(paths.image_cache_dir(), Path::new("/portable/cache/images"));
+        assert_eq!(paths.log_dir(), Path::new("/portable/data/logs"));
+        assert_eq!(paths.export_dir(), Path::new("/portable/data/subtitle_exports"));
+        assert_eq!(paths.data_file(LOGIN_FILE), Path::new("/portable/data").join(LOGIN_FILE));
+
+        let platform = AppPaths::resolve(None);
+        assert!(!platform.is_portable());
+        assert!(platform.data_dir().ends_with(APP_DIR_NAME) || platform.data_dir().ends_with("data"));
+    }
+
+    #[test]
+    fn migrates_legacy_files_and_keeps_old_cache_dirs() {
+        let cwd = temp_dir();
+        let paths = AppPaths::resolve(Some(cwd.join("home")));
+        fs::write(cwd.join(LOGIN_FILE), "{}").unwrap();
+        fs::create_dir_all(cwd.join(LEGACY_IMAGE_CACHE_DIR)).unwrap();
+        fs::write(cwd.join(LEGACY_IMAGE_CACHE_DIR).join("a.png"), "png").unwrap();
+        for name in LEGACY_CACHE_DIRS {
+            fs::create_dir_all(cwd.join(name)).unwrap();
+            fs::write(cwd.join(name).join("keep"), "x").unwrap();
+        }
+
+        paths.migrate_legacy_files_from(&cwd);
+
+        assert!(!cwd.join(LOGIN_FILE).exists());
+        assert_eq!(fs::read_to_string(paths.data_file(LOGIN_FILE)).unwrap(), "{}");
+        assert!(!cwd.join(LEGACY_IMAGE_CACHE_DIR).exists());
+        assert_eq!(fs::read_to_string(paths.image_cache_dir().join("a.png")).unwrap(), "png");
+        for name in LEGACY_CACHE_DIRS {
+            assert!(cwd.join(name).join("keep").is_file());
+        }
+        fs::remove_dir_all(cwd).ok();
+    }
+
+    #[test]
+    fn migration_skips_existing_target() {
+        let cwd = temp_dir();
+        let paths = AppPaths::resolve(Some(cwd.join("home")));
+        fs::write(cwd.join(LOGIN_FILE), "old").unwrap();
+        fs::create_dir_all(paths.data_dir()).unwrap();
+        fs::write(paths.data_file(LOGIN_FILE), "new").unwrap();
+
+        paths.migrate_legacy_files_from(&cwd);
+
+        assert_eq!(fs::read_to_string(cwd.join(LOGIN_FILE)).unwrap(), "old");
+        assert_eq!(fs::read_to_string(paths.data_file(LOGIN_FILE)).unwrap(), "new");
+        fs::remove_dir_all(cwd).ok();
+    }
+
+    #[test]
+    fn migration_skips_same_location() {
+        let cwd = temp_dir();
+        // 便携根目录的数据目录就是工作目录时什么都不做
+        let paths = AppPaths { config: cwd.clone(), data: cwd.clone(), cache: cwd.clone(), portable: true };
+        fs::write(cwd.join(LOGIN_FILE), "same").unwrap();
+
+        paths.migrate_legacy_files_from(&cwd);
+
+        assert_eq!(fs::read_to_string(cwd.join(LOGIN_FILE)).unwrap(), "same");
+        fs::remove_dir_all(cwd).ok();
+    }
+}
@@ -0,0 +1,375 @@
+use gpui::*;
+use gpui::prelude::FluentBuilder;
+use gpui_component::*;
+use gpui_component::scroll::ScrollbarAxis;
+use gpui_component::button::ButtonVariants;
+use gpui_component::input::{InputEvent, InputState};
+use crate::api::bilibili::{fetch_space_collections, Collection, UploadOrder};
+use crate::state::app_state::{AppState, Theme};
+use crate::utils::get_runtime_handle;
+use crate::views::home::HomeView;
+
+/// 合集列表每页条数
+const CREATOR_PAGE_SIZE: i32 = 20;
+/// 卡片上简介的最大字数
+const MAX_DESCRIPTION_CHARS: usize = 80;
+
+/// 创作者页面上的一张卡片：合集或系列
+struct CollectionCard {
+    collection: Collection,
+    name: String,
+    total: i32,
+    description: String,
+    cover: String,
+}
+
+pub struct CreatorView {
+    app_state: Entity<AppState>,
+    /// 全部投稿的关键词过滤
+    keyword_input: Entity<InputState>,
+}
+
+impl CreatorView {
+    pub fn new(app_state: Entity<AppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let keyword_input = cx.new(|cx| InputState::new(window, cx).placeholder("按标题关键词过滤（可选）"));
+        // 在关键词输入框中按 Enter 直接打开
+        cx.subscribe_in(&keyword_input, window, |view, _, event, _, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                view.open_uploads(cx);
+            }
+        }).detach();
+        Self { app_state, keyword_input }
+    }
+
+    /// 加载下一页合集和系列
+    fn load_more(&self, cx: &mut Context<Self>) {
+        let Some((mid, page)) = self.app_state.update(cx, |s, _| s.begin_creator_loading()) else { return; };
+
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        info!("📚 加载 UID {} 的合集列表第 {} 页", mid, page);
+
+        cx.spawn(async move |_: WeakEntity<CreatorView>, cx: &mut AsyncApp| {
+            let mid_for_fetch = mid.clone();
+            let result = handle.spawn(async move {
+                fetch_space_collections(&client, &mid_for_fetch, page, CREATOR_PAGE_SIZE).await
+            }).await;
+
+            match result {
+                Ok(Ok(data)) => {
+                    info!("✅ 合集列表加载完成: UID {} 第 {} 页", mid, page);
+                    app_state.update(cx, |s, cx| {
+                        // 请求期间切换了账号时结果属于原来的账号，直接丢弃
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.append_creator_collections(&mid, page, data);
+                        cx.notify();
+                    })?;
+                }
+                Ok(Err(e)) => {
+                    error!("❌ 获取合集列表失败: {} - {}", mid, e);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.fail_creator_loading(&mid);
+                        s.handle_api_error(&e);
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    error!("❌ 任务执行失败: {}", e);
+                    app_state.update(cx, |s, _| {
+                        if s.active_account() == account.as_deref() {
+                            s.fail_creator_loading(&mid);
+                        }
+                    })?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+
+    /// 打开选中的合集或系列，加载完成后跳转到视频列表页面
+    fn open(&self, collection: Collection, name: String, cx: &mut Context<Self>) {
+        let Some(mid) = self.app_state.read(cx).creator().map(|c| c.mid.clone()) else { return; };
+        self.app_state.update(cx, |s, _| s.set_search_status(Some(format!("正在打开 {}...", name))));
+
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        cx.spawn(async move |_: WeakEntity<CreatorView>, cx: &mut AsyncApp| {
+            HomeView::open_collection(app_state, account, client, mid, collection, cx).await;
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+
+    /// 打开全部投稿，使用当前选择的排序方式和关键词
+    fn open_uploads(&self, cx: &mut Context<Self>) {
+        let order = self.app_state.read(cx).upload_order();
+        let keyword = self.keyword_input.read(cx).value().trim().to_string();
+        let name = if keyword.is_empty() {
+            format!("全部视频（{}）", order.label())
+        } else {
+            format!("全部视频中包含“{}”的视频", keyword)
+        };
+        self.open(Collection::Uploads { order, keyword }, name, cx);
+    }
+
+    /// “全部视频”入口：不依赖合集，可选排序方式和关键词
+    fn render_uploads_card(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let order = self.app_state.read(cx).upload_order();
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_lg()
+            .bg(match theme {
+                Theme::Dark => rgb(0x0d0d0d),
+                Theme::Light => rgb(0xf5f5f5),
+            })
+            .child(
+                div()
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child("全部视频")
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_2()
+                    .children(UploadOrder::ALL.into_iter().enumerate().map(|(i, option)| {
+                        button::Button::new(("upload-order", i))
+                            .xsmall()
+                            .map(|b| if option == order { b.primary() } else { b.ghost() })
+                            .label(option.label())
+                            .on_click(cx.listener(move |view, _, _, cx| {
+                                view.app_state.update(cx, |s, _| s.set_upload_order(option));
+                                cx.notify();
+                            }))
+                    }))
+                    .child(div().flex_1().child(input::Input::new(&self.keyword_input).small()))
+                    .child(
+                        button::Button::new("open-uploads")
+                            .small()
+                            .primary()
+                            .label("打开")
+                            .on_click(cx.listener(|view, _, _, cx| view.open_uploads(cx)))
+                    )
+            )
+    }
+
+    fn render_card(&self, index: usize, card: CollectionCard, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        let kind = match card.collection {
+            Collection::Season(_) => "合集",
+            Collection::Series(_) => "系列",
+            Collection::Uploads { .. } => "投稿",
+        };
+        let description: String = if card.description.chars().count() > MAX_DESCRIPTION_CHARS {
+            card.description.chars().take(MAX_DESCRIPTION_CHARS).chain("…".chars()).collect()
+        } else {
+            card.description.clone()
+        };
+        let cover = card.cover.replace("http://", "https://");
+        let (collection, name) = (card.collection, card.name.clone());
+
+        div()
+            .id(("creator-collection", index))
+            .flex()
+            .flex_row()
+            .gap_4()
+            .p_3()
+            .rounded_lg()
+            .cursor(CursorStyle::PointingHand)
+            .bg(match theme {
+                Theme::Dark => rgb(0x0d0d0d),
+                Theme::Light => rgb(0xf5f5f5),
+            })
+            .hover(|style| style.bg(match theme {
+                Theme::Dark => rgb(0x1a1a1a),
+                Theme::Light => rgb(0xeeeeee),
+            }))
+            .on_click(cx.listener(move |view, _, _, cx| {
+                view.open(collection.clone(), name.clone(), cx);
+            }))
+            .child(
+                div()
+                    .w(px(160.0))
+                    .h(px(100.0))
+                    .flex_none()
+                    .rounded_md()
+                    .overflow_hidden()
+                    .bg(match theme {
+                        Theme::Dark => rgb(0x1a1a1a),
+                        Theme::Light => rgb(0xe0e0e0),
+                    })
+                    .when(!cover.is_empty(), |this| {
+                        this.child(
+                            img(cover)
+                                .w_full()
+                                .h_full()
+                                .object_fit(ObjectFit::Cover)
+                        )
+                    })
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_base()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(card.name)
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(secondary_color)
+                            .child(format!("{} · {} 个视频", kind, card.total))
+                    )
+                    .when(!description.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(secondary_color)
+                                .child(description)
+                        )
+                    })
+            )
+    }
+}
+
+impl Render for CreatorView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = self.app_state.read(cx).theme();
+        let status = self.app_state.read(cx).search_status().map(str::to_string);
+        let Some(creator) = self.app_state.read(cx).creator().cloned() else {
+            return div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child("在首页搜索 UP 主后在这里查看合集");
+        };
+
+        // 第一次打开时自动加载第一页
+        if creator.page == 0 && !creator.loading && !creator.failed {
+            self.load_more(cx);
+        }
+
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        let loaded = creator.seasons.len() + creator.series.len();
+        let cards: Vec<CollectionCard> = creator
+            .seasons
+            .iter()
+            .map(|s| CollectionCard {
+                collection: Collection::Season(s.season_id),
+                name: s.name.clone(),
+                total: s.total,
+                description: s.description.clone().unwrap_or_default(),
+                cover: s.cover.clone(),
+            })
+            .chain(creator.series.iter().map(|s| CollectionCard {
+                collection: Collection::Series(s.series_id),
+                name: s.name.clone(),
+                total: s.total,
+                description: s.description.clone().unwrap_or_default(),
+                cover: s.cover.clone(),
+            }))
+            .collect();
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(match theme {
+                Theme::Dark => rgb(0x000000),
+                Theme::Light => rgb(0xffffff),
+            })
+            .text_color(match theme {
+                Theme::Dark => rgb(0xffffff),
+                Theme::Light => rgb(0x000000),
+            })
+            .child(
+                div()
+                    .px_6()
+                    .py_4()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .child(format!("UID {}", creator.mid))
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(secondary_color)
+                            .child(format!("合集与系列 ({}/{})", loaded, creator.total))
+                    )
+                    .when_some(status, |this, status| {
+                        this.child(div().text_sm().text_color(secondary_color).child(status))
+                    })
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .px_6()
+                    .pb_6()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(self.render_uploads_card(theme, cx))
+                    .children(
+                        cards
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, card)| self.render_card(i, card, theme, cx)),
+                    )
+                    .map(|this| {
+                        if creator.loading {
+                            this.child(div().text_sm().text_color(secondary_color).child("正在加载合集..."))
+                        } else if creator.failed {
+                            this.child(
+                                button::Button::new("creator-retry")
+                                    .small()
+                                    .outline()
+                                    .label("合集加载失败，点击重试")
+                                    .on_click(cx.listener(|view, _, _, cx| view.load_more(cx)))
+                            )
+                        } else if !creator.is_end {
+                            this.child(
+                                button::Button::new("creator-more")
+                                    .small()
+                                    .outline()
+                                    .label("加载更多")
+                                    .on_click(cx.listener(|view, _, _, cx| view.load_more(cx)))
+                            )
+                        } else if loaded == 0 {
+                            this.child(div().text_sm().text_color(secondary_color).child("该 UP 主没有公开的合集或系列，可以在上方查看全部视频"))
+                        } else {
+                            this
+                        }
+                    })
+                    .scrollable(ScrollbarAxis::Vertical)
+            )
+    }
+}
@@ -1,29 +1,29 @@
-use std::sync::{Arc, OnceLock};
+use std::sync::Arc;
 use gpui::*;
 use gpui::prelude::FluentBuilder;
 use gpui_component::*;
+use gpui_component::button::ButtonVariants;
+use crate::api::bilibili::Collection;
+use crate::api::client::BiliClient;
+use crate::api::error::BiliError;
+use crate::api::search::{parse_query, resolve, SearchQuery, SearchTarget};
+use crate::api::session;
 use crate::state::app_state::{AppState, Theme, Cookies, UserProfile, VideoInfo, Page};
+use crate::utils::get_runtime_handle;
+use crate::utils::image_cache::image_cache;
+use futures::StreamExt;
 use qrcode::QrCode;
 use qrcode::render::svg;
 use gpui_component::input::{InputState, InputEvent};
 
-// Bilibili API 的标准 User-Agent
-const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+/// 同时下载的封面数量
+const COVER_CONCURRENCY: usize = 6;
 
-// 全局 Tokio runtime，参考 Zed 的实现
-static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
-
-fn get_runtime_handle() -> tokio::runtime::Handle {
-    tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
-        let runtime = RUNTIME.get_or_init(|| {
-            tokio::runtime::Builder::new_multi_thread()
-                .worker_threads(2)
-                .enable_all()
-                .build()
-                .expect("Failed to initialize Tokio runtime")
-        });
-        runtime.handle().clone()
-    })
+/// 二维码申请接口返回的数据
+#[derive(serde::Deserialize)]
+struct QrGenerateData {
+    url: String,
+    qrcode_key: String,
 }
 
 pub struct HomeView {
@@ -42,7 +42,7 @@ impl HomeView {
         // 订阅输入事件 - 增加详细日志
         let app_state_clone = app_state.clone();
         
-        println!("🎯 [HomeView::new] 创建输入框并订阅事件");
+        debug!("🎯 [HomeView::new] 创建输入框并订阅事件");
         
         cx.subscribe_in(&search_input, window, move |view, state, event, _window, cx| {
             let event_name = match event {
@@ -51,34 +51,34 @@ impl HomeView {
                 InputEvent::Focus => "Focus",
                 InputEvent::Blur => "Blur",
             };
-            println!("📨 [InputEvent] 收到输入事件: {}", event_name);
+            debug!("📨 [InputEvent] 收到输入事件: {}", event_name);
             
             match event {
                 InputEvent::Change => {
                     // 输入内容改变时，更新到 AppState
                     let text = state.read(cx).value().to_string();
-                    println!("✏️  [InputEvent::Change] 输入内容改变: '{}'", text);
+                    debug!("✏️  [InputEvent::Change] 输入内容改变: '{}'", text);
                     app_state_clone.update(cx, |s, _| {
                         s.set_search_text(text.clone());
                     });
-                    println!("💾 [InputEvent::Change] 已保存到 AppState");
+                    debug!("💾 [InputEvent::Change] 已保存到 AppState");
                 }
                 InputEvent::PressEnter { secondary } => {
                     // 按下 Enter 键时触发搜索
-                    println!("⌨️  [InputEvent::PressEnter] 按下 Enter 键，secondary: {}", secondary);
+                    debug!("⌨️  [InputEvent::PressEnter] 按下 Enter 键，secondary: {}", secondary);
                     let current_text = state.read(cx).value().to_string();
-                    println!("📝 [InputEvent::PressEnter] 当前输入内容: '{}'", current_text);
+                    debug!("📝 [InputEvent::PressEnter] 当前输入内容: '{}'", current_text);
                     Self::trigger_search(view, cx);
                 }
                 InputEvent::Focus => {
-                    println!("🎯 [InputEvent::Focus] 搜索框获得焦点");
+                    debug!("🎯 [InputEvent::Focus] 搜索框获得焦点");
                     let current_text = state.read(cx).value().to_string();
-                    println!("📝 [InputEvent::Focus] 当前输入内容: '{}'", current_text);
+                    debug!("📝 [InputEvent::Focus] 当前输入内容: '{}'", current_text);
                 }
                 InputEvent::Blur => {
-                    println!("💤 [InputEvent::Blur] 搜索框失去焦点");
+                    debug!("💤 [InputEvent::Blur] 搜索框失去焦点");
                     let current_text = state.read(cx).value().to_string();
-                    println!("📝 [InputEvent::Blur] 当前输入内容: '{}'", current_text);
+                    debug!("📝 [InputEvent::Blur] 当前输入内容: '{}'", current_text);
                 }
             }
         }).detach();
@@ -90,16 +90,16 @@ impl HomeView {
     }
 
     fn trigger_search(view: &mut Self, cx: &mut Context<Self>) {
-        println!("🚀 [trigger_search] 进入搜索函数");
+        debug!("🚀 [trigger_search] 进入搜索函数");
         
         // 读取搜索框内容
         let search_text = view.search_input.read_with(cx, |state, _| {
             let value = state.value().to_string();
-            println!("📖 [trigger_search] 从 InputState 读取到的值: '{}'", value);
+            debug!("📖 [trigger_search] 从 InputState 读取到的值: '{}'", value);
             value
         });
         
-        println!("🔍 [trigger_search] 开始搜索，内容: '{}'", search_text);
+        debug!("🔍 [trigger_search] 开始搜索，内容: '{}'", search_text);
         
         // 获取当前登录用户的 UID
         let uid = view.app_state.read_with(cx, |s, _| {
@@ -107,7 +107,7 @@ impl HomeView {
         });
         
         if uid.is_none() {
-            println!("❌ 用户未登录，无法搜索");
+            error!("❌ 用户未登录，无法搜索");
             return;
         }
         
@@ -116,166 +116,304 @@ impl HomeView {
             s.set_search_text(search_text.clone());
         });
         
-        // 获取 Cookie
-        let cookie = view.app_state.read_with(cx, |s, _| {
-            s.cookie_header().unwrap_or_default()
+        let Some(query) = parse_query(&search_text) else {
+            warn!("⚠️ 搜索内容为空");
+            return;
+        };
+        debug!("🧭 [trigger_search] 解析结果: {:?}", query);
+        if let SearchQuery::UnsupportedLink(url) = &query {
+            warn!("⚠️ 暂不支持的链接: {}", url);
+            view.app_state.update(cx, |s, _| {
+                s.set_search_status(Some(format!("暂不支持打开这个链接: {}", url)));
+            });
+            cx.notify();
+            return;
+        }
+        view.app_state.update(cx, |s, _| {
+            s.set_search_status(Some("正在查找...".to_string()));
         });
+        cx.notify();
         
-        // 临时使用固定的 mid 进行测试
-        let mid = "3461574394120551"; // 测试用的 mid
-        
-        println!("🚀 开始获取 UID {} 的视频合集列表", mid);
-        
-        // 在后台线程中执行 API 调用
+        // 共享的 API 客户端（已带上登录 Cookie）
+        let client = view.app_state.read_with(cx, |s, _| s.client());
+        let account = view.app_state.read(cx).active_account().map(str::to_string);
         let app_state_for_update = view.app_state.clone();
         
-        cx.spawn(async move |_: WeakEntity<HomeView>, mut _cx| {
+        cx.spawn(async move |_: WeakEntity<HomeView>, cx: &mut AsyncApp| {
             let handle = get_runtime_handle();
-            let cookie_for_collections = cookie.clone();
+            let client_for_resolve = client.clone();
             
-            // 使用 Tokio runtime 执行异步请求
+            // 短链接和昵称需要请求接口才能确定目标
             let result = handle.spawn(async move {
-                crate::api::bilibili::fetch_space_collections(
-                    mid,
-                    &cookie_for_collections,
-                    1,
-                    20
-                ).await
+                resolve(&client_for_resolve, query).await
             }).await;
             
             match result {
-                Ok(Ok(data)) => {
-                    println!("\n✅ 成功获取合集列表！");
-                    
-                    // 显示合集信息
-                    if let Some(seasons) = &data.items_lists.seasons_list {
-                        println!("\n📚 合集列表:");
-                        for season in seasons {
-                            println!("  - {} (ID: {}, 共{}个视频)", 
-                                season.meta.name,
-                                season.meta.season_id,
-                                season.meta.total
-                            );
-                            
-                            // 获取第一个合集的视频列表（测试）
-                            let season_id = season.meta.season_id.to_string();
-                            let mid = mid.to_string();
-                            let cookie = cookie.clone();
-                            let app_state_clone = app_state_for_update.clone();
-                            
-                            println!("\n🔍 正在获取合集 {} 的视频列表...", season.meta.name);
-                            
-                            let videos_result = handle.spawn(async move {
-                                crate::api::bilibili::fetch_all_season_archives(
-                                    &mid,
-                                    &season_id,
-                                    &cookie
-                                ).await
-                            }).await;
-                            
-                            match videos_result {
-                                Ok(Ok(videos)) => {
-                                    println!("\n✅ 成功获取 {} 个视频！", videos.len());
-                                    
-                                    // 转换为 VideoInfo 格式并下载封面
-                                    println!("\n📥 开始下载视频封面...");
-                                    let video_list: Vec<VideoInfo> = videos.iter().map(|v| {
-                                        let is_live_replay = v.title.contains("【直播回放】") || 
-                                                            v.title.contains("直播回放");
-                                        
-                                        // 下载封面到本地
-                                        let pic_url = if v.pic.starts_with("http://") {
-                                            v.pic.replace("http://", "https://")
-                                        } else {
-                                            v.pic.clone()
-                                        };
-                                        
-                                        let pic_local = match crate::utils::download_cover(&pic_url) {
-                                            Ok(path_arc) => {
-                                                let path_str = path_arc.display().to_string();
-                                                println!("[HomeView] ✅ 封面下载成功: {}", v.title);
-                                                Some(path_str)
-                                            }
-                                            Err(e) => {
-                                                println!("[HomeView] ❌ 封面下载失败: {} - {}", v.title, e);
-                                                None
-                                            }
-                                        };
-                                        
-                                        VideoInfo {
-                                            aid: v.aid,
-                                            bvid: v.bvid.clone(),
-                                            title: v.title.clone(),
-                                            pic: v.pic.clone(),
-                                            pic_local,
-                                            description: None,
-                                            pubdate: v.pubdate,
-                                            duration: v.duration,
-                                            view_count: v.stat.view,
-                                            like_count: v.stat.like.unwrap_or(0),
-                                            is_live_replay,
-                                        }
-                                    }).collect();
-                                    
-                                    println!("✅ 所有封面下载完成！");
-                                    
-                                    // 统计直播回放数量
-                                    let live_replay_count = video_list.iter()
-                                        .filter(|v| v.is_live_replay)
-                                        .count();
-                                    
-                                    println!("\n📊 统计:");
-                                    println!("  总视频数: {}", video_list.len());
-                                    println!("  直播回放: {} 个 🔴", live_replay_count);
-                                    println!("  普通视频: {} 个 ⚪", video_list.len() - live_replay_count);
-                                    
-                                    // 保存到状态并跳转到视频列表页面
-                                    let _ = _cx.update(|cx| {
-                                        app_state_clone.update(cx, |state, _| {
-                                            state.set_video_list(video_list);
-                                            state.set_selected_video_index(None);
-                                            state.set_page(Page::VideoList);
-                                        })
-                                    });
-                                    
-                                    println!("\n🎉 已跳转到视频列表页面");
-                                },
-                                Ok(Err(e)) => {
-                                    println!("❌ 获取视频列表失败: {}", e);
-                                },
-                                Err(e) => {
-                                    println!("❌ 任务执行失败: {}", e);
+                Ok(Ok(target)) => {
+                    info!("🎯 搜索目标: {:?}", target);
+                    match target {
+                        SearchTarget::Creator { mid } => {
+                            // 在创作者页面列出全部合集和系列，由用户选择打开哪一个
+                            app_state_for_update.update(cx, |s, cx| {
+                                if s.active_account() != account.as_deref() {
+                                    return;
                                 }
-                            }
-                            
-                            // 只获取第一个合集进行测试
-                            break;
+                                s.open_creator(mid.to_string());
+                                s.set_search_status(None);
+                                s.set_page(Page::Creator);
+                                cx.notify();
+                            })?;
                         }
-                    }
-                    
-                    // 显示系列信息
-                    if let Some(series) = &data.items_lists.series_list {
-                        println!("\n📖 系列列表:");
-                        for s in series {
-                            println!("  - {} (ID: {}, 共{}个视频)", 
-                                s.meta.name,
-                                s.meta.series_id,
-                                s.meta.total
-                            );
+                        SearchTarget::Season { mid, season_id } => {
+                            Self::open_collection(app_state_for_update, account, client, mid.to_string(), Collection::Season(season_id), cx).await;
+                        }
+                        SearchTarget::Series { mid, series_id } => {
+                            Self::open_collection(app_state_for_update, account, client, mid.to_string(), Collection::Series(series_id), cx).await;
+                        }
+                        SearchTarget::Video { bvid } => {
+                            Self::open_video(app_state_for_update, account, client, bvid, cx).await;
+                        }
+                        SearchTarget::Live { room_id } => {
+                            app_state_for_update.update(cx, |s, cx| {
+                                if s.active_account() != account.as_deref() {
+                                    return;
+                                }
+                                s.set_search_status(Some(format!("暂不支持打开直播间 {}", room_id)));
+                                cx.notify();
+                            })?;
                         }
                     }
                 },
                 Ok(Err(e)) => {
-                    println!("❌ API 调用失败: {}", e);
+                    error!("❌ 解析搜索内容失败: {}", e);
+                    app_state_for_update.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.set_search_status(Some(e.to_string()));
+                        s.handle_api_error(&e);
+                        cx.notify();
+                    })?;
                 },
                 Err(e) => {
-                    println!("❌ 任务执行失败: {}", e);
+                    error!("❌ 任务执行失败: {}", e);
                 }
             }
             
             Ok::<(), anyhow::Error>(())
         }).detach();
     }
+
+    /// 打开单个视频：视频列表里只放这一个视频并选中它
+    async fn open_video(
+        app_state: Entity<AppState>,
+        account: Option<String>,
+        client: BiliClient,
+        bvid: String,
+        cx: &mut AsyncApp,
+    ) {
+        let handle = get_runtime_handle();
+        let detail_result = handle.spawn(async move {
+            crate::api::bilibili::fetch_video_detail(&client, &bvid).await
+        }).await;
+        
+        match detail_result {
+            Ok(Ok(detail)) => {
+                info!("✅ 成功获取视频: {} ({})", detail.title, detail.bvid);
+                
+                let covers = vec![(detail.bvid.clone(), detail.pic.clone())];
+                let video = VideoInfo {
+                    aid: detail.aid,
+                    bvid: detail.bvid.clone(),
+                    title: detail.title.clone(),
+                    pic: detail.pic.clone(),
+                    pic_local: None,
+                    description: Some(detail.desc.clone()),
+                    pubdate: detail.pubdate,
+                    duration: detail.duration,
+                    view_count: detail.stat.view,
+                    like_count: detail.stat.like.unwrap_or(0),
+                    is_live_replay: detail.title.contains("直播回放"),
+                };
+                
+                let opened = app_state.update(cx, |state, _| {
+                    // 请求期间切换了账号时结果属于原来的账号，直接丢弃
+                    if state.active_account() != account.as_deref() {
+                        return false;
+                    }
+                    state.set_video_detail(detail);
+                    state.set_video_list(vec![video]);
+                    state.set_selected_video_index(Some(0));
+                    state.set_search_status(None);
+                    state.set_page(Page::VideoList);
+                    true
+                });
+                if !matches!(opened, Ok(true)) {
+                    return;
+                }
+                
+                info!("🎉 已跳转到视频列表页面");
+                Self::load_covers(app_state, account, covers, cx).await;
+            },
+            Ok(Err(e)) => {
+                error!("❌ 获取视频详情失败: {}", e);
+                let _ = app_state.update(cx, |s, cx| {
+                    if s.active_account() != account.as_deref() {
+                        return;
+                    }
+                    s.set_search_status(Some(e.to_string()));
+                    s.handle_api_error(&e);
+                    cx.notify();
+                });
+            },
+            Err(e) => {
+                error!("❌ 任务执行失败: {}", e);
+            }
+        }
+    }
+
+    /// 在后台并发下载封面（最多 `COVER_CONCURRENCY` 个同时进行），每下载完一张就更新对应的视频
+    async fn load_covers(app_state: Entity<AppState>, account: Option<String>, covers: Vec<(String, String)>, cx: &mut AsyncApp) {
+        if covers.is_empty() {
+            return;
+        }
+        let total = covers.len();
+        info!("📥 开始后台下载 {} 张视频封面", total);
+        let _ = app_state.update(cx, |s, _| {
+            if s.active_account() == account.as_deref() {
+                s.begin_cover_loading(covers.iter().map(|(bvid, _)| bvid.clone()));
+            }
+        });
+        
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        get_runtime_handle().spawn(async move {
+            futures::stream::iter(covers)
+                .map(|(bvid, pic)| async move {
+                    let result = image_cache().fetch(&pic).await;
+                    (bvid, pic, result)
+                })
+                .buffer_unordered(COVER_CONCURRENCY)
+                .for_each(|item| {
+                    let _ = tx.unbounded_send(item);
+                    async {}
+                })
+                .await;
+        });
+        
+        let mut failed = 0;
+        while let Some((bvid, url, result)) = rx.next().await {
+            let path = match result {
+                Ok(path) => Some(path.display().to_string()),
+                Err(e) => {
+                    error!("❌ 封面下载失败: {} - {}", url, e);
+                    failed += 1;
+                    None
+                }
+            };
+            // 切换了账号时封面属于已经换出的列表，不再继续写入
+            let updated = app_state.update(cx, |s, cx| {
+                if s.active_account() != account.as_deref() {
+                    return false;
+                }
+                s.set_video_cover(&bvid, path);
+                cx.notify();
+                true
+            });
+            if !matches!(updated, Ok(true)) {
+                return;
+            }
+        }
+        info!("✅ 封面下载结束: 成功 {} 张，失败 {} 张", total - failed, failed);
+    }
+
+    /// 拉取合集或系列的全部视频，填充视频列表并跳转到视频列表页面
+    pub(crate) async fn open_collection(
+        app_state: Entity<AppState>,
+        account: Option<String>,
+        client: BiliClient,
+        mid: String,
+        collection: Collection,
+        cx: &mut AsyncApp,
+    ) {
+        let handle = get_runtime_handle();
+        let videos_result = handle.spawn(async move {
+            crate::api::bilibili::fetch_collection_archives(&client, &mid, collection).await
+        }).await;
+        
+        match videos_result {
+            Ok(Ok(videos)) => {
+                info!("✅ 成功获取 {} 个视频！", videos.len());
+                
+                // 转换为 VideoInfo 格式，封面在列表显示后再后台下载
+                let video_list: Vec<VideoInfo> = videos.iter().map(|v| {
+                    let is_live_replay = v.title.contains("【直播回放】") || 
+                                        v.title.contains("直播回放");
+                    
+                    VideoInfo {
+                        aid: v.aid,
+                        bvid: v.bvid.clone(),
+                        title: v.title.clone(),
+                        pic: v.pic.clone(),
+                        pic_local: None,
+                        description: v.description.clone(),
+                        pubdate: v.pubdate,
+                        duration: v.duration,
+                        view_count: v.stat.view,
+                        like_count: v.stat.like.unwrap_or(0),
+                        is_live_replay,
+                    }
+                }).collect();
+                
+                // 统计直播回放数量
+                let live_replay_count = video_list.iter()
+                    .filter(|v| v.is_live_replay)
+                    .count();
+                
+                info!("📊 统计:");
+                info!("  总视频数: {}", video_list.len());
+                info!("  直播回放: {} 个 🔴", live_replay_count);
+                info!("  普通视频: {} 个 ⚪", video_list.len() - live_replay_count);
+                
+                let covers: Vec<(String, String)> = video_list
+                    .iter()
+                    .map(|v| (v.bvid.clone(), v.pic.clone()))
+                    .collect();
+                
+                // 保存到状态并跳转到视频列表页面
+                let opened = app_state.update(cx, |state, _| {
+                    // 请求期间切换了账号时结果属于原来的账号，直接丢弃
+                    if state.active_account() != account.as_deref() {
+                        return false;
+                    }
+                    state.set_video_list(video_list);
+                    state.set_selected_video_index(None);
+                    state.set_search_status(None);
+                    state.set_page(Page::VideoList);
+                    true
+                });
+                if !matches!(opened, Ok(true)) {
+                    return;
+                }
+                
+                info!("🎉 已跳转到视频列表页面");
+                Self::load_covers(app_state, account, covers, cx).await;
+            },
+            Ok(Err(e)) => {
+                error!("❌ 获取视频列表失败: {}", e);
+                let _ = app_state.update(cx, |s, cx| {
+                    if s.active_account() != account.as_deref() {
+                        return;
+                    }
+                    s.set_search_status(Some(e.to_string()));
+                    s.handle_api_error(&e);
+                    cx.notify();
+                });
+            },
+            Err(e) => {
+                error!("❌ 任务执行失败: {}", e);
+            }
+        }
+    }
     
     fn start_qr_flow(app_state: Entity<AppState>, cx: &mut Context<Self>) {
         app_state.update(cx, |s, _| { s.set_qr_started(true); s.set_qr_status("正在获取二维码..."); });
@@ -285,69 +423,22 @@ impl HomeView {
     fn request_qr(app_state: Entity<AppState>, cx: &mut Context<Self>) {
         // 获取 Tokio runtime handle
         let handle = get_runtime_handle();
+        let client = app_state.read(cx).client();
         
         cx.spawn(async move |_: WeakEntity<HomeView>, cx: &mut AsyncApp| {
             // 1) 获取二维码
-            let url = "https://passport.bilibili.com/x/passport-login/web/qrcode/generate";
-            println!("\n========== API Request ==========");
-            println!("Method: GET");
-            println!("URL: {}", url);
-            println!("Headers:");
-            println!("  User-Agent: {}", USER_AGENT);
-            println!("Body: None");
-            println!("=================================\n");
-            
-            let client = reqwest::Client::new();
-            // 使用 Tokio runtime handle 执行异步请求
-            let response = handle.spawn(async move {
-                client
-                    .get(url)
-                    .header("User-Agent", USER_AGENT)
-                    .send()
-                    .await
-            }).await??;
-            
-            println!("\n========== API Response ==========");
-            println!("URL: {}", url);
-            println!("Status: {}", response.status());
-            println!("Response Headers:");
-            for (key, value) in response.headers().iter() {
-                if let Ok(val_str) = value.to_str() {
-                    println!("  {}: {}", key, val_str);
-                }
-            }
-            
-            let body = response.text().await?;
-            println!("Response Body: {}", body);
-            println!("==================================\n");
-            
-            #[derive(serde::Deserialize)]
-            struct GenerateResp { code: i64, data: Option<GenData> }
-            #[derive(serde::Deserialize)]
-            struct GenData { url: String, qrcode_key: String }
-            let parsed: GenerateResp = serde_json::from_str(&body)?;
-            if parsed.code != 0 { anyhow::bail!("生成二维码失败"); }
-            let gen = parsed.data.unwrap();
+            let gen = Self::generate_qr(&client, &handle).await?;
 
             // 2) 生成二维码SVG并作为 Image(SVG) 显示
-            let code = QrCode::new(gen.url.as_bytes())?;
-            let svg_text = code
-                .render::<svg::Color>()
-                .min_dimensions(256, 256)
-                .quiet_zone(true)
-                .build();
-            let svg_bytes = svg_text.into_bytes();
+            let svg_bytes = Self::render_qr_svg(&gen.url)?;
             app_state.update(cx, |s, _| {
                 s.set_qrcode_key(Some(gen.qrcode_key.clone()));
                 s.set_qr_svg(Some(svg_bytes));
                 s.set_qr_status("请使用手机客户端扫码并确认");
             })?;
 
-            // 3) 开始轮询
+            // 3) 开始轮询（登录成功时 Set-Cookie 会直接写入共享客户端的 Cookie）
             use std::time::Duration;
-            let client = reqwest::Client::builder()
-                .cookie_store(true)
-                .build()?;
             let start = std::time::Instant::now();
             let handle_clone = handle.clone();
             
@@ -358,101 +449,40 @@ impl HomeView {
                 }
 
                 let qrcode_key = app_state.read_with(cx, |s, _| s.qrcode_key().cloned())?;
-                if qrcode_key.is_none() { break; }
-                let key = qrcode_key.unwrap();
+                let Some(key) = qrcode_key else { break; };
 
-                let url = format!("https://passport.bilibili.com/x/passport-login/web/qrcode/poll?qrcode_key={}", key);
-                
-                println!("\n========== API Request ==========");
-                println!("Method: GET");
-                println!("URL: {}", url);
-                println!("Headers:");
-                println!("  User-Agent: {}", USER_AGENT);
-                println!("Body: None");
-                println!("=================================\n");
-                
-                let response = handle_clone.spawn({
-                    let client = client.clone();
-                    let url = url.clone();
-                    async move {
-                        client
-                            .get(&url)
-                            .header("User-Agent", USER_AGENT)
-                            .send()
-                            .await
-                    }
-                }).await??;
-                
-                println!("\n========== API Response ==========");
-                println!("URL: {}", url);
-                println!("Status: {}", response.status());
-                println!("Response Headers:");
-                for (key, value) in response.headers().iter() {
-                    if let Ok(val_str) = value.to_str() {
-                        println!("  {}: {}", key, val_str);
-                    }
-                }
+                let url = client.passport_url("/x/passport-login/web/qrcode/poll");
+                let sent = handle_clone.spawn({
+                    let request = client.get(url).query(&[("qrcode_key", key.as_str())]);
+                    async move { request.send().await }
+                }).await?;
                 
-                let headers = response.headers().clone();
-                let body = response.text().await?;
-                println!("Response Body: {}", body);
-                println!("==================================\n");
-
-                #[derive(serde::Deserialize)]
-                struct PollData { code: i64 }
-                #[derive(serde::Deserialize)]
-                struct PollResp { code: i64, data: Option<PollData> }
-                let parsed: PollResp = serde_json::from_str(&body).unwrap_or(PollResp{ code: -1, data: None });
-                if parsed.code != 0 {
-                    handle_clone.spawn(async {
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                    }).await?;
-                    continue;
-                }
-                let Some(data) = parsed.data else {
-                    handle_clone.spawn(async {
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                    }).await?;
-                    continue;
+                let status = sent.and_then(|response| Self::parse_poll_status(&response.text()));
+                // 被风控或限流时放慢轮询
+                let delay = match &status {
+                    Err(e) if e.should_back_off() => Duration::from_secs(10),
+                    _ => Duration::from_secs(2),
                 };
 
-                match data.code {
-                    0 => {
-                        // 登录成功：解析 Cookie
-                        let mut cookies = Cookies::default();
-                        for (_k, v) in headers.iter().filter(|(k, _)| k.as_str().eq_ignore_ascii_case("set-cookie")) {
-                            if let Ok(line) = v.to_str() {
-                                // 解析 Set-Cookie 头，提取第一个键值对
-                                if let Some(first_part) = line.split(';').next() {
-                                    if let Some((k, v)) = first_part.split_once('=') {
-                                        let k = k.trim();
-                                        let v = v.trim();
-                                        match k {
-                                            "SESSDATA" => cookies.SESSDATA = v.to_string(),
-                                            "DedeUserID" => cookies.DedeUserID = Some(v.to_string()),
-                                            "DedeUserID__ckMd5" => cookies.DedeUserID__ckMd5 = Some(v.to_string()),
-                                            "bili_jct" => cookies.bili_jct = Some(v.to_string()),
-                                            "sid" => cookies.sid = Some(v.to_string()),
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                match status {
+                    Ok(refresh_token) => {
+                        // 登录成功：从共享客户端的 Cookie 中取出登录态
+                        let cookies = Cookies::from_jar(client.cookie_jar());
 
                         app_state.update(cx, |s, _| {
                             s.set_cookies(cookies);
+                            s.set_refresh_token(refresh_token);
                             s.set_logged_in(true);
                             s.persist_login();
                             s.set_qr_status("登录成功");
                         })?;
 
                         // 获取用户信息
-                        println!("登录成功，正在获取用户信息并设置测试头像...");
+                        info!("登录成功，正在获取用户信息并设置测试头像...");
                         Self::fetch_user_info(app_state.clone(), cx).await.ok();
                         break;
                     }
-                    86038 => {
+                    Err(BiliError::QrExpired) => {
                         app_state.update(cx, |s, _| {
                             s.set_qr_status("二维码已失效，正在刷新...");
                             s.set_qr_started(false);
@@ -460,159 +490,151 @@ impl HomeView {
                             s.set_qrcode_key(None);
                         })?;
                         // 重新生成
-                        let refresh_url = "https://passport.bilibili.com/x/passport-login/web/qrcode/generate";
-                        println!("\n========== API Request (Refresh QR) ==========");
-                        println!("Method: GET");
-                        println!("URL: {}", refresh_url);
-                        println!("Headers:");
-                        println!("  User-Agent: {}", USER_AGENT);
-                        println!("Body: None");
-                        println!("==============================================\n");
-                        
-                        let response = handle_clone.spawn({
-                            let client = client.clone();
-                            async move {
-                                client
-                                    .get(refresh_url)
-                                    .header("User-Agent", USER_AGENT)
-                                    .send()
-                                    .await
-                            }
-                        }).await??;
-                        
-                        println!("\n========== API Response (Refresh QR) ==========");
-                        println!("URL: {}", refresh_url);
-                        println!("Status: {}", response.status());
-                        println!("Response Headers:");
-                        for (key, value) in response.headers().iter() {
-                            if let Ok(val_str) = value.to_str() {
-                                println!("  {}: {}", key, val_str);
-                            }
-                        }
-                        
-                        let body = response.text().await?;
-                        println!("Response Body: {}", body);
-                        println!("===============================================\n");
-                        let parsed: GenerateResp = serde_json::from_str(&body)?;
-                        if parsed.code != 0 { anyhow::bail!("生成二维码失败"); }
-                        let gen = parsed.data.unwrap();
-                        let code = QrCode::new(gen.url.as_bytes())?;
-                        let svg_text = code.render::<svg::Color>().min_dimensions(256, 256).quiet_zone(true).build();
-                        let svg_bytes = svg_text.into_bytes();
+                        let gen = Self::generate_qr(&client, &handle_clone).await?;
+                        let svg_bytes = Self::render_qr_svg(&gen.url)?;
                         app_state.update(cx, |s, _| {
                             s.set_qrcode_key(Some(gen.qrcode_key.clone()));
                             s.set_qr_svg(Some(svg_bytes));
                             s.set_qr_status("请使用手机客户端扫码并确认");
                         })?;
                     }
-                    86090 => {
+                    Err(BiliError::QrScanned) => {
                         app_state.update(cx, |s, _| { s.set_qr_status("已扫码，等待确认..."); })?;
                     }
-                    86101 => {
+                    Err(BiliError::QrPending) => {
                         // 未扫码
                     }
-                    _ => {}
+                    Err(e) => {
+                        warn!("⚠️ 轮询登录状态失败: {}", e);
+                    }
                 }
-                handle_clone.spawn(async {
-                    tokio::time::sleep(Duration::from_secs(2)).await;
+                handle_clone.spawn(async move {
+                    tokio::time::sleep(delay).await;
                 }).await?;
             }
             Ok::<(), anyhow::Error>(())
         }).detach();
     }
 
-    async fn fetch_user_info(app_state: Entity<AppState>, cx: &mut AsyncApp) -> anyhow::Result<()> {
-        let cookie_header = app_state.read_with(cx, |s, _| s.cookie_header())?;
-        let Some(cookie) = cookie_header else { return Ok(()); };
-        
-        let url = "https://api.bilibili.com/x/web-interface/nav";
-        println!("\n========== API Request (User Info) ==========");
-        println!("Method: GET");
-        println!("URL: {}", url);
-        println!("Request Headers:");
-        println!("  User-Agent: {}", USER_AGENT);
-        println!("  Cookie: {}", cookie);
-        println!("Body: None");
-        println!("=============================================\n");
-        
-        let handle = get_runtime_handle();
-        let client = reqwest::Client::new();
-        let resp = handle.spawn(async move {
-            client
-                .get(url)
-                .header("User-Agent", USER_AGENT)
-                .header("Cookie", cookie)
-                .send()
-                .await
+    /// 申请登录二维码
+    async fn generate_qr(client: &BiliClient, handle: &tokio::runtime::Handle) -> anyhow::Result<QrGenerateData> {
+        let url = client.passport_url("/x/passport-login/web/qrcode/generate");
+        let response = handle.spawn({
+            let request = client.get(url);
+            async move { request.send().await }
         }).await??;
-        
-        println!("\n========== API Response (User Info) ==========");
-        println!("URL: {}", url);
-        println!("Status: {}", resp.status());
-        println!("Response Headers:");
-        for (key, value) in resp.headers().iter() {
-            if let Ok(val_str) = value.to_str() {
-                println!("  {}: {}", key, val_str);
-            }
-        }
-        
-        let text = resp.text().await?;
-        println!("Response Body: {}", text);
-        println!("==============================================\n");
+        let body = response.text();
         
         #[derive(serde::Deserialize)]
-        struct NavPendant { image: Option<String> }
+        struct GenerateResp { code: i32, #[serde(default)] message: String, data: Option<QrGenerateData> }
+        let parsed: GenerateResp = serde_json::from_str(&body).map_err(BiliError::from)?;
+        if parsed.code != 0 {
+            return Err(BiliError::from_code(parsed.code, parsed.message).into());
+        }
+        Ok(parsed.data.ok_or_else(|| BiliError::Decode("生成二维码失败：缺少 data".to_string()))?)
+    }
+
+    /// 解析扫码轮询结果：外层 `code` 是请求本身的结果，`data.code` 才是扫码状态（0 为登录成功）；
+    /// 登录成功时返回刷新 Cookie 用的 `refresh_token`
+    fn parse_poll_status(body: &str) -> Result<Option<String>, BiliError> {
         #[derive(serde::Deserialize)]
-        #[allow(dead_code)]
-        struct NavData { uname: Option<String>, face: Option<String>, pendant: Option<NavPendant> }
+        struct PollData { code: i32, #[serde(default)] message: String, #[serde(default)] refresh_token: String }
         #[derive(serde::Deserialize)]
-        struct NavResp { code: i64, data: Option<NavData> }
-        let parsed: NavResp = serde_json::from_str(&text).unwrap_or(NavResp{ code: -1, data: None });
-        println!("[HomeView] 🔍 解析用户信息响应，code: {}", parsed.code);
-        if parsed.code == 0 {
-            if let Some(d) = parsed.data {
-                println!("[HomeView] 📝 原始头像URL: {:?}", d.face);
-                
-                // 下载头像到本地
-                let face_local = if let Some(face_url) = &d.face {
-                    match crate::utils::download_avatar(face_url) {
-                        Ok(path_arc) => {
-                            // 将 Arc<Path> 转换为字符串用于存储
-                            let path_str = path_arc.display().to_string();
-                            println!("[HomeView] ✅ 头像下载成功: {}", path_str);
-                            Some(path_str)
-                        }
-                        Err(e) => {
-                            println!("[HomeView] ❌ 头像下载失败: {}", e);
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
-                
-                // 使用本地缓存路径优先，没有则使用网络URL
-                let user = UserProfile { 
-                    uname: d.uname.clone(), 
-                    face: d.face.clone(),
-                    face_local, // 本地缓存路径
-                    pendant_image: d.pendant.and_then(|p| p.image) 
-                };
-                
-                println!("[HomeView] ✅ 用户信息构建完成");
-                println!("[HomeView]    - 用户名: {:?}", user.uname);
-                println!("[HomeView]    - 头像URL: {:?}", user.face);
-                println!("[HomeView]    - 本地头像: {:?}", user.face_local);
-                println!("[HomeView]    - 挂件图片: {:?}", user.pendant_image);
-                
+        struct PollResp { code: i32, #[serde(default)] message: String, data: Option<PollData> }
+
+        let parsed: PollResp = serde_json::from_str(body)?;
+        if parsed.code != 0 {
+            return Err(BiliError::from_code(parsed.code, parsed.message));
+        }
+        match parsed.data {
+            Some(data) if data.code == 0 => Ok(Some(data.refresh_token).filter(|t| !t.is_empty())),
+            Some(data) => Err(BiliError::from_code(data.code, data.message)),
+            None => Err(BiliError::Decode("轮询响应缺少 data".to_string())),
+        }
+    }
+
+    fn render_qr_svg(url: &str) -> anyhow::Result<Vec<u8>> {
+        let code = QrCode::new(url.as_bytes())?;
+        let svg_text = code
+            .render::<svg::Color>()
+            .min_dimensions(256, 256)
+            .quiet_zone(true)
+            .build();
+        Ok(svg_text.into_bytes())
+    }
+
+    /// 通过 nav 接口获取当前用户的名字、头像和挂件，头像下载到本地缓存；登录失效时退回登录页
+    async fn fetch_user_info(app_state: Entity<AppState>, cx: &mut AsyncApp) -> anyhow::Result<()> {
+        let (logged_in, client, account) = app_state
+            .read_with(cx, |s, _| (s.cookie_header().is_some(), s.client(), s.active_account().map(str::to_string)))?;
+        if !logged_in {
+            return Ok(());
+        }
+        
+        let nav = get_runtime_handle()
+            .spawn(async move { session::fetch_nav(&client).await })
+            .await?;
+        let nav = match nav {
+            Ok(nav) => nav,
+            Err(e) => {
+                error!("❌ 获取用户信息失败: {}", e);
                 app_state.update(cx, |s, cx| {
-                    s.set_user(user);
-                    s.persist_login(); // 保存用户信息到文件
-                    cx.notify(); // 触发重新渲染
+                    if s.active_account() != account.as_deref() {
+                        return;
+                    }
+                    s.handle_api_error(&e);
+                    cx.notify();
                 })?;
-                println!("[HomeView] 🔄 触发UI重新渲染");
+                return Ok(());
             }
-        }
+        };
+        // 下载头像到本地
+        let face_local = if let Some(face_url) = nav.face.clone() {
+            let downloaded = get_runtime_handle()
+                .spawn(async move { image_cache().fetch(&face_url).await })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            match downloaded {
+                Ok(path_arc) => {
+                    // 将 Arc<Path> 转换为字符串用于存储
+                    let path_str = path_arc.display().to_string();
+                    info!("✅ 头像下载成功: {}", path_str);
+                    Some(path_str)
+                }
+                Err(e) => {
+                    error!("❌ 头像下载失败: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        
+        // 使用本地缓存路径优先，没有则使用网络URL
+        let user = UserProfile { 
+            uname: nav.uname.clone(), 
+            face: nav.face.clone(),
+            face_local, // 本地缓存路径
+            pendant_image: nav.pendant.and_then(|p| p.image) 
+        };
+        
+        debug!("✅ 用户信息构建完成");
+        debug!("   - 用户名: {:?}", user.uname);
+        debug!("   - 头像URL: {:?}", user.face);
+        debug!("   - 本地头像: {:?}", user.face_local);
+        debug!("   - 挂件图片: {:?}", user.pendant_image);
+        
+        app_state.update(cx, |s, cx| {
+            // 请求期间切换了账号时不能写到别的账号上
+            if s.active_account() != account.as_deref() {
+                return;
+            }
+            s.set_user(user);
+            s.persist_login(); // 保存用户信息到文件
+            cx.notify(); // 触发重新渲染
+        })?;
+        debug!("🔄 触发UI重新渲染");
         Ok(())
     }
 }
@@ -640,6 +662,7 @@ impl Render for HomeView {
 
         // 如果已登录，显示欢迎页面
         if is_logged {
+            let search_status = self.app_state.read(cx).search_status().map(str::to_string);
             return div()
                 .size_full()
                 .flex()
@@ -682,7 +705,7 @@ impl Render for HomeView {
                                     .cursor(CursorStyle::IBeam)
                                     .on_mouse_down(gpui::MouseButton::Left, cx.listener(|view, _, window, cx| {
                                         // 点击时聚焦输入框
-                                        println!("🖱️  [CustomInput] 点击自定义输入框区域，聚焦输入框");
+                                        debug!("🖱️  [CustomInput] 点击自定义输入框区域，聚焦输入框");
                                         view.search_input.read(cx).focus_handle(cx).focus(window);
                                         cx.notify(); // 触发重新渲染以显示光标
                                     }))
@@ -761,7 +784,7 @@ impl Render for HomeView {
                                                                 .child(IconName::Close)
                                                         )
                                                         .on_mouse_down(gpui::MouseButton::Left, cx.listener(|view, _, window, cx| {
-                                                            println!("🗑️  [CustomInput] 点击清除按钮");
+                                                            debug!("🗑️  [CustomInput] 点击清除按钮");
                                                             view.search_input.update(cx, |state, cx| {
                                                                 state.set_value("", window, cx);
                                                             });
@@ -812,14 +835,27 @@ impl Render for HomeView {
                                         .child(IconName::Search)
                                 )
                                 .on_mouse_down(gpui::MouseButton::Left, cx.listener(|view, _, _, cx| {
-                                    println!("🔍 [SearchButton] 搜索按钮被点击");
+                                    debug!("🔍 [SearchButton] 搜索按钮被点击");
                                     let current_value = view.search_input.read(cx).value().to_string();
-                                    println!("📝 [SearchButton] 当前输入框的值: '{}'", current_value);
+                                    debug!("📝 [SearchButton] 当前输入框的值: '{}'", current_value);
                                     Self::trigger_search(view, cx);
                                     cx.stop_propagation();
                                 }))
                         )
-                );
+                )
+                .when_some(search_status, |this, status| {
+                    // 搜索提示：解析中、未找到用户等
+                    this.child(
+                        div()
+                            .mt_4()
+                            .text_sm()
+                            .text_color(match theme {
+                                Theme::Dark => rgb(0xaaaaaa),
+                                Theme::Light => rgb(0x666666),
+                            })
+                            .child(status)
+                    )
+                });
         }
 
         // 未登录，显示扫码登录页面（去除背景，保持居中）
@@ -866,6 +902,40 @@ impl Render for HomeView {
                                 Self::request_qr(view.app_state.clone(), cx);
                             }))
                     )
+                    // 添加账号或退出登录后，可以回到之前的账号
+                    .when_some(self.app_state.read(cx).previous_account().map(|a| a.display_name()), |this, name| {
+                        this.child(
+                            button::Button::new("cancel-add-account")
+                                .ghost()
+                                .label(format!("返回 {}", name))
+                                .on_click(cx.listener(|view, _, _, cx| {
+                                    // 清空 qrcode_key 后轮询会自行结束
+                                    view.app_state.update(cx, |s, _| s.cancel_add_account());
+                                    cx.notify();
+                                }))
+                        )
+                    })
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    // 不能用 `super::*`，否则 `#[test]` 会解析成 gpui 的测试宏
+    use super::HomeView;
+    use crate::api::error::BiliError;
+
+    #[test]
+    fn parses_poll_status() {
+        let success = r#"{"code":0,"message":"0","data":{"url":"","refresh_token":"c4a3e1f2","timestamp":1700000000000,"code":0,"message":""}}"#;
+        assert_eq!(HomeView::parse_poll_status(success).unwrap().as_deref(), Some("c4a3e1f2"));
+        let no_token = r#"{"code":0,"data":{"code":0,"refresh_token":""}}"#;
+        assert_eq!(HomeView::parse_poll_status(no_token).unwrap(), None);
+
+        let scanned = r#"{"code":0,"data":{"code":86090,"message":"二维码已扫码未确认"}}"#;
+        assert!(matches!(HomeView::parse_poll_status(scanned), Err(BiliError::QrScanned)));
+        let expired = r#"{"code":0,"data":{"code":86038,"message":"二维码已失效"}}"#;
+        assert!(matches!(HomeView::parse_poll_status(expired), Err(BiliError::QrExpired)));
+        assert!(matches!(HomeView::parse_poll_status(r#"{"code":0}"#), Err(BiliError::Decode(_))));
+    }
+}
@@ -0,0 +1,222 @@
+use gpui::*;
+use gpui::prelude::FluentBuilder;
+use gpui_component::*;
+use gpui_component::scroll::ScrollbarAxis;
+use gpui_component::button::ButtonVariants;
+use gpui_component::input::{InputEvent, InputState};
+use crate::state::app_state::{AppState, Theme};
+use crate::utils::logging::{logger, Level, Record};
+
+/// 页面上最多显示的日志条数
+const MAX_VISIBLE: usize = 500;
+/// 检查有没有新日志的间隔
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// 日志页面：按级别和关键词过滤内存中的日志，可以复制给别人排查问题
+pub struct LogsView {
+    app_state: Entity<AppState>,
+    filter_input: Entity<InputState>,
+    /// 显示这个级别及更严重的日志
+    level: Level,
+    /// 过滤结果，新日志在前；只在有新日志或过滤条件变化时重新计算
+    visible: Vec<Record>,
+    visible_key: Option<(u64, Level, String)>,
+}
+
+impl LogsView {
+    pub fn new(app_state: Entity<AppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("按模块或内容过滤"));
+        cx.subscribe_in(&filter_input, window, |_, _, event, _, cx| {
+            if let InputEvent::Change = event {
+                cx.notify();
+            }
+        }).detach();
+        // 页面打开期间有新日志时刷新
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                let result = this.update(cx, |view, cx| {
+                    if view.visible_key.as_ref().is_some_and(|key| key.0 != logger().last_seq()) {
+                        cx.notify();
+                    }
+                });
+                if result.is_err() {
+                    break;
+                }
+            }
+        }).detach();
+        Self {
+            app_state,
+            filter_input,
+            level: Level::Trace,
+            visible: Vec::new(),
+            visible_key: None,
+        }
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let filter = self.filter_input.read(cx).value().trim().to_lowercase();
+        let key = (logger().last_seq(), self.level, filter);
+        if self.visible_key.as_ref() == Some(&key) {
+            return;
+        }
+        let (level, filter) = (key.1, key.2.as_str());
+        let mut records = logger().recent(MAX_VISIBLE, |r| {
+            r.level <= level
+                && (filter.is_empty()
+                    || r.target.to_lowercase().contains(filter)
+                    || r.message.to_lowercase().contains(filter))
+        });
+        records.reverse();
+        self.visible = records;
+        self.visible_key = Some(key);
+    }
+
+    /// 按时间顺序复制当前显示的日志
+    fn copy_visible(&self, cx: &mut Context<Self>) {
+        let text = self
+            .visible
+            .iter()
+            .rev()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+        info!("📋 已复制 {} 条日志", self.visible.len());
+    }
+
+    fn render_record(&self, record: &Record, theme: Theme) -> impl IntoElement {
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0x888888),
+            Theme::Light => rgb(0x777777),
+        };
+        let level_color = match record.level {
+            Level::Error => rgb(0xe5484d),
+            Level::Warn => rgb(0xf5a524),
+            Level::Info => rgb(0x3e9bd6),
+            Level::Debug | Level::Trace => secondary_color,
+        };
+        div()
+            .flex()
+            .flex_row()
+            .gap_2()
+            .text_xs()
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(secondary_color)
+                    .child(record.time.format("%H:%M:%S%.3f").to_string())
+            )
+            .child(
+                div()
+                    .flex_none()
+                    .w(px(44.0))
+                    .text_color(level_color)
+                    .child(record.level.label())
+            )
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(secondary_color)
+                    .child(record.target)
+            )
+            .child(div().flex_1().child(record.message.clone()))
+    }
+}
+
+impl Render for LogsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.refresh(cx);
+        let theme = self.app_state.read(cx).theme();
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        let file = logger()
+            .file_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "未写入日志文件".to_string());
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(match theme {
+                Theme::Dark => rgb(0x000000),
+                Theme::Light => rgb(0xffffff),
+            })
+            .text_color(match theme {
+                Theme::Dark => rgb(0xffffff),
+                Theme::Light => rgb(0x000000),
+            })
+            .child(
+                div()
+                    .px_6()
+                    .py_4()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .child("日志")
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(secondary_color)
+                            .child(format!("记录级别 {} · 日志文件 {}", logger().level().label(), file))
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap_2()
+                            .children(Level::ALL.into_iter().enumerate().map(|(i, level)| {
+                                button::Button::new(("log-level-filter", i))
+                                    .xsmall()
+                                    .map(|b| if level == self.level { b.primary() } else { b.ghost() })
+                                    .label(level.label())
+                                    .on_click(cx.listener(move |view, _, _, cx| {
+                                        view.level = level;
+                                        cx.notify();
+                                    }))
+                            }))
+                            .child(div().flex_1().child(input::Input::new(&self.filter_input).small()))
+                            .child(
+                                button::Button::new("copy-logs")
+                                    .small()
+                                    .outline()
+                                    .label("复制")
+                                    .on_click(cx.listener(|view, _, _, cx| view.copy_visible(cx)))
+                            )
+                            .child(
+                                button::Button::new("clear-logs")
+                                    .small()
+                                    .ghost()
+                                    .label("清空")
+                                    .on_click(cx.listener(|_, _, _, cx| {
+                                        logger().clear();
+                                        cx.notify();
+                                    }))
+                            )
+                    )
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .px_6()
+                    .pb_6()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .when(self.visible.is_empty(), |this| {
+                        this.child(div().text_sm().text_color(secondary_color).child("没有符合条件的日志"))
+                    })
+                    .children(self.visible.iter().map(|record| self.render_record(record, theme)))
+                    .scrollable(ScrollbarAxis::Vertical)
+            )
+    }
+}
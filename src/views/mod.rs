@@ -1,3 +1,6 @@
+pub mod creator;
 pub mod home;
+pub mod logs;
+pub mod network;
 pub mod settings;
 pub mod video_list;
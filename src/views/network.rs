@@ -0,0 +1,361 @@
+use gpui::*;
+use gpui::prelude::FluentBuilder;
+use gpui_component::*;
+use gpui_component::scroll::ScrollbarAxis;
+use gpui_component::button::ButtonVariants;
+use gpui_component::input::{InputEvent, InputState};
+use crate::api::inspector::{network_log, to_har, NetworkEntry};
+use crate::state::app_state::{AppState, Theme};
+use crate::utils::paths;
+
+/// 详情里最多显示的响应体字符数，完整内容在导出的 HAR 文件里
+const MAX_BODY_CHARS: usize = 20_000;
+/// 检查有没有新请求的间隔
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// 网络页面：列出 API 层发出的请求，可以复制为 curl 命令或导出 HAR 文件
+pub struct NetworkView {
+    app_state: Entity<AppState>,
+    filter_input: Entity<InputState>,
+    /// 过滤结果，新请求在前；只在有新请求或过滤条件变化时重新计算
+    visible: Vec<NetworkEntry>,
+    visible_key: Option<(u64, String)>,
+    /// 选中的请求和格式化后的响应体
+    selected: Option<(NetworkEntry, Option<String>)>,
+    /// 操作结果提示
+    notice: Option<String>,
+}
+
+impl NetworkView {
+    pub fn new(app_state: Entity<AppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("按地址或 code 过滤"));
+        cx.subscribe_in(&filter_input, window, |_, _, event, _, cx| {
+            if let InputEvent::Change = event {
+                cx.notify();
+            }
+        }).detach();
+        // 页面打开期间有新请求时刷新
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                let result = this.update(cx, |view, cx| {
+                    if view.visible_key.as_ref().is_some_and(|key| key.0 != network_log().last_id()) {
+                        cx.notify();
+                    }
+                });
+                if result.is_err() {
+                    break;
+                }
+            }
+        }).detach();
+        Self {
+            app_state,
+            filter_input,
+            visible: Vec::new(),
+            visible_key: None,
+            selected: None,
+            notice: None,
+        }
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let filter = self.filter_input.read(cx).value().trim().to_lowercase();
+        let key = (network_log().last_id(), filter);
+        if self.visible_key.as_ref() == Some(&key) {
+            return;
+        }
+        let filter = key.1.as_str();
+        let mut entries = network_log().entries();
+        entries.retain(|e| {
+            filter.is_empty()
+                || e.url.to_lowercase().contains(filter)
+                || e.method.to_lowercase().contains(filter)
+                || e.bili_code.is_some_and(|code| code.to_string() == filter)
+        });
+        entries.reverse();
+        self.visible = entries;
+        self.visible_key = Some(key);
+    }
+
+    fn select(&mut self, id: u64, cx: &mut Context<Self>) {
+        if self.selected.as_ref().is_some_and(|(e, _)| e.id == id) {
+            self.selected = None;
+        } else if let Some(entry) = self.visible.iter().find(|e| e.id == id) {
+            let body = entry.pretty_body().map(|body| {
+                if body.chars().count() > MAX_BODY_CHARS {
+                    let head: String = body.chars().take(MAX_BODY_CHARS).collect();
+                    format!("{}\n…（已截断，完整内容请导出 HAR 查看）", head)
+                } else {
+                    body
+                }
+            });
+            self.selected = Some((entry.clone(), body));
+        }
+        cx.notify();
+    }
+
+    fn copy_curl(&mut self, cx: &mut Context<Self>) {
+        let Some((entry, _)) = &self.selected else { return; };
+        cx.write_to_clipboard(ClipboardItem::new_string(entry.to_curl()));
+        info!("📋 已复制请求 #{} 的 curl 命令", entry.id);
+        self.notice = Some("已复制 curl 命令，被遮盖的 Cookie 需要自己替换".to_string());
+        cx.notify();
+    }
+
+    /// 把全部记录导出到数据目录下的 HAR 文件
+    fn export_har(&mut self, cx: &mut Context<Self>) {
+        let entries = network_log().entries();
+        let dir = paths::paths().network_dir();
+        let path = dir.join(format!("bilibili-{}.har", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(serde_json::to_vec_pretty(&to_har(&entries))?))
+            .and_then(|data| Ok(std::fs::write(&path, data)?));
+        self.notice = Some(match result {
+            Ok(()) => {
+                info!("💾 已导出 {} 条请求到 {:?}", entries.len(), path);
+                format!("已导出 {} 条请求到 {}", entries.len(), path.display())
+            }
+            Err(e) => {
+                error!("❌ 导出 HAR 失败: {:?} - {}", path, e);
+                format!("导出失败: {}", e)
+            }
+        });
+        cx.notify();
+    }
+
+    fn render_entry(&self, entry: &NetworkEntry, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0x888888),
+            Theme::Light => rgb(0x777777),
+        };
+        let (status, status_color) = match entry.status {
+            Some(status) if (200..300).contains(&status) => (status.to_string(), rgb(0x30a46c)),
+            Some(status) => (status.to_string(), rgb(0xe5484d)),
+            None => ("ERR".to_string(), rgb(0xe5484d)),
+        };
+        let code = entry.bili_code.map(|c| c.to_string()).unwrap_or_default();
+        let code_color = match entry.bili_code {
+            Some(0) | None => secondary_color,
+            Some(_) => rgb(0xf5a524),
+        };
+        let selected = self.selected.as_ref().is_some_and(|(e, _)| e.id == entry.id);
+        let id = entry.id;
+
+        div()
+            .id(("network-entry", id))
+            .flex()
+            .flex_row()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .text_xs()
+            .cursor(CursorStyle::PointingHand)
+            .when(selected, |this| this.bg(match theme {
+                Theme::Dark => rgb(0x1a1a1a),
+                Theme::Light => rgb(0xeeeeee),
+            }))
+            .hover(|style| style.bg(match theme {
+                Theme::Dark => rgb(0x1a1a1a),
+                Theme::Light => rgb(0xeeeeee),
+            }))
+            .on_click(cx.listener(move |view, _, _, cx| view.select(id, cx)))
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(secondary_color)
+                    .child(entry.started_at.format("%H:%M:%S").to_string())
+            )
+            .child(div().flex_none().w(px(36.0)).child(entry.method.clone()))
+            .child(div().flex_none().w(px(32.0)).text_color(status_color).child(status))
+            .child(div().flex_none().w(px(48.0)).text_color(code_color).child(code))
+            .child(
+                div()
+                    .flex_none()
+                    .w(px(56.0))
+                    .text_color(secondary_color)
+                    .child(format!("{} ms", entry.latency.as_millis()))
+            )
+            .child(div().flex_1().min_w_0().truncate().child(entry.url.clone()))
+    }
+
+    fn render_detail(&self, entry: &NetworkEntry, body: Option<&String>, theme: Theme) -> impl IntoElement {
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0x888888),
+            Theme::Light => rgb(0x777777),
+        };
+        let section = |title: &'static str| {
+            div()
+                .mt_2()
+                .text_sm()
+                .font_weight(FontWeight::BOLD)
+                .child(title)
+        };
+        let headers = |pairs: &[(String, String)]| {
+            div().flex().flex_col().children(pairs.iter().map(|(name, value)| {
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .child(div().flex_none().text_color(secondary_color).child(format!("{}:", name)))
+                    .child(div().flex_1().child(value.clone()))
+            }))
+        };
+        let summary = match (entry.status, &entry.error) {
+            (Some(status), _) => format!("HTTP {} · {} ms · {} 字节", status, entry.latency.as_millis(), entry.response_size),
+            (None, Some(error)) => format!("请求失败 · {} ms · {}", entry.latency.as_millis(), error),
+            (None, None) => format!("{} ms", entry.latency.as_millis()),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_xs()
+            .child(div().text_sm().child(format!("{} {}", entry.method, entry.url)))
+            .child(div().text_color(secondary_color).child(summary))
+            .when_some(entry.bili_code, |this, code| {
+                this.child(div().child(format!(
+                    "code: {}{}",
+                    code,
+                    entry.bili_message.as_ref().map(|m| format!(" · {}", m)).unwrap_or_default()
+                )))
+            })
+            .child(section("请求头"))
+            .child(headers(&entry.request_headers))
+            .when_some(entry.request_body.clone(), |this, body| {
+                this.child(section("请求体")).child(div().child(body))
+            })
+            .child(section("响应头"))
+            .child(headers(&entry.response_headers))
+            .child(section("响应体"))
+            .child(div().child(match body {
+                Some(body) => body.clone(),
+                None if entry.response_size > 0 => format!("二进制内容（{} 字节），未保存", entry.response_size),
+                None => "空".to_string(),
+            }))
+    }
+}
+
+impl Render for NetworkView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.refresh(cx);
+        let theme = self.app_state.read(cx).theme();
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        let has_selection = self.selected.is_some();
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(match theme {
+                Theme::Dark => rgb(0x000000),
+                Theme::Light => rgb(0xffffff),
+            })
+            .text_color(match theme {
+                Theme::Dark => rgb(0xffffff),
+                Theme::Light => rgb(0x000000),
+            })
+            .child(
+                div()
+                    .px_6()
+                    .py_4()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .child("网络")
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(secondary_color)
+                            .child(format!(
+                                "最近 {} 个请求 · Cookie、csrf 和登录令牌已遮盖，可以放心分享",
+                                self.visible.len()
+                            ))
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap_2()
+                            .child(div().flex_1().child(input::Input::new(&self.filter_input).small()))
+                            .child(
+                                button::Button::new("copy-curl")
+                                    .small()
+                                    .outline()
+                                    .label("复制 curl")
+                                    .disabled(!has_selection)
+                                    .on_click(cx.listener(|view, _, _, cx| view.copy_curl(cx)))
+                            )
+                            .child(
+                                button::Button::new("export-har")
+                                    .small()
+                                    .outline()
+                                    .label("导出 HAR")
+                                    .on_click(cx.listener(|view, _, _, cx| view.export_har(cx)))
+                            )
+                            .child(
+                                button::Button::new("clear-network")
+                                    .small()
+                                    .ghost()
+                                    .label("清空")
+                                    .on_click(cx.listener(|view, _, _, cx| {
+                                        network_log().clear();
+                                        view.selected = None;
+                                        cx.notify();
+                                    }))
+                            )
+                    )
+                    .when_some(self.notice.clone(), |this, notice| {
+                        this.child(div().text_sm().text_color(secondary_color).child(notice))
+                    })
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .px_6()
+                    .pb_6()
+                    .flex()
+                    .flex_row()
+                    .gap_4()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .flex()
+                            .flex_col()
+                            .when(self.visible.is_empty(), |this| {
+                                this.child(div().text_sm().text_color(secondary_color).child("还没有请求记录"))
+                            })
+                            .children(self.visible.iter().map(|entry| self.render_entry(entry, theme, cx)))
+                            .scrollable(ScrollbarAxis::Vertical)
+                    )
+                    .when_some(self.selected.as_ref(), |this, (entry, body)| {
+                        this.child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .pl_4()
+                                .border_l_1()
+                                .border_color(match theme {
+                                    Theme::Dark => rgb(0x333333),
+                                    Theme::Light => rgb(0xcccccc),
+                                })
+                                .child(self.render_detail(entry, body.as_ref(), theme))
+                                .scrollable(ScrollbarAxis::Vertical)
+                        )
+                    })
+            )
+    }
+}
@@ -1,5 +1,9 @@
-use crate::state::app_state::{AppState, Theme};
+use crate::state::app_state::{AppState, Page, Theme};
+use crate::utils::image_cache::{format_bytes, image_cache};
+use crate::utils::logging::{logger, Level};
+use crate::utils::paths::paths;
 use gpui::*;
+use gpui::prelude::FluentBuilder;
 use gpui_component::button::ButtonVariants;
 use gpui_component::*;
 
@@ -26,6 +30,9 @@ impl SettingsView {
 impl Render for SettingsView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = self.app_state.read(cx).theme();
+        let cache_stats = image_cache().stats();
+        let app_paths = paths();
+        let log_level = logger().level();
         div()
             .size_full()
             .flex()
@@ -102,7 +109,7 @@ impl Render for SettingsView {
                                     let input_state = self.input_state.clone();
                                     move |_, _, cx| {
                                         let value = input_state.read(cx).text();
-                                        println!("Settings saved! Name: {}", value);
+                                        info!("Settings saved! Name: {}", value);
                                     }
                                 }),
                         ),
@@ -198,6 +205,188 @@ impl Render for SettingsView {
                             }),
                     ),
             )
+            .child(
+                div()
+                    .mt_4()
+                    .p_6()
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(match theme {
+                        Theme::Dark => rgb(0x333333),
+                        Theme::Light => rgb(0xcccccc),
+                    })
+                    .bg(match theme {
+                        Theme::Dark => rgb(0x0d0d0d),
+                        Theme::Light => rgb(0xf5f5f5),
+                    })
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(if app_paths.is_portable() { "Storage (portable)" } else { "Storage" }),
+                    )
+                    .children(
+                        [
+                            ("Config", app_paths.config_dir()),
+                            ("Data", app_paths.data_dir()),
+                            ("Cache", app_paths.cache_dir()),
+                        ]
+                        .into_iter()
+                        .map(|(label, dir)| {
+                            div()
+                                .text_sm()
+                                .text_color(match theme {
+                                    Theme::Dark => rgb(0xaaaaaa),
+                                    Theme::Light => rgb(0x666666),
+                                })
+                                .child(format!("{}: {}", label, dir.display()))
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .mt_4()
+                    .p_6()
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(match theme {
+                        Theme::Dark => rgb(0x333333),
+                        Theme::Light => rgb(0xcccccc),
+                    })
+                    .bg(match theme {
+                        Theme::Dark => rgb(0x0d0d0d),
+                        Theme::Light => rgb(0xf5f5f5),
+                    })
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("Logging"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap_2()
+                            .children(Level::ALL.into_iter().enumerate().map(|(i, level)| {
+                                button::Button::new(("log-level", i))
+                                    .small()
+                                    .map(|b| if level == log_level { b.primary() } else { b.outline() })
+                                    .label(level.label())
+                                    .on_click(cx.listener(move |_, _, _, cx| {
+                                        logger().set_level(level);
+                                        info!("📝 日志级别已切换为 {}", level.label());
+                                        cx.notify();
+                                    }))
+                            })),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(match theme {
+                                Theme::Dark => rgb(0xaaaaaa),
+                                Theme::Light => rgb(0x666666),
+                            })
+                            .child(match logger().file_path() {
+                                Some(path) => format!("Log file: {}", path.display()),
+                                None => "Log file: not available".to_string(),
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap_2()
+                            .child(
+                                button::Button::new("open-logs")
+                                    .outline()
+                                    .label("View Logs")
+                                    .on_click(cx.listener(|view, _, _, cx| {
+                                        view.app_state.update(cx, |state, _| state.set_page(Page::Logs));
+                                    })),
+                            )
+                            .child(
+                                button::Button::new("open-network")
+                                    .outline()
+                                    .label("Network Inspector")
+                                    .on_click(cx.listener(|view, _, _, cx| {
+                                        view.app_state.update(cx, |state, _| state.set_page(Page::Network));
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .mt_4()
+                    .p_6()
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(match theme {
+                        Theme::Dark => rgb(0x333333),
+                        Theme::Light => rgb(0xcccccc),
+                    })
+                    .bg(match theme {
+                        Theme::Dark => rgb(0x0d0d0d),
+                        Theme::Light => rgb(0xf5f5f5),
+                    })
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("Image Cache"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(match theme {
+                                Theme::Dark => rgb(0xaaaaaa),
+                                Theme::Light => rgb(0x666666),
+                            })
+                            .child(format!(
+                                "{} images ({} files), {} of {} used",
+                                cache_stats.entries,
+                                cache_stats.files,
+                                format_bytes(cache_stats.total_bytes),
+                                format_bytes(cache_stats.budget)
+                            )),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(match theme {
+                                Theme::Dark => rgb(0xaaaaaa),
+                                Theme::Light => rgb(0x666666),
+                            })
+                            .child(format!(
+                                "This session: {} hits, {} downloads",
+                                cache_stats.hits, cache_stats.misses
+                            )),
+                    )
+                    .child(
+                        div().child(
+                            button::Button::new("clear-image-cache")
+                                .outline()
+                                .label("Clear Cache")
+                                .on_click(cx.listener(|view, _, _, cx| {
+                                    if let Err(e) = image_cache().clear() {
+                                        error!("❌ 清除图片缓存失败: {}", e);
+                                    }
+                                    // 已经显示的封面和头像改用网络地址
+                                    view.app_state.update(cx, |state, _| state.forget_cached_images());
+                                    cx.notify();
+                                })),
+                        ),
+                    ),
+            )
             .child(
                 div()
                     .mt_4()
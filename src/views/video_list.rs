@@ -3,17 +3,655 @@ use std::sync::Arc;
 use std::path::Path;
 use gpui::prelude::FluentBuilder;
 use gpui_component::*;
-use gpui_component::scroll::ScrollbarAxis;
+use gpui_component::scroll::{Scrollbar, ScrollbarAxis, ScrollbarState};
 use gpui_component::resizable::{h_resizable, resizable_panel};
-use crate::state::app_state::{AppState, Theme, VideoInfo};
+use gpui_component::button::ButtonVariants;
+use crate::analysis::comments::CommentDigest;
+use crate::analysis::timeline::DanmakuTimeline;
+use crate::api::bilibili::{
+    fetch_replies, fetch_sub_replies, fetch_subtitle_lines, fetch_subtitle_tracks, fetch_video_detail, Reply, ReplySort,
+    SubtitleLine, VideoDetail,
+};
+use crate::api::danmaku::{fetch_all_danmaku, fetch_danmaku_xml, Danmaku};
+use crate::api::error::BiliError;
+use crate::export::{self, ExportFormat};
+use crate::state::app_state::{AppState, SideTab, Theme, VideoInfo};
+use crate::utils::get_runtime_handle;
 
 pub struct VideoListView {
     app_state: Entity<AppState>,
+    // 字幕区域的滚动状态，点击高能时刻时滚动到对应字幕
+    subtitle_scroll: ScrollHandle,
+    subtitle_scrollbar: ScrollbarState,
+    // 高亮的字幕行 (cid, 行号)
+    focused_line: Option<(i64, usize)>,
 }
 
 impl VideoListView {
     pub fn new(app_state: Entity<AppState>, _window: &Window, _cx: &mut Context<Self>) -> Self {
-        Self { app_state }
+        Self {
+            app_state,
+            subtitle_scroll: ScrollHandle::new(),
+            subtitle_scrollbar: ScrollbarState::default(),
+            focused_line: None,
+        }
+    }
+
+    /// 选中视频时按需加载详情，已加载或正在加载的不会重复请求
+    fn load_video_detail(&self, bvid: String, cx: &mut Context<Self>) {
+        let started = self.app_state.update(cx, |s, _| s.begin_detail_loading(&bvid));
+        if !started {
+            // 详情已缓存时直接加载字幕和评论
+            self.load_current_part(cx);
+            self.load_comments(false, cx);
+            return;
+        }
+        
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |this: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let result = handle.spawn({
+                let bvid = bvid.clone();
+                async move { fetch_video_detail(&client, &bvid).await }
+            }).await;
+            
+            match result {
+                Ok(Ok(detail)) => {
+                    info!("✅ 视频详情加载完成: {} ({}P)", detail.title, detail.pages.len());
+                    // 请求期间切换了账号时结果属于原来的账号，直接丢弃
+                    let current = app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return false;
+                        }
+                        s.set_video_detail(detail);
+                        cx.notify();
+                        true
+                    })?;
+                    if !current {
+                        return Ok(());
+                    }
+                    this.update(cx, |view, cx| {
+                        view.load_current_part(cx);
+                        view.load_comments(false, cx);
+                    })?;
+                }
+                Ok(Err(e)) => {
+                    error!("❌ 获取视频详情失败: {} - {}", bvid, e);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.finish_detail_loading(&bvid);
+                        s.handle_api_error(&e);
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    error!("❌ 任务执行失败: {}", e);
+                    app_state.update(cx, |s, _| {
+                        if s.active_account() == account.as_deref() {
+                            s.finish_detail_loading(&bvid);
+                        }
+                    })?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+
+    /// 加载当前分P的字幕；弹幕栏打开或者是直播回放时同时加载弹幕，用于显示密度时间轴
+    fn load_current_part(&self, cx: &mut Context<Self>) {
+        self.load_subtitles(cx);
+        let state = self.app_state.read(cx);
+        let is_live_replay = state.selected_video().is_some_and(|v| v.is_live_replay);
+        if state.is_danmaku_shown() || is_live_replay {
+            self.load_danmaku(cx);
+        }
+    }
+
+    /// 加载当前分P的弹幕：优先按分段接口获取全部弹幕，失败时退回 XML 接口
+    fn load_danmaku(&self, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        let Some(bvid) = state.selected_video().map(|v| v.bvid.clone()) else { return; };
+        let Some(cid) = state.current_cid() else { return; };
+        if state.danmaku(cid).is_some() {
+            return;
+        }
+        let duration = state
+            .video_detail(&bvid)
+            .map(|d| d.pages.iter().find(|p| p.cid == cid).map_or(d.duration, |p| p.duration))
+            .unwrap_or(0);
+        if !self.app_state.update(cx, |s, _| s.begin_danmaku_loading(cid)) {
+            return;
+        }
+        
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let result = handle.spawn(async move {
+                let list = match fetch_all_danmaku(&client, cid, duration).await {
+                    Ok(list) => list,
+                    Err(e) => {
+                        warn!("⚠️ 分段弹幕获取失败，改用 XML 接口: {}", e);
+                        fetch_danmaku_xml(&client, cid).await?
+                    }
+                };
+                // 几个小时的直播回放可能有几十万条弹幕，统计放在后台线程
+                let timeline = DanmakuTimeline::build(&list, duration);
+                anyhow::Ok((list, timeline))
+            }).await;
+            
+            match result {
+                Ok(Ok((list, timeline))) => {
+                    info!("✅ 弹幕加载完成: {} 条，高能时刻 {} 处 (cid={})", list.len(), timeline.highlights.len(), cid);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.set_danmaku(cid, list, timeline);
+                        cx.notify();
+                    })?;
+                }
+                Ok(Err(e)) => {
+                    error!("❌ 获取弹幕失败: {} - {}", cid, e);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.finish_danmaku_loading(cid);
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    error!("❌ 任务执行失败: {}", e);
+                    app_state.update(cx, |s, _| {
+                        if s.active_account() == account.as_deref() {
+                            s.finish_danmaku_loading(cid);
+                        }
+                    })?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+
+    /// 加载当前视频的评论，评论栏和 AI 分析栏都会用到；`more` 为 true 时加载下一页，否则只在还没有缓存时加载第一页
+    fn load_comments(&self, more: bool, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        let Some(aid) = state.selected_video().and_then(|v| state.video_detail(&v.bvid)).map(|d| d.aid) else { return; };
+        let sort = state.reply_sort();
+        if !more && state.comment_thread(aid, sort).is_some_and(|t| !t.failed) {
+            return;
+        }
+        let Some(offset) = self.app_state.update(cx, |s, _| s.begin_comment_loading(aid, sort)) else { return; };
+        
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let result = handle.spawn(async move {
+                fetch_replies(&client, aid, sort, offset.as_deref()).await
+            }).await;
+            
+            match result {
+                Ok(Ok(page)) => {
+                    info!("✅ 评论加载完成: {} 条 (aid={}, {})", page.replies.len(), aid, sort.label());
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.append_comments(aid, sort, page);
+                        cx.notify();
+                    })?;
+                }
+                Ok(Err(e)) => {
+                    error!("❌ 获取评论失败: {} - {}", aid, e);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.fail_comment_loading(aid, sort);
+                        s.handle_api_error(&e);
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    error!("❌ 任务执行失败: {}", e);
+                    app_state.update(cx, |s, _| {
+                        if s.active_account() == account.as_deref() {
+                            s.fail_comment_loading(aid, sort);
+                        }
+                    })?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+    
+    /// 加载根评论 `root` 的下一页楼中楼回复
+    fn load_sub_replies(&self, oid: i64, root: i64, cx: &mut Context<Self>) {
+        let Some(page) = self.app_state.update(cx, |s, _| s.begin_sub_reply_loading(root)) else { return; };
+        
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let result = handle.spawn(async move {
+                fetch_sub_replies(&client, oid, root, page).await
+            }).await;
+            
+            match result {
+                Ok(Ok(page)) => {
+                    info!("✅ 回复加载完成: 第 {} 页 {} 条 (root={})", page.page, page.replies.len(), root);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.append_sub_replies(root, page);
+                        cx.notify();
+                    })?;
+                }
+                Ok(Err(e)) => {
+                    error!("❌ 获取回复失败: {} - {}", root, e);
+                    app_state.update(cx, |s, cx| {
+                        if s.active_account() != account.as_deref() {
+                            return;
+                        }
+                        s.finish_sub_reply_loading(root);
+                        cx.notify();
+                    })?;
+                }
+                Err(e) => {
+                    error!("❌ 任务执行失败: {}", e);
+                    app_state.update(cx, |s, _| {
+                        if s.active_account() == account.as_deref() {
+                            s.finish_sub_reply_loading(root);
+                        }
+                    })?;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+    
+    /// 把字幕区域滚动到 `time` 秒附近的那一行并高亮
+    fn scroll_subtitles_to(&mut self, time: f64, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        let Some(cid) = state.current_cid() else { return; };
+        let Some(lines) = state
+            .active_subtitle_track(cid)
+            .and_then(|t| state.subtitle_lines(cid, &t.lan))
+        else {
+            return;
+        };
+        if lines.is_empty() {
+            return;
+        }
+        let index = lines
+            .iter()
+            .position(|l| l.to > time)
+            .unwrap_or(lines.len() - 1);
+        self.focused_line = Some((cid, index));
+        // 第一个子元素是视频详情，字幕行从 1 开始
+        self.subtitle_scroll.scroll_to_top_of_item(index + 1);
+        cx.notify();
+    }
+
+    /// 加载当前分P的字幕：先获取字幕轨道，再下载当前语言的字幕内容
+    fn load_subtitles(&self, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        let Some(bvid) = state.selected_video().map(|v| v.bvid.clone()) else { return; };
+        let Some(cid) = state.current_cid() else { return; };
+        let tracks_loaded = state.subtitle_tracks(cid).is_some();
+        let lines_loaded = state
+            .active_subtitle_track(cid)
+            .is_none_or(|t| state.subtitle_lines(cid, &t.lan).is_some());
+        if tracks_loaded && lines_loaded {
+            return;
+        }
+        if !self.app_state.update(cx, |s, _| s.begin_subtitle_loading(cid)) {
+            return;
+        }
+        
+        let app_state = self.app_state.clone();
+        let client = self.app_state.read(cx).client();
+        let account = self.app_state.read(cx).active_account().map(str::to_string);
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            if !tracks_loaded {
+                let result = handle.spawn({
+                    let client = client.clone();
+                    async move { fetch_subtitle_tracks(&client, &bvid, cid).await }
+                }).await;
+                match result {
+                    Ok(Ok(tracks)) => {
+                        info!("✅ 字幕轨道: {} 条 (cid={})", tracks.len(), cid);
+                        app_state.update(cx, |s, _| {
+                            if s.active_account() == account.as_deref() {
+                                s.set_subtitle_tracks(cid, tracks);
+                            }
+                        })?;
+                    }
+                    Ok(Err(e)) => {
+                        error!("❌ 获取字幕轨道失败: {} - {}", cid, e);
+                        app_state.update(cx, |s, _| {
+                            if s.active_account() == account.as_deref() {
+                                s.handle_api_error(&e);
+                            }
+                        })?;
+                    }
+                    Err(e) => error!("❌ 任务执行失败: {}", e),
+                }
+            }
+            
+            // 下载期间可能切换了语言，直到当前语言的字幕已缓存为止
+            loop {
+                let track = app_state.read_with(cx, |s, _| {
+                    if s.active_account() != account.as_deref() {
+                        return None;
+                    }
+                    s.active_subtitle_track(cid)
+                        .filter(|t| s.subtitle_lines(cid, &t.lan).is_none())
+                        .cloned()
+                })?;
+                let Some(track) = track else { break; };
+                
+                let result = handle.spawn({
+                    let client = client.clone();
+                    let track = track.clone();
+                    async move { fetch_subtitle_lines(&client, &track).await }
+                }).await;
+                match result {
+                    Ok(Ok(lines)) => {
+                        info!("✅ 字幕下载完成: {} {} 行", track.lan_doc, lines.len());
+                        app_state.update(cx, |s, _| {
+                            if s.active_account() == account.as_deref() {
+                                s.set_subtitle_lines(cid, track.lan.clone(), lines);
+                            }
+                        })?;
+                    }
+                    Ok(Err(e)) => {
+                        error!("❌ 下载字幕失败: {} - {}", track.lan_doc, e);
+                        break;
+                    }
+                    Err(e) => {
+                        error!("❌ 任务执行失败: {}", e);
+                        break;
+                    }
+                }
+            }
+            
+            app_state.update(cx, |s, cx| {
+                if s.active_account() != account.as_deref() {
+                    return;
+                }
+                s.finish_subtitle_loading(cid);
+                cx.notify();
+            })?;
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+}
+
+impl VideoListView {
+    /// 导出当前选中视频的字幕（所有分P，使用当前字幕语言）
+    fn export_current(&self, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        if state.is_exporting() {
+            return;
+        }
+        let Some(bvid) = state.selected_video().map(|v| v.bvid.clone()) else { return; };
+        let lan = state
+            .current_cid()
+            .and_then(|cid| state.active_subtitle_track(cid))
+            .map(|t| t.lan.clone())
+            .or_else(|| state.subtitle_lan().map(str::to_string));
+        let format = state.export_format();
+        let client = state.client();
+        let account = state.active_account().map(str::to_string);
+        
+        let app_state = self.app_state.clone();
+        app_state.update(cx, |s, _| {
+            s.set_exporting(true);
+            s.set_export_status(format!("正在导出 {} ...", bvid));
+        });
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let dir = export::export_dir();
+            let result = handle.spawn({
+                let bvid = bvid.clone();
+                async move { export::export_video(&client, &bvid, lan.as_deref(), format, &dir).await }
+            }).await;
+            
+            let status = match result {
+                Ok(Ok(Some(path))) => format!("已导出: {}", path.display()),
+                Ok(Ok(None)) => format!("{} 没有字幕，未导出", bvid),
+                Ok(Err(e)) => format!("导出失败: {}", e),
+                Err(e) => format!("导出失败: {}", e),
+            };
+            info!("📤 {}", status);
+            app_state.update(cx, |s, cx| {
+                s.set_exporting(false);
+                // 切换了账号时不显示原来账号的视频的导出结果
+                if s.active_account() == account.as_deref() {
+                    s.set_export_status(status);
+                } else {
+                    s.clear_export_status();
+                }
+                cx.notify();
+            })?;
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+    
+    /// 批量导出视频列表中所有视频的字幕，每个 bvid 一个文件
+    fn export_all(&self, cx: &mut Context<Self>) {
+        let state = self.app_state.read(cx);
+        if state.is_exporting() || state.video_list().is_empty() {
+            return;
+        }
+        let videos: Vec<(String, String)> = state
+            .video_list()
+            .iter()
+            .map(|v| (v.bvid.clone(), v.title.clone()))
+            .collect();
+        let lan = state.subtitle_lan().map(str::to_string);
+        let format = state.export_format();
+        let client = state.client();
+        let account = state.active_account().map(str::to_string);
+        
+        let app_state = self.app_state.clone();
+        app_state.update(cx, |s, _| s.set_exporting(true));
+        let handle = get_runtime_handle();
+        
+        cx.spawn(async move |_: WeakEntity<VideoListView>, cx: &mut AsyncApp| {
+            let dir = export::export_dir();
+            let total = videos.len();
+            let (mut written, mut skipped, mut failed) = (0, 0, 0);
+            let mut switched = false;
+            
+            for (i, (bvid, title)) in videos.into_iter().enumerate() {
+                // 切换了账号时列表属于原来的账号，Cookie 却已经换成新账号的，停止导出
+                let current = app_state.update(cx, |s, cx| {
+                    if s.active_account() != account.as_deref() {
+                        return false;
+                    }
+                    s.set_export_status(format!("正在导出 {}/{}: {}", i + 1, total, title));
+                    cx.notify();
+                    true
+                })?;
+                if !current {
+                    switched = true;
+                    break;
+                }
+                
+                let result = handle.spawn({
+                    let client = client.clone();
+                    let lan = lan.clone();
+                    let dir = dir.clone();
+                    let bvid = bvid.clone();
+                    async move { export::export_video(&client, &bvid, lan.as_deref(), format, &dir).await }
+                }).await;
+                
+                match result {
+                    Ok(Ok(Some(_))) => written += 1,
+                    Ok(Ok(None)) => skipped += 1,
+                    Ok(Err(e)) => {
+                        error!("❌ 导出失败: {} - {}", bvid, e);
+                        failed += 1;
+                        match BiliError::find(&e) {
+                            Some(err) if err.needs_login() => {
+                                app_state.update(cx, |s, _| {
+                                    if s.active_account() == account.as_deref() {
+                                        s.handle_api_error(err);
+                                    }
+                                })?;
+                            }
+                            Some(err) if err.should_back_off() => {
+                                // 被风控时暂停一会儿再继续，避免后面的视频全部失败
+                                app_state.update(cx, |s, cx| {
+                                    if s.active_account() != account.as_deref() {
+                                        return;
+                                    }
+                                    s.set_export_status(format!("请求被限制，{} 秒后继续导出...", EXPORT_BACKOFF.as_secs()));
+                                    cx.notify();
+                                })?;
+                                handle.spawn(tokio::time::sleep(EXPORT_BACKOFF)).await?;
+                            }
+                            _ => {}
+                        }
+                    }
+                    Err(e) => {
+                        error!("❌ 任务执行失败: {}", e);
+                        failed += 1;
+                    }
+                }
+            }
+            
+            let status = format!(
+                "{}：成功 {} 个，无字幕 {} 个，失败 {} 个，保存在 {}",
+                if switched { "已切换账号，批量导出已停止" } else { "批量导出完成" },
+                written, skipped, failed, dir.display()
+            );
+            info!("📤 {}", status);
+            app_state.update(cx, |s, cx| {
+                s.set_exporting(false);
+                s.set_export_status(status);
+                cx.notify();
+            })?;
+            Ok::<(), anyhow::Error>(())
+        }).detach();
+    }
+    
+    /// 字幕导出栏：格式选择、导出当前视频、批量导出
+    fn render_export_bar(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let current_format = state.export_format();
+        let exporting = state.is_exporting();
+        let has_selection = state.selected_video().is_some();
+        let video_count = state.video_list().len();
+        let status = state.export_status().map(str::to_string);
+        
+        div()
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .px_4()
+            .py_3()
+            .border_b_1()
+            .border_color(match theme {
+                Theme::Dark => rgb(0x2a2a2a),
+                Theme::Light => rgb(0xe0e0e0),
+            })
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .gap_1()
+                    .children(ExportFormat::ALL.into_iter().enumerate().map(|(i, format)| {
+                        button::Button::new(("export-format", i))
+                            .xsmall()
+                            .map(|b| if format == current_format { b.primary() } else { b.outline() })
+                            .label(format.label())
+                            .on_click(cx.listener(move |view, _, _, cx| {
+                                view.app_state.update(cx, |s, _| s.set_export_format(format));
+                            }))
+                    }))
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .child(
+                        button::Button::new("export-current")
+                            .small()
+                            .primary()
+                            .label("导出当前视频")
+                            .disabled(exporting || !has_selection)
+                            .on_click(cx.listener(|view, _, _, cx| view.export_current(cx)))
+                    )
+                    .child(
+                        button::Button::new("export-all")
+                            .small()
+                            .outline()
+                            .label(format!("批量导出全部 ({})", video_count))
+                            .disabled(exporting || video_count == 0)
+                            .on_click(cx.listener(|view, _, _, cx| view.export_all(cx)))
+                    )
+            )
+            .when_some(status, |this, status| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(match theme {
+                            Theme::Dark => rgb(0xaaaaaa),
+                            Theme::Light => rgb(0x666666),
+                        })
+                        .child(status)
+                )
+            })
+    }
+}
+
+/// 批量导出被风控时暂停的时间
+const EXPORT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// 弹幕栏最多渲染的条数，避免热门视频上万条弹幕拖慢界面
+const MAX_DANMAKU_ROWS: usize = 1000;
+
+/// 把播放量等数字格式化为 “1.2万” 的形式
+fn format_count(n: i64) -> String {
+    if n >= 100_000_000 {
+        format!("{:.1}亿", n as f64 / 100_000_000.0)
+    } else if n >= 10_000 {
+        format!("{:.1}万", n as f64 / 10_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+/// 把秒数格式化为 `mm:ss` 或 `h:mm:ss`
+fn format_duration(seconds: i64) -> String {
+    let (h, m, s) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
     }
 }
 
@@ -47,11 +685,11 @@ impl Render for VideoListView {
                     .child(self.render_subtitle_panel(theme, cx))
             )
             .child(
-                // 右侧：AI内容 - 可调整大小
+                // 右侧：AI 分析 / 评论 - 可调整大小
                 resizable_panel()
                     .size(px(320.0))  // 初始宽度
                     .size_range(px(200.0)..px(600.0))  // 最小200px，最大600px
-                    .child(self.render_ai_panel(theme, cx))
+                    .child(self.render_side_panel(theme, cx))
             )
             )
     }
@@ -122,6 +760,7 @@ impl VideoListView {
         theme: Theme,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
+        let cover_pending = video.pic_local.is_none() && self.app_state.read(cx).is_cover_pending(&video.bvid);
         let item_bg = if is_selected {
             match theme {
                 Theme::Dark => rgb(0x2a2a2a),
@@ -155,6 +794,7 @@ impl VideoListView {
             .unwrap_or_else(|| "未知时间".to_string());
         
         let app_state = self.app_state.clone();
+        let bvid = video.bvid.clone();
         
         div()
             .w_full()
@@ -170,10 +810,11 @@ impl VideoListView {
             })
             .hover(move |style| style.bg(hover_bg))
             .cursor(CursorStyle::PointingHand)
-            .on_mouse_down(gpui::MouseButton::Left, cx.listener(move |_view, _, _, cx| {
+            .on_mouse_down(gpui::MouseButton::Left, cx.listener(move |view, _, _, cx| {
                 app_state.update(cx, |state, _| {
                     state.set_selected_video_index(Some(index));
                 });
+                view.load_video_detail(bvid.clone(), cx);
             }))
             .child(
                 // 整体布局：左右结构 - 封面在左，信息在右
@@ -194,103 +835,121 @@ impl VideoListView {
                                 Theme::Dark => rgb(0x1a1a1a),
                                 Theme::Light => rgb(0xe0e0e0),
                             })
-                            .child({
-                                // 优先使用本地缓存的封面，否则使用网络URL
-                                println!("\n========== 视频封面加载 ==========");
-                                println!("[VideoList] 视频: {}", video.title);
-                                
-                                let pic_path = if let Some(local_path) = &video.pic_local {
-                                    println!("[VideoList] ✅ 使用本地缓存: {}", local_path);
-                                    local_path.clone()
-                                } else {
-                                    println!("[VideoList] ⚠️ 无本地缓存，使用网络URL: {}", video.pic);
-                                    // 将http转为https
-                                    if video.pic.starts_with("http://") {
-                                        let https_url = video.pic.replace("http://", "https://");
-                                        println!("[VideoList] ✅ URL转换: http -> https");
-                                        https_url
-                                    } else {
-                                        video.pic.clone()
-                                    }
-                                };
-                                
-                                // 参考AnimatedAvatar的图片源创建逻辑
-                                let is_local = Path::new(&pic_path).exists();
-                                println!("[VideoList] 路径类型: {}", if is_local { "本地文件" } else { "网络URL" });
-                                
-                                let image_source: ImageSource = if is_local {
-                                    // 本地文件路径
-                                    let arc_path: Arc<Path> = Arc::from(Path::new(&pic_path));
-                                    println!("[VideoList] 🔧 创建 ImageSource (本地): {:?}", arc_path);
-                                    ImageSource::from(arc_path)
-                                } else {
-                                    // 网络URL
-                                    println!("[VideoList] 🔧 创建 ImageSource (网络): {}", pic_path);
-                                    ImageSource::from(pic_path.clone())
-                                };
-                                
-                                println!("[VideoList] ✅ ImageSource 创建完成");
-                                println!("===================================\n");
-                                
-                                // 保存theme和标题用于日志
-                                let theme_for_img = theme;
-                                let video_title_loading = video.title.clone();
-                                let video_title_fallback = video.title.clone();
-                                let pic_path_for_log = pic_path.clone();
-                                
-                                img(image_source)
-                                    .w_full()
-                                    .h_full()
-                                    .object_fit(ObjectFit::Cover)
-                                    .with_loading(move || {
-                                        // 加载中的占位符
-                                        println!("[VideoList] 📷 加载中: {}", video_title_loading);
-                                        div()
-                                            .w_full()
-                                            .h_full()
-                                            .flex()
-                                            .items_center()
-                                            .justify_center()
-                                            .bg(match theme_for_img {
-                                                Theme::Dark => rgb(0x1a1a1a),
-                                                Theme::Light => rgb(0xe0e0e0),
-                                            })
-                                            .child(
-                                                div()
-                                                    .text_xs()
-                                                    .text_color(match theme_for_img {
-                                                        Theme::Dark => rgb(0x666666),
-                                                        Theme::Light => rgb(0x999999),
-                                                    })
-                                                    .child("📷")
-                                            )
-                                            .into_any_element()
-                                    })
-                                    .with_fallback(move || {
-                                        // 加载失败的占位符
-                                        println!("[VideoList] ❌ 加载失败: {}", video_title_fallback);
-                                        println!("[VideoList] ❌ 失败的路径: {}", pic_path_for_log);
+                            .map(|this| {
+                                // 封面还在后台下载时先显示占位符，下载完成后切换到本地文件
+                                if cover_pending {
+                                    return this.child(
                                         div()
-                                            .w_full()
-                                            .h_full()
+                                            .size_full()
                                             .flex()
                                             .items_center()
                                             .justify_center()
-                                            .bg(match theme_for_img {
-                                                Theme::Dark => rgb(0x1a1a1a),
-                                                Theme::Light => rgb(0xe0e0e0),
+                                            .text_xs()
+                                            .text_color(match theme {
+                                                Theme::Dark => rgb(0x666666),
+                                                Theme::Light => rgb(0x999999),
                                             })
-                                            .child(
-                                                div()
-                                                    .text_xs()
-                                                    .text_color(match theme_for_img {
-                                                        Theme::Dark => rgb(0x666666),
-                                                        Theme::Light => rgb(0x999999),
-                                                    })
-                                                    .child("🖼️")
-                                            )
-                                            .into_any_element()
-                                    })
+                                            .child("📷")
+                                    );
+                                }
+                                this.child({
+                                    // 优先使用本地缓存的封面，下载失败时使用网络URL
+                                    trace!("========== 视频封面加载 ==========");
+                                    trace!("视频: {}", video.title);
+                                    
+                                    let pic_path = if let Some(local_path) = &video.pic_local {
+                                        trace!("✅ 使用本地缓存: {}", local_path);
+                                        local_path.clone()
+                                    } else {
+                                        trace!("⚠️ 无本地缓存，使用网络URL: {}", video.pic);
+                                        // 将http转为https
+                                        if video.pic.starts_with("http://") {
+                                            let https_url = video.pic.replace("http://", "https://");
+                                            trace!("✅ URL转换: http -> https");
+                                            https_url
+                                        } else {
+                                            video.pic.clone()
+                                        }
+                                    };
+                                    
+                                    // 参考AnimatedAvatar的图片源创建逻辑
+                                    let is_local = Path::new(&pic_path).exists();
+                                    trace!("路径类型: {}", if is_local { "本地文件" } else { "网络URL" });
+                                    
+                                    let image_source: ImageSource = if is_local {
+                                        // 本地文件路径
+                                        let arc_path: Arc<Path> = Arc::from(Path::new(&pic_path));
+                                        trace!("🔧 创建 ImageSource (本地): {:?}", arc_path);
+                                        ImageSource::from(arc_path)
+                                    } else {
+                                        // 网络URL
+                                        trace!("🔧 创建 ImageSource (网络): {}", pic_path);
+                                        ImageSource::from(pic_path.clone())
+                                    };
+                                    
+                                    trace!("✅ ImageSource 创建完成");
+                                    trace!("===================================");
+                                    
+                                    // 保存theme和标题用于日志
+                                    let theme_for_img = theme;
+                                    let video_title_loading = video.title.clone();
+                                    let video_title_fallback = video.title.clone();
+                                    let pic_path_for_log = pic_path.clone();
+                                    
+                                    img(image_source)
+                                        .w_full()
+                                        .h_full()
+                                        .object_fit(ObjectFit::Cover)
+                                        .with_loading(move || {
+                                            // 加载中的占位符
+                                            trace!("📷 加载中: {}", video_title_loading);
+                                            div()
+                                                .w_full()
+                                                .h_full()
+                                                .flex()
+                                                .items_center()
+                                                .justify_center()
+                                                .bg(match theme_for_img {
+                                                    Theme::Dark => rgb(0x1a1a1a),
+                                                    Theme::Light => rgb(0xe0e0e0),
+                                                })
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(match theme_for_img {
+                                                            Theme::Dark => rgb(0x666666),
+                                                            Theme::Light => rgb(0x999999),
+                                                        })
+                                                        .child("📷")
+                                                )
+                                                .into_any_element()
+                                        })
+                                        .with_fallback(move || {
+                                            // 加载失败的占位符
+                                            debug!("❌ 加载失败: {}", video_title_fallback);
+                                            debug!("❌ 失败的路径: {}", pic_path_for_log);
+                                            div()
+                                                .w_full()
+                                                .h_full()
+                                                .flex()
+                                                .items_center()
+                                                .justify_center()
+                                                .bg(match theme_for_img {
+                                                    Theme::Dark => rgb(0x1a1a1a),
+                                                    Theme::Light => rgb(0xe0e0e0),
+                                                })
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(match theme_for_img {
+                                                            Theme::Dark => rgb(0x666666),
+                                                            Theme::Light => rgb(0x999999),
+                                                        })
+                                                        .child("🖼️")
+                                                )
+                                                .into_any_element()
+                                        })
+                                })
                             })
                     )
                     .child(
@@ -342,7 +1001,17 @@ impl VideoListView {
             )
     }
     
-    fn render_subtitle_panel(&self, theme: Theme, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render_subtitle_panel(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let selected = state.selected_video().cloned();
+        let detail = selected.as_ref().and_then(|v| state.video_detail(&v.bvid)).cloned();
+        let detail_loading = selected.as_ref().is_some_and(|v| state.is_detail_loading(&v.bvid));
+        let cid = state.current_cid();
+        let tracks = cid.and_then(|cid| state.subtitle_tracks(cid)).map(|t| t.to_vec());
+        let active_lan = cid.and_then(|cid| state.active_subtitle_track(cid)).map(|t| t.lan.clone());
+        let export_open = state.is_export_open();
+        let show_danmaku = state.is_danmaku_shown();
+        
         let panel_bg = match theme {
             Theme::Dark => rgb(0x000000),
             Theme::Light => rgb(0xffffff),
@@ -365,6 +1034,8 @@ impl VideoListView {
                     .h(px(48.0))
                     .flex()
                     .items_center()
+                    .justify_between()
+                    .gap_2()
                     .px_4()
                     .border_b_1()
                     .border_color(match theme {
@@ -378,31 +1049,519 @@ impl VideoListView {
                             .text_color(text_color)
                             .child("字幕内容")
                     )
+                    .child(
+                        // 字幕语言选择
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap_1()
+                            .overflow_hidden()
+                            .children(tracks.iter().flatten().enumerate().map(|(i, track)| {
+                                let lan = track.lan.clone();
+                                let label = if track.is_ai() && !track.lan_doc.contains("自动") {
+                                    format!("{} (AI)", track.lan_doc)
+                                } else {
+                                    track.lan_doc.clone()
+                                };
+                                button::Button::new(("subtitle-lan", i))
+                                    .xsmall()
+                                    .map(|b| if active_lan.as_ref() == Some(&lan) { b.primary() } else { b.ghost() })
+                                    .label(label)
+                                    .on_click(cx.listener(move |view, _, _, cx| {
+                                        view.app_state.update(cx, |s, _| s.set_subtitle_lan(lan.clone()));
+                                        view.load_subtitles(cx);
+                                    }))
+                            }))
+                            .child(
+                                button::Button::new("subtitle-danmaku")
+                                    .xsmall()
+                                    .map(|b| if show_danmaku { b.primary() } else { b.outline() })
+                                    .label("弹幕")
+                                    .on_click(cx.listener(|view, _, _, cx| {
+                                        view.app_state.update(cx, |s, _| s.toggle_danmaku_shown());
+                                        view.load_danmaku(cx);
+                                    }))
+                            )
+                            .child(
+                                button::Button::new("subtitle-export")
+                                    .xsmall()
+                                    .map(|b| if export_open { b.primary() } else { b.outline() })
+                                    .label("导出")
+                                    .on_click(cx.listener(|view, _, _, cx| {
+                                        view.app_state.update(cx, |s, _| s.toggle_export_open());
+                                    }))
+                            )
+                    )
             )
+            .when(export_open, |this| this.child(self.render_export_bar(theme, cx)))
+            .when_some(self.render_danmaku_timeline(theme, cx), |this, timeline| this.child(timeline))
             .child(
-                // 内容区域
+                // 内容区域：字幕和弹幕左右并排
                 div()
                     .flex_1()
-                    .p_4()
+                    .min_h_0()
+                    .flex()
+                    .flex_row()
                     .text_color(match theme {
                         Theme::Dark => rgb(0xaaaaaa),
                         Theme::Light => rgb(0x666666),
                     })
-                    .child("选择视频查看字幕内容")
+                    .child(
+                        // 字幕区域自己管理滚动，点击高能时刻时滚动到对应字幕
+                        div()
+                            .relative()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .child(
+                                div()
+                                    .id("subtitle-scroll")
+                                    .size_full()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.subtitle_scroll)
+                                    .flex()
+                                    .flex_col()
+                                    .gap_1()
+                                    .p_4()
+                                    .map(|this| match (&selected, detail) {
+                                        (None, _) => this.child("选择视频查看字幕内容"),
+                                        (Some(_), Some(detail)) => this
+                                            .child(
+                                                div()
+                                                    .pb_3()
+                                                    .mb_1()
+                                                    .border_b_1()
+                                                    .border_color(match theme {
+                                                        Theme::Dark => rgb(0x1a1a1a),
+                                                        Theme::Light => rgb(0xeeeeee),
+                                                    })
+                                                    .child(self.render_video_detail(detail, cid, theme, cx))
+                                            )
+                                            .children(self.render_subtitle_lines(theme, cx)),
+                                        (Some(_), None) if detail_loading => this.child("正在加载视频详情..."),
+                                        (Some(_), None) => this.child("视频详情加载失败，请重新选择视频"),
+                                    })
+                            )
+                            .child(
+                                div()
+                                    .absolute()
+                                    .top_0()
+                                    .left_0()
+                                    .right_0()
+                                    .bottom_0()
+                                    .child(Scrollbar::vertical(&self.subtitle_scrollbar, &self.subtitle_scroll))
+                            )
+                    )
+                    .when(show_danmaku && selected.is_some(), |this| {
+                        this.child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .h_full()
+                                .p_4()
+                                .border_l_1()
+                                .border_color(match theme {
+                                    Theme::Dark => rgb(0x1a1a1a),
+                                    Theme::Light => rgb(0xeeeeee),
+                                })
+                                .child(self.render_danmaku_list(theme, cx))
+                                .scrollable(ScrollbarAxis::Vertical)
+                        )
+                    })
             )
     }
     
-    fn render_ai_panel(&self, theme: Theme, _cx: &mut Context<Self>) -> impl IntoElement {
-        let panel_bg = match theme {
-            Theme::Dark => rgb(0x0d0d0d),
-            Theme::Light => rgb(0xf5f5f5),
+    /// 弹幕密度时间轴：每段一根柱子，高能时刻标红，点击后字幕滚动到对应时间
+    fn render_danmaku_timeline(&self, theme: Theme, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let state = self.app_state.read(cx);
+        let timeline = state.danmaku_timeline(state.current_cid()?)?.clone();
+        if timeline.buckets.is_empty() {
+            return None;
+        }
+        let max = timeline.buckets.iter().copied().max().unwrap_or(0).max(1);
+        let bucket_seconds = timeline.bucket_seconds;
+        
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        
+        let bar_color = match theme {
+            Theme::Dark => rgb(0x3a3a3a),
+            Theme::Light => rgb(0xcccccc),
+        };
+        
+        let chip_bg = match theme {
+            Theme::Dark => rgb(0x1a1a1a),
+            Theme::Light => rgb(0xeeeeee),
+        };
+        
+        Some(
+            div()
+                .w_full()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .px_4()
+                .py_2()
+                .border_b_1()
+                .border_color(match theme {
+                    Theme::Dark => rgb(0x2a2a2a),
+                    Theme::Light => rgb(0xe0e0e0),
+                })
+                .text_xs()
+                .text_color(secondary_color)
+                .child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .justify_between()
+                        .child(format!("弹幕密度（高能时刻 {} 处）", timeline.highlights.len()))
+                        .child(format!("每格 {} 秒", bucket_seconds))
+                )
+                .child(
+                    div()
+                        .w_full()
+                        .h(px(48.0))
+                        .flex()
+                        .flex_row()
+                        .items_end()
+                        .gap(px(1.0))
+                        .children(timeline.buckets.iter().enumerate().map(|(i, &count)| {
+                            let highlighted = timeline.is_highlighted(i);
+                            let time = i as f64 * bucket_seconds;
+                            div()
+                                .flex_1()
+                                .min_h(px(1.0))
+                                .h(relative(count as f32 / max as f32))
+                                .bg(if highlighted { rgb(0xff6b6b) } else { bar_color })
+                                .when(highlighted, |this| {
+                                    this.cursor(CursorStyle::PointingHand)
+                                        .on_mouse_down(gpui::MouseButton::Left, cx.listener(move |view, _, _, cx| {
+                                            view.scroll_subtitles_to(time, cx);
+                                        }))
+                                })
+                        }))
+                )
+                .when(!timeline.highlights.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(timeline.highlights.iter().map(|highlight| {
+                                let start = highlight.start;
+                                let phrases = highlight
+                                    .phrases
+                                    .iter()
+                                    .map(|(text, n)| format!("{}×{}", text, n))
+                                    .collect::<Vec<_>>()
+                                    .join(" / ");
+                                div()
+                                    .px_2()
+                                    .py_0p5()
+                                    .rounded_sm()
+                                    .bg(chip_bg)
+                                    .cursor(CursorStyle::PointingHand)
+                                    .child(if phrases.is_empty() {
+                                        format!("{} · {} 条", format_duration(highlight.peak as i64), highlight.count)
+                                    } else {
+                                        format!("{} · {} 条 · {}", format_duration(highlight.peak as i64), highlight.count, phrases)
+                                    })
+                                    .on_mouse_down(gpui::MouseButton::Left, cx.listener(move |view, _, _, cx| {
+                                        view.scroll_subtitles_to(start, cx);
+                                    }))
+                            }))
+                    )
+                }),
+        )
+    }
+    
+    /// 当前分P、当前语言的字幕，每行一个元素（方便按行号滚动）；没有字幕时显示空状态
+    fn render_subtitle_lines(&self, theme: Theme, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let state = self.app_state.read(cx);
+        let logged_in = state.is_logged_in();
+        let cid = state.current_cid();
+        let loading = cid.is_some_and(|cid| state.is_subtitle_loading(cid));
+        let tracks_empty = cid.and_then(|cid| state.subtitle_tracks(cid)).map(|t| t.is_empty());
+        let lines: Option<Vec<SubtitleLine>> = cid.and_then(|cid| {
+            let track = state.active_subtitle_track(cid)?;
+            state.subtitle_lines(cid, &track.lan).map(|l| l.to_vec())
+        });
+        
+        let focused = self.focused_line.filter(|(focused_cid, _)| Some(*focused_cid) == cid).map(|(_, i)| i);
+        
+        let text_color = match theme {
+            Theme::Dark => rgb(0xdddddd),
+            Theme::Light => rgb(0x333333),
+        };
+        
+        let time_color = match theme {
+            Theme::Dark => rgb(0x666666),
+            Theme::Light => rgb(0x999999),
+        };
+        
+        let focus_bg = match theme {
+            Theme::Dark => rgb(0x2a1a1a),
+            Theme::Light => rgb(0xffeeee),
+        };
+        
+        let empty_state = |message: &'static str| {
+            div()
+                .w_full()
+                .py_8()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_2()
+                .child(message)
+                .when(!logged_in, |this| {
+                    this.child(div().text_xs().child("登录后可获取 AI 自动生成的字幕"))
+                })
+        };
+        
+        match (lines, tracks_empty) {
+            (Some(lines), _) if !lines.is_empty() => lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_3()
+                        .text_sm()
+                        .when(focused == Some(i), |this| this.bg(focus_bg).rounded_sm())
+                        .child(
+                            div()
+                                .w(px(64.0))
+                                .flex_shrink_0()
+                                .text_color(time_color)
+                                .child(format_duration(line.from as i64))
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_color(text_color)
+                                .line_height(relative(1.5))
+                                .child(line.content)
+                        )
+                        .into_any_element()
+                })
+                .collect(),
+            (Some(_), _) => vec![empty_state("字幕内容为空").into_any_element()],
+            (None, _) if loading => vec!["正在加载字幕...".into_any_element()],
+            (None, Some(true)) => vec![empty_state("该视频没有字幕").into_any_element()],
+            (None, _) => vec![empty_state("字幕加载失败，请重新选择视频").into_any_element()],
+        }
+    }
+    
+    /// 当前分P的弹幕，按出现时间排列，颜色点为弹幕颜色
+    fn render_danmaku_list(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let cid = state.current_cid();
+        let loading = cid.is_some_and(|cid| state.is_danmaku_loading(cid));
+        let list: Option<Vec<Danmaku>> = cid
+            .and_then(|cid| state.danmaku(cid))
+            .map(|d| d.iter().take(MAX_DANMAKU_ROWS).cloned().collect());
+        let total = cid.and_then(|cid| state.danmaku(cid)).map_or(0, |d| d.len());
+        
+        let text_color = match theme {
+            Theme::Dark => rgb(0xdddddd),
+            Theme::Light => rgb(0x333333),
         };
         
+        let time_color = match theme {
+            Theme::Dark => rgb(0x666666),
+            Theme::Light => rgb(0x999999),
+        };
+        
+        div()
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .map(|this| match list {
+                Some(list) if !list.is_empty() => this
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(time_color)
+                            .child(if total > MAX_DANMAKU_ROWS {
+                                format!("弹幕 {} 条（显示前 {} 条）", total, MAX_DANMAKU_ROWS)
+                            } else {
+                                format!("弹幕 {} 条", total)
+                            })
+                    )
+                    .children(list.into_iter().map(|danmaku| {
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap_2()
+                            .text_sm()
+                            .child(
+                                div()
+                                    .w(px(56.0))
+                                    .flex_shrink_0()
+                                    .text_color(time_color)
+                                    .child(format_duration(danmaku.time as i64))
+                            )
+                            .child(
+                                div()
+                                    .size(px(8.0))
+                                    .flex_shrink_0()
+                                    .rounded_full()
+                                    .border_1()
+                                    .border_color(time_color)
+                                    .bg(rgb(danmaku.color & 0xffffff))
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_color(text_color)
+                                    .child(danmaku.text)
+                            )
+                    })),
+                Some(_) => this.child("没有弹幕"),
+                None if loading => this.child("正在加载弹幕..."),
+                None => this.child("弹幕加载失败，请重新选择视频"),
+            })
+    }
+    
+    /// 视频详情：UP 主、统计数据、标签、简介和分P列表
+    fn render_video_detail(
+        &self,
+        detail: VideoDetail,
+        current_cid: Option<i64>,
+        theme: Theme,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
         let text_color = match theme {
             Theme::Dark => rgb(0xffffff),
             Theme::Light => rgb(0x333333),
         };
         
+        let secondary_color = match theme {
+            Theme::Dark => rgb(0xaaaaaa),
+            Theme::Light => rgb(0x666666),
+        };
+        
+        let tag_bg = match theme {
+            Theme::Dark => rgb(0x1a1a1a),
+            Theme::Light => rgb(0xeeeeee),
+        };
+        
+        let stat = &detail.stat;
+        let stats = [
+            ("播放", stat.view),
+            ("点赞", stat.like.unwrap_or(0)),
+            ("投币", stat.coin.unwrap_or(0)),
+            ("收藏", stat.favorite.unwrap_or(0)),
+            ("弹幕", stat.danmaku.unwrap_or(0)),
+            ("评论", stat.reply.unwrap_or(0)),
+            ("分享", stat.share.unwrap_or(0)),
+        ];
+        
+        div()
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(text_color)
+                    .child(detail.title.clone())
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(secondary_color)
+                    .child(format!("UP主: {} (UID {})", detail.owner.name, detail.owner.mid))
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .gap_3()
+                    .text_xs()
+                    .text_color(secondary_color)
+                    .children(stats.into_iter().map(|(label, n)| format!("{} {}", label, format_count(n))))
+            )
+            .when(!detail.tags.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .flex_wrap()
+                        .gap_2()
+                        .children(detail.tags.iter().map(|tag| {
+                            div()
+                                .px_2()
+                                .py_0p5()
+                                .rounded_sm()
+                                .bg(tag_bg)
+                                .text_xs()
+                                .text_color(secondary_color)
+                                .child(tag.tag_name.clone())
+                        }))
+                )
+            })
+            .when(!detail.desc.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(text_color)
+                        .line_height(relative(1.6))
+                        .child(detail.desc.clone())
+                )
+            })
+            .when(detail.pages.len() > 1, |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .text_sm()
+                        .child(
+                            div()
+                                .font_weight(FontWeight::MEDIUM)
+                                .text_color(text_color)
+                                .child(format!("分P ({})", detail.pages.len()))
+                        )
+                        .children(detail.pages.iter().map(|page| {
+                            let cid = page.cid;
+                            div()
+                                .cursor(CursorStyle::PointingHand)
+                                .text_color(if current_cid == Some(cid) { text_color } else { secondary_color })
+                                .when(current_cid == Some(cid), |this| this.font_weight(FontWeight::MEDIUM))
+                                .child(format!("P{} {} [{}]", page.page, page.part, format_duration(page.duration)))
+                                .on_mouse_down(gpui::MouseButton::Left, cx.listener(move |view, _, _, cx| {
+                                    // 切换分P后加载该分P的字幕和弹幕
+                                    view.app_state.update(cx, |s, _| s.set_selected_cid(Some(cid)));
+                                    view.load_current_part(cx);
+                                }))
+                        }))
+                )
+            })
+    }
+    
+    fn render_side_panel(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let tab = state.side_tab();
+        let reply_count = state
+            .selected_video()
+            .and_then(|v| state.video_detail(&v.bvid))
+            .and_then(|d| d.stat.reply);
+        
+        let panel_bg = match theme {
+            Theme::Dark => rgb(0x0d0d0d),
+            Theme::Light => rgb(0xf5f5f5),
+        };
+        
         div()
             .size_full() // resizable_panel 会自动管理宽度
             .flex()
@@ -415,6 +1574,7 @@ impl VideoListView {
                     .h(px(48.0))
                     .flex()
                     .items_center()
+                    .gap_1()
                     .px_4()
                     .border_b_1()
                     .border_color(match theme {
@@ -422,11 +1582,27 @@ impl VideoListView {
                         Theme::Light => rgb(0xe0e0e0),
                     })
                     .child(
-                        div()
-                            .text_base()
-                            .font_weight(FontWeight::SEMIBOLD)
-                            .text_color(text_color)
-                            .child("AI 分析")
+                        button::Button::new("side-tab-ai")
+                            .small()
+                            .map(|b| if tab == SideTab::Ai { b.primary() } else { b.ghost() })
+                            .label("AI 分析")
+                            .on_click(cx.listener(|view, _, _, cx| {
+                                view.app_state.update(cx, |s, _| s.set_side_tab(SideTab::Ai));
+                                view.load_comments(false, cx);
+                            }))
+                    )
+                    .child(
+                        button::Button::new("side-tab-comments")
+                            .small()
+                            .map(|b| if tab == SideTab::Comments { b.primary() } else { b.ghost() })
+                            .label(match reply_count {
+                                Some(n) => format!("评论 {}", format_count(n)),
+                                None => "评论".to_string(),
+                            })
+                            .on_click(cx.listener(|view, _, _, cx| {
+                                view.app_state.update(cx, |s, _| s.set_side_tab(SideTab::Comments));
+                                view.load_comments(false, cx);
+                            }))
                     )
             )
             .child(
@@ -438,7 +1614,291 @@ impl VideoListView {
                         Theme::Dark => rgb(0xaaaaaa),
                         Theme::Light => rgb(0x666666),
                     })
-                    .child("AI 内容功能开发中...")
+                    .map(|this| match tab {
+                        SideTab::Ai => this.child(self.render_ai(theme, cx)),
+                        SideTab::Comments => this.child(self.render_comments(theme, cx)),
+                    })
+                    .scrollable(ScrollbarAxis::Vertical)
+            )
+    }
+    
+    /// AI 分析：根据已加载的评论统计获赞最多的评论、重复最多的短评论和常用表情，加载更多评论后统计会更准确
+    fn render_ai(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let sort = state.reply_sort();
+        let detail = state.selected_video().and_then(|v| state.video_detail(&v.bvid));
+        let reply_count = detail.and_then(|d| d.stat.reply);
+        let thread = detail.and_then(|d| state.comment_thread(d.aid, sort)).cloned();
+        
+        let chip_bg = match theme {
+            Theme::Dark => rgb(0x1a1a1a),
+            Theme::Light => rgb(0xe8e8e8),
+        };
+        let section = |title: &'static str| {
+            div()
+                .text_sm()
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(title)
+        };
+        let chips = |items: &[(String, usize)]| {
+            div()
+                .flex()
+                .flex_row()
+                .flex_wrap()
+                .gap_2()
+                .text_xs()
+                .children(items.iter().map(|(text, n)| {
+                    div()
+                        .px_2()
+                        .py_0p5()
+                        .rounded_sm()
+                        .bg(chip_bg)
+                        .child(format!("{}×{}", text, n))
+                }))
+        };
+        
+        let Some(thread) = thread else {
+            return div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(if detail.is_some() { "正在加载评论..." } else { "选择视频查看评论分析" });
+        };
+        
+        let digest = CommentDigest::build(thread.top_replies.iter().chain(&thread.replies));
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(div().text_xs().child(match reply_count {
+                Some(n) => format!("根据已加载的 {} 条评论（共 {} 条），来自 {} 位用户", digest.total, format_count(n), digest.users),
+                None => format!("根据已加载的 {} 条评论，来自 {} 位用户", digest.total, digest.users),
+            }))
+            .when(!digest.top_liked.is_empty(), |this| {
+                this.child(section("获赞最多"))
+                    .children(digest.top_liked.iter().map(|reply| reply_body(reply, false, theme)))
+            })
+            .when(!digest.phrases.is_empty(), |this| {
+                this.child(section("刷屏评论")).child(chips(&digest.phrases))
+            })
+            .when(!digest.emotes.is_empty(), |this| {
+                this.child(section("常用表情")).child(chips(&digest.emotes))
+            })
+            .map(|this| {
+                if thread.loading {
+                    this.child("正在加载评论...")
+                } else if thread.failed {
+                    this.child(
+                        button::Button::new("ai-comments-retry")
+                            .small()
+                            .outline()
+                            .label("评论加载失败，点击重试")
+                            .on_click(cx.listener(|view, _, _, cx| view.load_comments(true, cx)))
+                    )
+                } else if !thread.is_end {
+                    this.child(
+                        button::Button::new("ai-comments-more")
+                            .small()
+                            .outline()
+                            .label("加载更多评论用于分析")
+                            .on_click(cx.listener(|view, _, _, cx| view.load_comments(true, cx)))
+                    )
+                } else if digest.total == 0 {
+                    this.child("还没有评论")
+                } else {
+                    this
+                }
+            })
+    }
+    
+    /// 当前视频的评论：排序切换、置顶评论、一级评论和楼中楼，底部加载更多
+    fn render_comments(&self, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.app_state.read(cx);
+        let sort = state.reply_sort();
+        let aid = state.selected_video().and_then(|v| state.video_detail(&v.bvid)).map(|d| d.aid);
+        let thread = aid.and_then(|aid| state.comment_thread(aid, sort)).cloned();
+        
+        let header = div()
+            .flex()
+            .flex_row()
+            .gap_1()
+            .children([ReplySort::Hot, ReplySort::Time].into_iter().enumerate().map(|(i, option)| {
+                button::Button::new(("reply-sort", i))
+                    .xsmall()
+                    .map(|b| if option == sort { b.primary() } else { b.ghost() })
+                    .label(option.label())
+                    .on_click(cx.listener(move |view, _, _, cx| {
+                        view.app_state.update(cx, |s, _| s.set_reply_sort(option));
+                        view.load_comments(false, cx);
+                    }))
+            }));
+        
+        let (Some(aid), Some(thread)) = (aid, thread) else {
+            return div()
+                .flex()
+                .flex_col()
+                .gap_3()
+                .child(header)
+                .child(if aid.is_some() { "正在加载评论..." } else { "选择视频查看评论" });
+        };
+        
+        let top_count = thread.top_replies.len();
+        let empty = top_count == 0 && thread.replies.is_empty();
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(header)
+            .children(
+                thread
+                    .top_replies
+                    .into_iter()
+                    .chain(thread.replies)
+                    .enumerate()
+                    .map(|(i, reply)| self.render_reply(aid, reply, i < top_count, theme, cx)),
             )
+            .map(|this| {
+                if thread.loading {
+                    this.child("正在加载评论...")
+                } else if thread.failed {
+                    this.child(
+                        button::Button::new("comments-retry")
+                            .small()
+                            .outline()
+                            .label("评论加载失败，点击重试")
+                            .on_click(cx.listener(|view, _, _, cx| view.load_comments(true, cx)))
+                    )
+                } else if !thread.is_end {
+                    this.child(
+                        button::Button::new("comments-more")
+                            .small()
+                            .outline()
+                            .label("加载更多评论")
+                            .on_click(cx.listener(|view, _, _, cx| view.load_comments(true, cx)))
+                    )
+                } else if empty {
+                    this.child("还没有评论")
+                } else {
+                    this.child(div().text_xs().child("没有更多评论了"))
+                }
+            })
+    }
+    
+    /// 一条一级评论；已展开的楼中楼显示全部已加载的回复，否则显示接口附带的前几条
+    fn render_reply(&self, oid: i64, reply: Reply, pinned: bool, theme: Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let sub_thread = self.app_state.read(cx).sub_reply_thread(reply.rpid).cloned();
+        let root = reply.rpid;
+        let rcount = reply.rcount;
+        let sub_replies = match &sub_thread {
+            Some(thread) if !thread.replies.is_empty() => thread.replies.clone(),
+            _ => reply.replies.clone().unwrap_or_default(),
+        };
+        let shown = sub_replies.len() as i64;
+        
+        let sub_bg = match theme {
+            Theme::Dark => rgb(0x161616),
+            Theme::Light => rgb(0xebebeb),
+        };
+        
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .pb_3()
+            .border_b_1()
+            .border_color(match theme {
+                Theme::Dark => rgb(0x1a1a1a),
+                Theme::Light => rgb(0xe0e0e0),
+            })
+            .child(reply_body(&reply, pinned, theme))
+            .when(!sub_replies.is_empty() || rcount > 0, |this| {
+                this.child(
+                    div()
+                        .ml_4()
+                        .p_2()
+                        .rounded_md()
+                        .bg(sub_bg)
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .children(sub_replies.iter().map(|sub| reply_body(sub, false, theme)))
+                        .map(|this| match &sub_thread {
+                            Some(thread) if thread.loading => this.child(div().text_xs().child("正在加载回复...")),
+                            Some(thread) if thread.is_end => this,
+                            _ if rcount > shown || sub_thread.is_some() => this.child(
+                                button::Button::new(("sub-replies-more", root as usize))
+                                    .xsmall()
+                                    .ghost()
+                                    .label(if sub_thread.is_some() {
+                                        "加载更多回复".to_string()
+                                    } else {
+                                        format!("共 {} 条回复，点击查看", rcount)
+                                    })
+                                    .on_click(cx.listener(move |view, _, _, cx| {
+                                        view.load_sub_replies(oid, root, cx);
+                                    }))
+                            ),
+                            _ => this,
+                        })
+                )
+            })
     }
 }
+
+/// 评论内容：昵称、时间、点赞数和正文
+fn reply_body(reply: &Reply, pinned: bool, theme: Theme) -> impl IntoElement {
+    let text_color = match theme {
+        Theme::Dark => rgb(0xdddddd),
+        Theme::Light => rgb(0x333333),
+    };
+    
+    let secondary_color = match theme {
+        Theme::Dark => rgb(0x777777),
+        Theme::Light => rgb(0x999999),
+    };
+    
+    let time = chrono::DateTime::from_timestamp(reply.ctime, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .flex_wrap()
+                .items_center()
+                .gap_2()
+                .text_xs()
+                .text_color(secondary_color)
+                .when(pinned, |this| {
+                    this.child(
+                        div()
+                            .px_1()
+                            .rounded_sm()
+                            .bg(rgb(0xff6b6b))
+                            .text_color(rgb(0xffffff))
+                            .child("置顶")
+                    )
+                })
+                .child(
+                    div()
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(text_color)
+                        .child(reply.member.uname.clone())
+                )
+                .child(time)
+                .when(reply.like > 0, |this| this.child(format!("👍 {}", format_count(reply.like))))
+        )
+        .child(
+            div()
+                .text_sm()
+                .text_color(text_color)
+                .line_height(relative(1.5))
+                .child(reply.content.message.clone())
+        )
+
+}